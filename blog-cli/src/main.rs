@@ -1,3 +1,222 @@
-fn main() {
-    println!("Hello, world!");
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use anyhow::{Context, bail};
+use blog_client::{BlogClient, Post, PostList, Transport};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(name = "blog-cli", about = "Command-line client for blog-server")]
+struct Cli {
+    /// HTTP base URL of the server
+    #[arg(long, env = "BLOG_SERVER", default_value = "http://127.0.0.1:8080")]
+    server: String,
+    /// Use gRPC at this address instead of HTTP, e.g. http://127.0.0.1:50051
+    #[arg(long, env = "BLOG_GRPC")]
+    grpc: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Recover or change an account password
+    #[command(subcommand)]
+    Password(PasswordCommand),
+    /// List posts, newest first
+    List {
+        /// Only posts in this status; drafts, scheduled and archived posts need --username
+        #[arg(long, value_parser = ["draft", "scheduled", "published", "archived"])]
+        status: Option<String>,
+        /// Only posts of the user with this id
+        #[arg(long)]
+        author: Option<i64>,
+        /// Log in as this user to see their unpublished posts
+        #[arg(long)]
+        username: Option<String>,
+        #[arg(long, default_value_t = 10)]
+        limit: i64,
+        #[arg(long, default_value_t = 0)]
+        offset: i64,
+    },
+    /// Create a post; published immediately unless --status says otherwise
+    Create {
+        #[arg(long)]
+        username: String,
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        content: String,
+        #[arg(long, value_parser = ["draft", "scheduled", "published"])]
+        status: Option<String>,
+        /// Publication time for scheduled posts, RFC 3339, e.g. 2030-01-01T09:00:00Z
+        #[arg(long)]
+        publish_at: Option<DateTime<Utc>>,
+    },
+    /// Replace the title and content of a post, optionally changing its status
+    Update {
+        id: i64,
+        #[arg(long)]
+        username: String,
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        content: String,
+        /// New status; the current one is kept if omitted
+        #[arg(long, value_parser = ["draft", "scheduled", "published", "archived"])]
+        status: Option<String>,
+        /// Publication time for scheduled posts, RFC 3339
+        #[arg(long)]
+        publish_at: Option<DateTime<Utc>>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum PasswordCommand {
+    /// Email a password reset link to the account with this address
+    Forgot {
+        #[arg(long)]
+        email: String,
+    },
+    /// Set a new password using the token from the reset email
+    Reset {
+        #[arg(long)]
+        token: String,
+    },
+    /// Change the password; signs out every other session of the account
+    Change {
+        #[arg(long)]
+        username: String,
+    },
+}
+
+// Пароли не передаются аргументами, чтобы не попасть в историю shell и список
+// процессов: берутся из переменной окружения или читаются со stdin. В терминале
+// ввод не отображается; построчное чтение остаётся только для stdin из конвейера.
+fn read_password(env: &str, prompt: &str) -> anyhow::Result<String> {
+    if let Ok(password) = std::env::var(env) {
+        return Ok(password);
+    }
+    let password = if io::stdin().is_terminal() {
+        rpassword::prompt_password(format!("{prompt}: "))?
+    } else {
+        eprint!("{prompt}: ");
+        io::stderr().flush()?;
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line)?;
+        line.trim_end_matches(['\r', '\n']).to_string()
+    };
+    if password.is_empty() {
+        bail!("{prompt} is required (or set {env})");
+    }
+    Ok(password)
+}
+
+fn read_new_password() -> anyhow::Result<String> {
+    const ENV: &str = "BLOG_NEW_PASSWORD";
+    if let Ok(password) = std::env::var(ENV) {
+        return Ok(password);
+    }
+    let password = read_password(ENV, "New password")?;
+    if read_password(ENV, "Repeat new password")? != password {
+        bail!("passwords do not match");
+    }
+    Ok(password)
+}
+
+fn print_post(post: &Post) {
+    let published = post
+        .published_at
+        .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".into());
+    println!("{:>6}  {:<9}  {published:<16}  {}", post.id, post.status, post.title);
+}
+
+fn print_posts(page: &PostList) {
+    for post in &page.posts {
+        print_post(post);
+    }
+    let shown = page.offset + page.posts.len() as i64;
+    println!("{shown} of {} posts", page.total);
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let transport = match cli.grpc {
+        Some(addr) => Transport::Grpc(addr),
+        None => Transport::Http(cli.server),
+    };
+    let mut client = BlogClient::new(transport)
+        .await
+        .context("failed to connect to the server")?;
+
+    match cli.command {
+        Command::Password(PasswordCommand::Forgot { email }) => {
+            client.forgot_password(&email).await?;
+            println!("If {email} belongs to an account, a reset link has been sent to it.");
+        }
+        Command::Password(PasswordCommand::Reset { token }) => {
+            let new_password = read_new_password()?;
+            client.reset_password(&token, &new_password).await?;
+            println!(
+                "Password has been reset. All sessions were signed out; log in with the new password."
+            );
+        }
+        Command::Password(PasswordCommand::Change { username }) => {
+            let current = read_password("BLOG_PASSWORD", "Current password")?;
+            client.login(&username, &current).await?;
+            let new_password = read_new_password()?;
+            client.change_password(&current, &new_password).await?;
+            println!("Password changed. Other sessions were signed out.");
+        }
+        Command::List {
+            status,
+            author,
+            username,
+            limit,
+            offset,
+        } => {
+            if let Some(username) = username {
+                let password = read_password("BLOG_PASSWORD", "Password")?;
+                client.login(&username, &password).await?;
+            }
+            let status = status.as_deref();
+            let page = match author {
+                Some(author) => client.list_user_posts(author, status, limit, offset).await?,
+                None => client.list_posts(status, limit, offset).await?,
+            };
+            print_posts(&page);
+        }
+        Command::Create {
+            username,
+            title,
+            content,
+            status,
+            publish_at,
+        } => {
+            let password = read_password("BLOG_PASSWORD", "Password")?;
+            client.login(&username, &password).await?;
+            let post = client
+                .create_post(&title, &content, status.as_deref(), publish_at)
+                .await?;
+            print_post(&post);
+        }
+        Command::Update {
+            id,
+            username,
+            title,
+            content,
+            status,
+            publish_at,
+        } => {
+            let password = read_password("BLOG_PASSWORD", "Password")?;
+            client.login(&username, &password).await?;
+            let post = client
+                .update_post(id, &title, &content, status.as_deref(), publish_at)
+                .await?;
+            print_post(&post);
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use thiserror::Error;
+
+// Ошибка отдельного поля запроса: `errors` в problem+json или blog.BadRequest в gRPC
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Error, Debug)]
+pub enum BlogClientError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("gRPC error: {0}")]
+    Grpc(tonic::Status),
+    #[error("Transport error: {0}")]
+    Transport(#[from] tonic::transport::Error),
+    #[error("Not found")]
+    NotFound,
+    #[error("Unauthorized")]
+    Unauthorized,
+    #[error("Forbidden")]
+    Forbidden,
+    // Ошибка сервера со стабильным кодом (`code` из problem+json или `x-error-code` в gRPC)
+    #[error("{detail} ({code})")]
+    Api { code: String, detail: String },
+    // Запрос не прошёл проверку; `fields` перечисляет ошибки по полям
+    #[error("{detail}")]
+    Validation { detail: String, fields: Vec<FieldError> },
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("Server error: {0}")]
+    Server(String),
+}
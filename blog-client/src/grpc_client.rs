@@ -0,0 +1,505 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use prost::Message;
+use tonic::metadata::{AsciiMetadataKey, MetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Request, Status};
+
+use crate::error::{BlogClientError, FieldError};
+use crate::trace_context;
+use crate::{AuthResponse, Author, Post, PostCursorPage, PostList, Profile, User};
+
+pub mod proto {
+    tonic::include_proto!("blog");
+}
+
+use proto::blog_service_client::BlogServiceClient;
+use proto::list_posts_request::Pagination;
+
+impl From<Status> for BlogClientError {
+    fn from(status: Status) -> Self {
+        let code = status
+            .metadata()
+            .get("x-error-code")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let fields = field_errors(&status);
+        match (status.code(), code) {
+            (Code::NotFound, _) => BlogClientError::NotFound,
+            (Code::Unauthenticated, _) => BlogClientError::Unauthorized,
+            (Code::PermissionDenied, _) => BlogClientError::Forbidden,
+            (Code::InvalidArgument, _) if !fields.is_empty() => BlogClientError::Validation {
+                detail: status.message().to_string(),
+                fields,
+            },
+            (_, Some(code)) => BlogClientError::Api {
+                code,
+                detail: status.message().to_string(),
+            },
+            (Code::InvalidArgument | Code::AlreadyExists, None) => {
+                BlogClientError::InvalidRequest(status.message().to_string())
+            }
+            _ => BlogClientError::Grpc(status),
+        }
+    }
+}
+
+// Ошибки по полям сервер передаёт в details статуса как blog.BadRequest.
+fn field_errors(status: &Status) -> Vec<FieldError> {
+    proto::BadRequest::decode(status.details())
+        .map(|details| {
+            details
+                .field_violations
+                .into_iter()
+                .map(|v| FieldError {
+                    field: v.field,
+                    code: v.code,
+                    message: v.description,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn datetime(ts: Option<prost_types::Timestamp>) -> DateTime<Utc> {
+    ts.and_then(|ts| DateTime::from_timestamp(ts.seconds, ts.nanos as u32))
+        .unwrap_or_default()
+}
+
+fn timestamp(value: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: value.timestamp(),
+        nanos: value.timestamp_subsec_nanos() as i32,
+    }
+}
+
+impl From<proto::Post> for Post {
+    fn from(post: proto::Post) -> Self {
+        Post {
+            id: post.id,
+            title: post.title,
+            content: post.content,
+            author_id: post.author_id,
+            created_at: datetime(post.created_at),
+            updated_at: datetime(post.updated_at),
+            hidden_at: post.hidden_at.map(|ts| datetime(Some(ts))),
+            author: post.author.map(Into::into),
+            status: post.status,
+            published_at: post.published_at.map(|ts| datetime(Some(ts))),
+        }
+    }
+}
+
+impl From<proto::Author> for Author {
+    fn from(author: proto::Author) -> Self {
+        Author {
+            id: author.id,
+            username: author.username,
+            display_name: author.display_name,
+            avatar_url: author.avatar_url,
+        }
+    }
+}
+
+impl From<proto::Profile> for Profile {
+    fn from(profile: proto::Profile) -> Self {
+        Profile {
+            id: profile.id,
+            username: profile.username,
+            display_name: profile.display_name,
+            bio: profile.bio,
+            avatar_url: profile.avatar_url,
+            joined_at: datetime(profile.joined_at),
+            post_count: profile.post_count,
+        }
+    }
+}
+
+impl From<proto::User> for User {
+    fn from(user: proto::User) -> Self {
+        User {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            created_at: datetime(user.created_at),
+            email_verified_at: user
+                .email_verified_at
+                .map(|ts| datetime(Some(ts))),
+            role: user.role,
+            suspended_at: user.suspended_at.map(|ts| datetime(Some(ts))),
+            display_name: user.display_name,
+            bio: user.bio,
+            avatar_url: user.avatar_url,
+        }
+    }
+}
+
+impl TryFrom<proto::AuthResponse> for AuthResponse {
+    type Error = BlogClientError;
+
+    fn try_from(res: proto::AuthResponse) -> Result<Self, Self::Error> {
+        let user = res
+            .user
+            .ok_or_else(|| BlogClientError::Server("auth response without user".into()))?;
+        Ok(AuthResponse {
+            token: res.token,
+            refresh_token: res.refresh_token,
+            expires_in: res.expires_in,
+            user: user.into(),
+        })
+    }
+}
+
+fn profile_from(res: proto::ProfileResponse) -> Result<Profile, BlogClientError> {
+    res.profile
+        .map(Into::into)
+        .ok_or_else(|| BlogClientError::Server("profile response without profile".into()))
+}
+
+fn post_from(res: proto::PostResponse) -> Result<Post, BlogClientError> {
+    res.post
+        .map(Into::into)
+        .ok_or_else(|| BlogClientError::Server("post response without post".into()))
+}
+
+type TraceInterceptor = fn(Request<()>) -> Result<Request<()>, Status>;
+
+// Добавляет в metadata каждого вызова W3C traceparent текущего контекста.
+fn inject_trace_context(mut request: Request<()>) -> Result<Request<()>, Status> {
+    for (name, value) in trace_context::current() {
+        if let (Ok(key), Ok(value)) = (AsciiMetadataKey::from_str(&name), value.parse()) {
+            request.metadata_mut().insert(key, value);
+        }
+    }
+    Ok(request)
+}
+
+#[derive(Clone)]
+pub struct GrpcClient {
+    client: BlogServiceClient<InterceptedService<Channel, TraceInterceptor>>,
+}
+
+impl GrpcClient {
+    pub async fn connect(addr: impl Into<String>) -> Result<Self, BlogClientError> {
+        let channel = Endpoint::new(addr.into())?.connect().await?;
+        let client = BlogServiceClient::with_interceptor(
+            channel,
+            inject_trace_context as TraceInterceptor,
+        );
+        Ok(Self { client })
+    }
+
+    fn authorized<T>(message: T, token: Option<&str>) -> Result<Request<T>, BlogClientError> {
+        let token = token.ok_or(BlogClientError::Unauthorized)?;
+        let mut request = Request::new(message);
+        let value = MetadataValue::try_from(format!("Bearer {token}"))
+            .map_err(|_| BlogClientError::InvalidRequest("malformed token".into()))?;
+        request.metadata_mut().insert("authorization", value);
+        Ok(request)
+    }
+
+    // Для публичных методов, где токен меняет только набор видимых данных.
+    fn optionally_authorized<T>(message: T, token: Option<&str>) -> Result<Request<T>, BlogClientError> {
+        match token {
+            Some(_) => Self::authorized(message, token),
+            None => Ok(Request::new(message)),
+        }
+    }
+
+    pub async fn register(
+        &self,
+        username: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<AuthResponse, BlogClientError> {
+        let res = self
+            .client
+            .clone()
+            .register(proto::RegisterRequest {
+                username: username.to_string(),
+                email: email.to_string(),
+                password: password.to_string(),
+            })
+            .await?;
+        res.into_inner().try_into()
+    }
+
+    pub async fn login(&self, username: &str, password: &str) -> Result<AuthResponse, BlogClientError> {
+        let res = self
+            .client
+            .clone()
+            .login(proto::LoginRequest {
+                username: username.to_string(),
+                password: password.to_string(),
+            })
+            .await?;
+        res.into_inner().try_into()
+    }
+
+    pub async fn refresh(&self, refresh_token: &str) -> Result<AuthResponse, BlogClientError> {
+        let res = self
+            .client
+            .clone()
+            .refresh(proto::RefreshRequest {
+                refresh_token: refresh_token.to_string(),
+            })
+            .await?;
+        res.into_inner().try_into()
+    }
+
+    pub async fn logout(
+        &self,
+        token: Option<&str>,
+        refresh_token: Option<&str>,
+    ) -> Result<(), BlogClientError> {
+        let request = Self::authorized(
+            proto::LogoutRequest {
+                refresh_token: refresh_token.unwrap_or_default().to_string(),
+            },
+            token,
+        )?;
+        self.client.clone().logout(request).await?;
+        Ok(())
+    }
+
+    pub async fn logout_all(&self, token: Option<&str>) -> Result<(), BlogClientError> {
+        let request = Self::authorized(proto::LogoutAllRequest {}, token)?;
+        self.client.clone().logout_all(request).await?;
+        Ok(())
+    }
+
+    pub async fn verify_email(&self, token: &str) -> Result<User, BlogClientError> {
+        let res = self
+            .client
+            .clone()
+            .verify_email(proto::VerifyEmailRequest {
+                token: token.to_string(),
+            })
+            .await?;
+        res.into_inner()
+            .user
+            .map(User::from)
+            .ok_or_else(|| BlogClientError::Server("verify email response without user".into()))
+    }
+
+    pub async fn resend_verification(&self, token: Option<&str>) -> Result<(), BlogClientError> {
+        let request = Self::authorized(proto::ResendVerificationRequest {}, token)?;
+        self.client.clone().resend_verification(request).await?;
+        Ok(())
+    }
+
+    pub async fn forgot_password(&self, email: &str) -> Result<(), BlogClientError> {
+        self.client
+            .clone()
+            .forgot_password(proto::ForgotPasswordRequest {
+                email: email.to_string(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), BlogClientError> {
+        self.client
+            .clone()
+            .reset_password(proto::ResetPasswordRequest {
+                token: token.to_string(),
+                new_password: new_password.to_string(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    pub async fn change_password(
+        &self,
+        token: Option<&str>,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<AuthResponse, BlogClientError> {
+        let request = Self::authorized(
+            proto::ChangePasswordRequest {
+                current_password: current_password.to_string(),
+                new_password: new_password.to_string(),
+            },
+            token,
+        )?;
+        let res = self.client.clone().change_password(request).await?;
+        res.into_inner().try_into()
+    }
+
+    pub async fn create_post(
+        &self,
+        token: Option<&str>,
+        title: &str,
+        content: &str,
+        status: Option<&str>,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<Post, BlogClientError> {
+        let request = Self::authorized(
+            proto::CreatePostRequest {
+                title: title.to_string(),
+                content: content.to_string(),
+                status: status.unwrap_or_default().to_string(),
+                published_at: published_at.map(timestamp),
+            },
+            token,
+        )?;
+        post_from(self.client.clone().create_post(request).await?.into_inner())
+    }
+
+    pub async fn get_post(&self, id: i64) -> Result<Post, BlogClientError> {
+        let res = self
+            .client
+            .clone()
+            .get_post(proto::GetPostRequest { id })
+            .await?;
+        post_from(res.into_inner())
+    }
+
+    pub async fn update_post(
+        &self,
+        token: Option<&str>,
+        id: i64,
+        title: &str,
+        content: &str,
+        status: Option<&str>,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<Post, BlogClientError> {
+        let request = Self::authorized(
+            proto::UpdatePostRequest {
+                id,
+                title: title.to_string(),
+                content: content.to_string(),
+                status: status.unwrap_or_default().to_string(),
+                published_at: published_at.map(timestamp),
+            },
+            token,
+        )?;
+        post_from(self.client.clone().update_post(request).await?.into_inner())
+    }
+
+    pub async fn delete_post(&self, token: Option<&str>, id: i64) -> Result<(), BlogClientError> {
+        let request = Self::authorized(proto::DeletePostRequest { id }, token)?;
+        self.client.clone().delete_post(request).await?;
+        Ok(())
+    }
+
+    pub async fn set_post_hidden(
+        &self,
+        token: Option<&str>,
+        id: i64,
+        hidden: bool,
+    ) -> Result<Post, BlogClientError> {
+        let request = Self::authorized(proto::HidePostRequest { id }, token)?;
+        let mut client = self.client.clone();
+        let res = if hidden {
+            client.hide_post(request).await?
+        } else {
+            client.unhide_post(request).await?
+        };
+        post_from(res.into_inner())
+    }
+
+    pub async fn list_posts(
+        &self,
+        token: Option<&str>,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<PostList, BlogClientError> {
+        let request = Self::optionally_authorized(
+            proto::ListPostsRequest {
+                limit,
+                pagination: Some(Pagination::Offset(offset)),
+                status: status.unwrap_or_default().to_string(),
+            },
+            token,
+        )?;
+        let res = self.client.clone().list_posts(request).await?.into_inner();
+        Ok(PostList {
+            posts: res.posts.into_iter().map(Into::into).collect(),
+            total: res.total,
+            limit: res.limit,
+            offset: res.offset,
+        })
+    }
+
+    pub async fn list_posts_by_cursor(
+        &self,
+        cursor: Option<&str>,
+        limit: i64,
+    ) -> Result<PostCursorPage, BlogClientError> {
+        let res = self
+            .client
+            .clone()
+            .list_posts(proto::ListPostsRequest {
+                limit,
+                pagination: Some(Pagination::Cursor(cursor.unwrap_or_default().to_string())),
+                status: String::new(),
+            })
+            .await?
+            .into_inner();
+        Ok(PostCursorPage {
+            posts: res.posts.into_iter().map(Into::into).collect(),
+            limit: res.limit,
+            next_cursor: res.next_cursor,
+            prev_cursor: res.prev_cursor,
+        })
+    }
+
+    pub async fn get_profile(&self, user_id: i64) -> Result<Profile, BlogClientError> {
+        let res = self
+            .client
+            .clone()
+            .get_profile(proto::GetProfileRequest { id: user_id })
+            .await?;
+        profile_from(res.into_inner())
+    }
+
+    pub async fn update_profile(
+        &self,
+        token: Option<&str>,
+        user_id: i64,
+        display_name: Option<&str>,
+        bio: Option<&str>,
+        avatar_url: Option<&str>,
+    ) -> Result<Profile, BlogClientError> {
+        let request = Self::authorized(
+            proto::UpdateProfileRequest {
+                id: user_id,
+                display_name: display_name.map(str::to_string),
+                bio: bio.map(str::to_string),
+                avatar_url: avatar_url.map(str::to_string),
+            },
+            token,
+        )?;
+        profile_from(self.client.clone().update_profile(request).await?.into_inner())
+    }
+
+    pub async fn list_user_posts(
+        &self,
+        token: Option<&str>,
+        user_id: i64,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<PostList, BlogClientError> {
+        let request = Self::optionally_authorized(
+            proto::ListUserPostsRequest {
+                id: user_id,
+                limit,
+                offset,
+                status: status.unwrap_or_default().to_string(),
+            },
+            token,
+        )?;
+        let res = self.client.clone().list_user_posts(request).await?.into_inner();
+        Ok(PostList {
+            posts: res.posts.into_iter().map(Into::into).collect(),
+            total: res.total,
+            limit: res.limit,
+            offset: res.offset,
+        })
+    }
+}
@@ -0,0 +1,319 @@
+use chrono::{DateTime, Utc};
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::error::{BlogClientError, FieldError};
+use crate::trace_context;
+use crate::{AuthResponse, Post, PostCursorPage, PostList, Profile, User};
+
+// Тело ошибки сервера в формате RFC 7807 (application/problem+json)
+#[derive(Debug, Deserialize)]
+struct Problem {
+    code: String,
+    detail: String,
+    #[serde(default)]
+    errors: Vec<FieldError>,
+}
+
+#[derive(Clone)]
+pub struct HttpClient {
+    base_url: String,
+    client: Client,
+}
+
+impl HttpClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client: Client::new(),
+        }
+    }
+
+    // Каждый запрос несёт W3C traceparent текущего контекста вызывающего приложения.
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let mut request = self.client.request(method, format!("{}{}", self.base_url, path));
+        for (name, value) in trace_context::current() {
+            request = request.header(name, value);
+        }
+        request
+    }
+
+    pub async fn register(
+        &self,
+        username: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<AuthResponse, BlogClientError> {
+        let resp = self
+            .request(Method::POST, "/api/auth/register")
+            .json(&json!({"username": username, "email": email, "password": password}))
+            .send()
+            .await?;
+        parse(resp).await
+    }
+
+    pub async fn login(&self, username: &str, password: &str) -> Result<AuthResponse, BlogClientError> {
+        let resp = self
+            .request(Method::POST, "/api/auth/login")
+            .json(&json!({"username": username, "password": password}))
+            .send()
+            .await?;
+        parse(resp).await
+    }
+
+    pub async fn refresh(&self, refresh_token: &str) -> Result<AuthResponse, BlogClientError> {
+        let resp = self
+            .request(Method::POST, "/api/auth/refresh")
+            .json(&json!({"refresh_token": refresh_token}))
+            .send()
+            .await?;
+        parse(resp).await
+    }
+
+    pub async fn logout(
+        &self,
+        token: Option<&str>,
+        refresh_token: Option<&str>,
+    ) -> Result<(), BlogClientError> {
+        let request = self
+            .request(Method::POST, "/api/auth/logout")
+            .json(&json!({"refresh_token": refresh_token}));
+        let resp = authorized(request, token)?.send().await?;
+        check(resp).await.map(|_| ())
+    }
+
+    pub async fn logout_all(&self, token: Option<&str>) -> Result<(), BlogClientError> {
+        let request = self.request(Method::POST, "/api/auth/logout-all");
+        let resp = authorized(request, token)?.send().await?;
+        check(resp).await.map(|_| ())
+    }
+
+    pub async fn verify_email(&self, token: &str) -> Result<User, BlogClientError> {
+        let resp = self
+            .request(Method::POST, "/api/auth/verify-email")
+            .json(&json!({"token": token}))
+            .send()
+            .await?;
+        parse(resp).await
+    }
+
+    pub async fn resend_verification(&self, token: Option<&str>) -> Result<(), BlogClientError> {
+        let request = self.request(Method::POST, "/api/auth/verify-email/resend");
+        let resp = authorized(request, token)?.send().await?;
+        check(resp).await.map(|_| ())
+    }
+
+    pub async fn forgot_password(&self, email: &str) -> Result<(), BlogClientError> {
+        let resp = self
+            .request(Method::POST, "/api/auth/forgot-password")
+            .json(&json!({"email": email}))
+            .send()
+            .await?;
+        check(resp).await.map(|_| ())
+    }
+
+    pub async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), BlogClientError> {
+        let resp = self
+            .request(Method::POST, "/api/auth/reset-password")
+            .json(&json!({"token": token, "new_password": new_password}))
+            .send()
+            .await?;
+        check(resp).await.map(|_| ())
+    }
+
+    pub async fn change_password(
+        &self,
+        token: Option<&str>,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<AuthResponse, BlogClientError> {
+        let request = self
+            .request(Method::POST, "/api/auth/change-password")
+            .json(&json!({"current_password": current_password, "new_password": new_password}));
+        let resp = authorized(request, token)?.send().await?;
+        parse(resp).await
+    }
+
+    pub async fn create_post(
+        &self,
+        token: Option<&str>,
+        title: &str,
+        content: &str,
+        status: Option<&str>,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<Post, BlogClientError> {
+        let request = self.request(Method::POST, "/api/posts").json(&json!({
+            "title": title,
+            "content": content,
+            "status": status,
+            "published_at": published_at,
+        }));
+        let resp = authorized(request, token)?.send().await?;
+        parse(resp).await
+    }
+
+    pub async fn get_post(&self, id: i64) -> Result<Post, BlogClientError> {
+        let resp = self
+            .request(Method::GET, &format!("/api/posts/{id}"))
+            .send()
+            .await?;
+        parse(resp).await
+    }
+
+    pub async fn update_post(
+        &self,
+        token: Option<&str>,
+        id: i64,
+        title: &str,
+        content: &str,
+        status: Option<&str>,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<Post, BlogClientError> {
+        let request = self.request(Method::PUT, &format!("/api/posts/{id}")).json(&json!({
+            "title": title,
+            "content": content,
+            "status": status,
+            "published_at": published_at,
+        }));
+        let resp = authorized(request, token)?.send().await?;
+        parse(resp).await
+    }
+
+    pub async fn delete_post(&self, token: Option<&str>, id: i64) -> Result<(), BlogClientError> {
+        let request = self.request(Method::DELETE, &format!("/api/posts/{id}"));
+        let resp = authorized(request, token)?.send().await?;
+        check(resp).await.map(|_| ())
+    }
+
+    pub async fn set_post_hidden(
+        &self,
+        token: Option<&str>,
+        id: i64,
+        hidden: bool,
+    ) -> Result<Post, BlogClientError> {
+        let action = if hidden { "hide" } else { "unhide" };
+        let request = self.request(Method::POST, &format!("/api/posts/{id}/{action}"));
+        let resp = authorized(request, token)?.send().await?;
+        parse(resp).await
+    }
+
+    pub async fn list_posts(
+        &self,
+        token: Option<&str>,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<PostList, BlogClientError> {
+        let request = self
+            .request(Method::GET, "/api/posts")
+            .query(&[("limit", limit), ("offset", offset)])
+            .query(&[("status", status)]);
+        let resp = optionally_authorized(request, token).send().await?;
+        parse(resp).await
+    }
+
+    pub async fn list_posts_by_cursor(
+        &self,
+        cursor: Option<&str>,
+        limit: i64,
+    ) -> Result<PostCursorPage, BlogClientError> {
+        let limit = limit.to_string();
+        let mut query = vec![("pagination", "cursor"), ("limit", limit.as_str())];
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor));
+        }
+        let resp = self
+            .request(Method::GET, "/api/posts")
+            .query(&query)
+            .send()
+            .await?;
+        parse(resp).await
+    }
+
+    pub async fn get_profile(&self, user_id: i64) -> Result<Profile, BlogClientError> {
+        let resp = self
+            .request(Method::GET, &format!("/api/users/{user_id}"))
+            .send()
+            .await?;
+        parse(resp).await
+    }
+
+    pub async fn update_profile(
+        &self,
+        token: Option<&str>,
+        user_id: i64,
+        display_name: Option<&str>,
+        bio: Option<&str>,
+        avatar_url: Option<&str>,
+    ) -> Result<Profile, BlogClientError> {
+        let request = self
+            .request(Method::PUT, &format!("/api/users/{user_id}/profile"))
+            .json(&json!({"display_name": display_name, "bio": bio, "avatar_url": avatar_url}));
+        let resp = authorized(request, token)?.send().await?;
+        parse(resp).await
+    }
+
+    pub async fn list_user_posts(
+        &self,
+        token: Option<&str>,
+        user_id: i64,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<PostList, BlogClientError> {
+        let request = self
+            .request(Method::GET, &format!("/api/users/{user_id}/posts"))
+            .query(&[("limit", limit), ("offset", offset)])
+            .query(&[("status", status)]);
+        let resp = optionally_authorized(request, token).send().await?;
+        parse(resp).await
+    }
+}
+
+fn authorized(request: RequestBuilder, token: Option<&str>) -> Result<RequestBuilder, BlogClientError> {
+    let token = token.ok_or(BlogClientError::Unauthorized)?;
+    Ok(request.bearer_auth(token))
+}
+
+// Для публичных маршрутов, где токен меняет только набор видимых данных.
+fn optionally_authorized(request: RequestBuilder, token: Option<&str>) -> RequestBuilder {
+    match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
+
+async fn parse<T: DeserializeOwned>(resp: Response) -> Result<T, BlogClientError> {
+    Ok(check(resp).await?.json().await?)
+}
+
+async fn check(resp: Response) -> Result<Response, BlogClientError> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+
+    let problem = resp.json::<Problem>().await.ok();
+    Err(match (status, problem) {
+        (StatusCode::NOT_FOUND, _) => BlogClientError::NotFound,
+        (StatusCode::UNAUTHORIZED, _) => BlogClientError::Unauthorized,
+        // 403 с уточняющим кодом (например, email_not_verified) отдаётся как Api.
+        (StatusCode::FORBIDDEN, Some(problem)) if problem.code != "forbidden" => BlogClientError::Api {
+            code: problem.code,
+            detail: problem.detail,
+        },
+        (StatusCode::FORBIDDEN, _) => BlogClientError::Forbidden,
+        (_, Some(problem)) if !problem.errors.is_empty() => BlogClientError::Validation {
+            detail: problem.detail,
+            fields: problem.errors,
+        },
+        (_, Some(problem)) => BlogClientError::Api {
+            code: problem.code,
+            detail: problem.detail,
+        },
+        (s, None) if s.is_client_error() => BlogClientError::InvalidRequest(s.to_string()),
+        (s, None) => BlogClientError::Server(s.to_string()),
+    })
+}
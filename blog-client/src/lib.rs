@@ -1,17 +1,483 @@
 mod http_client;
 mod grpc_client;
+mod trace_context;
+pub mod error;
 
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub use error::{BlogClientError, FieldError};
+use grpc_client::GrpcClient;
+use http_client::HttpClient;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+    // None, пока адрес не подтверждён.
+    #[serde(default)]
+    pub email_verified_at: Option<DateTime<Utc>>,
+    // reader | author | moderator | admin
+    #[serde(default)]
+    pub role: String,
+    // Задан, если аккаунт заблокирован администратором.
+    #[serde(default)]
+    pub suspended_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthResponse {
+    pub token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+    pub user: User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Post {
+    pub id: i64,
+    pub title: String,
+    pub content: String,
+    pub author_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    // Задан, если пост скрыт модератором.
+    #[serde(default)]
+    pub hidden_at: Option<DateTime<Utc>>,
+    // Подпись автора; старые серверы её не присылают.
+    #[serde(default)]
+    pub author: Option<Author>,
+    // draft | scheduled | published | archived; старые серверы статус не присылают.
+    #[serde(default)]
+    pub status: String,
+    // Время публикации; у запланированного поста — когда он выйдет.
+    #[serde(default)]
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Author {
+    pub id: i64,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+// Публичный профиль пользователя.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: i64,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub joined_at: DateTime<Utc>,
+    pub post_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostList {
+    pub posts: Vec<Post>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostCursorPage {
+    pub posts: Vec<Post>,
+    pub limit: i64,
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Transport {
+    Http(String),
+    Grpc(String),
+}
+
+pub struct BlogClient {
+    transport: Transport,
+    http_client: Option<HttpClient>,
+    grpc_client: Option<GrpcClient>,
+    token: Option<String>,
+    refresh_token: Option<String>,
+}
+
+// Вызывает одноимённый метод у клиента выбранного транспорта.
+macro_rules! dispatch {
+    ($self:ident.$method:ident($($arg:expr),* $(,)?)) => {
+        match (&$self.http_client, &$self.grpc_client) {
+            (Some(http), _) => http.$method($($arg),*).await,
+            (None, Some(grpc)) => grpc.$method($($arg),*).await,
+            (None, None) => unreachable!("BlogClient without transport"),
+        }
+    };
+}
+
+// Как `dispatch!`, но с access-токеном первым аргументом. Если сервер ответил 401,
+// а refresh-токен есть, один раз обновляет пару токенов и повторяет запрос.
+macro_rules! dispatch_authorized {
+    ($self:ident.$method:ident($($arg:expr),*)) => {{
+        let res = dispatch!($self.$method($self.token.as_deref(), $($arg),*));
+        match res {
+            Err(BlogClientError::Unauthorized) if $self.refresh_token.is_some() => {
+                $self.refresh().await?;
+                dispatch!($self.$method($self.token.as_deref(), $($arg),*))
+            }
+            res => res,
+        }
+    }};
+}
+
+impl BlogClient {
+    pub async fn new(transport: Transport) -> Result<Self, BlogClientError> {
+        let (http_client, grpc_client) = match &transport {
+            Transport::Http(url) => (Some(HttpClient::new(url.clone())), None),
+            Transport::Grpc(addr) => (None, Some(GrpcClient::connect(addr.clone()).await?)),
+        };
+        Ok(Self {
+            transport,
+            http_client,
+            grpc_client,
+            token: None,
+            refresh_token: None,
+        })
+    }
+
+    pub fn transport(&self) -> &Transport {
+        &self.transport
+    }
+
+    pub fn set_token(&mut self, token: impl Into<String>) {
+        self.token = Some(token.into());
+    }
+
+    pub fn get_token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    pub fn set_refresh_token(&mut self, refresh_token: impl Into<String>) {
+        self.refresh_token = Some(refresh_token.into());
+    }
+
+    pub fn get_refresh_token(&self) -> Option<&str> {
+        self.refresh_token.as_deref()
+    }
+
+    fn store_tokens(&mut self, res: &AuthResponse) {
+        self.token = Some(res.token.clone());
+        self.refresh_token = Some(res.refresh_token.clone());
+    }
+
+    pub async fn register(
+        &mut self,
+        username: &str,
+        email: &str,
+        password: &str,
+    ) -> Result<AuthResponse, BlogClientError> {
+        let res = dispatch!(self.register(username, email, password))?;
+        self.store_tokens(&res);
+        Ok(res)
+    }
+
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<AuthResponse, BlogClientError> {
+        let res = dispatch!(self.login(username, password))?;
+        self.store_tokens(&res);
+        Ok(res)
+    }
+
+    // Обменивает сохранённый refresh-токен на новую пару. Старый refresh-токен
+    // после этого недействителен, поэтому его нужно заменить и во внешнем хранилище.
+    pub async fn refresh(&mut self) -> Result<AuthResponse, BlogClientError> {
+        let refresh_token = self
+            .refresh_token
+            .clone()
+            .ok_or(BlogClientError::Unauthorized)?;
+        let res = dispatch!(self.refresh(&refresh_token));
+        if let Err(BlogClientError::Unauthorized) = res {
+            // Токен отозван или истёк — повторять бессмысленно, нужен новый логин.
+            self.refresh_token = None;
+        }
+        let res = res?;
+        self.store_tokens(&res);
+        Ok(res)
+    }
+
+    // Завершает текущую сессию на сервере и забывает токены локально.
+    pub async fn logout(&mut self) -> Result<(), BlogClientError> {
+        let refresh_token = self.refresh_token.clone();
+        dispatch_authorized!(self.logout(refresh_token.as_deref()))?;
+        self.token = None;
+        self.refresh_token = None;
+        Ok(())
+    }
+
+    // Отзывает все сессии пользователя, включая текущую.
+    pub async fn logout_all(&mut self) -> Result<(), BlogClientError> {
+        dispatch_authorized!(self.logout_all())?;
+        self.token = None;
+        self.refresh_token = None;
+        Ok(())
+    }
+
+    // Подтверждает email токеном из письма; токен для этого не нужен.
+    pub async fn verify_email(&self, token: &str) -> Result<User, BlogClientError> {
+        dispatch!(self.verify_email(token))
+    }
+
+    pub async fn resend_verification(&mut self) -> Result<(), BlogClientError> {
+        dispatch_authorized!(self.resend_verification())
+    }
+
+    // Письмо со ссылкой сброса уходит, только если адрес зарегистрирован;
+    // ответ сервера от этого не зависит.
+    pub async fn forgot_password(&self, email: &str) -> Result<(), BlogClientError> {
+        dispatch!(self.forgot_password(email))
+    }
+
+    pub async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), BlogClientError> {
+        dispatch!(self.reset_password(token, new_password))
+    }
+
+    // Сервер отзывает все сессии пользователя; новая пара токенов сохраняется в клиенте.
+    pub async fn change_password(
+        &mut self,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<AuthResponse, BlogClientError> {
+        let res = dispatch_authorized!(self.change_password(current_password, new_password))?;
+        self.store_tokens(&res);
+        Ok(res)
+    }
+
+    // `status` — draft | scheduled | published, `None` — сразу опубликовать.
+    // Для scheduled `published_at` обязателен: это время выхода поста.
+    pub async fn create_post(
+        &mut self,
+        title: &str,
+        content: &str,
+        status: Option<&str>,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<Post, BlogClientError> {
+        dispatch_authorized!(self.create_post(title, content, status, published_at))
+    }
+
+    pub async fn get_post(&self, id: i64) -> Result<Post, BlogClientError> {
+        dispatch!(self.get_post(id))
+    }
+
+    // `status: None` оставляет статус поста прежним.
+    pub async fn update_post(
+        &mut self,
+        id: i64,
+        title: &str,
+        content: &str,
+        status: Option<&str>,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<Post, BlogClientError> {
+        dispatch_authorized!(self.update_post(id, title, content, status, published_at))
+    }
+
+    pub async fn delete_post(&mut self, id: i64) -> Result<(), BlogClientError> {
+        dispatch_authorized!(self.delete_post(id))
+    }
+
+    // Скрыть пост или вернуть его в ленту; нужна роль moderator или admin.
+    pub async fn set_post_hidden(&mut self, id: i64, hidden: bool) -> Result<Post, BlogClientError> {
+        dispatch_authorized!(self.set_post_hidden(id, hidden))
+    }
+
+    // `status` — draft | scheduled | published | archived, `None` — опубликованные.
+    // Посты в остальных статусах сервер отдаёт только их автору, поэтому нужен вход.
+    pub async fn list_posts(
+        &mut self,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<PostList, BlogClientError> {
+        if is_published(status) {
+            dispatch!(self.list_posts(None, status, limit, offset))
+        } else {
+            dispatch_authorized!(self.list_posts(status, limit, offset))
+        }
+    }
+
+    // Keyset-пагинация: `cursor` — значение `next_cursor`/`prev_cursor` из предыдущего ответа,
+    // `None` — первая страница ленты.
+    pub async fn list_posts_by_cursor(
+        &self,
+        cursor: Option<&str>,
+        limit: i64,
+    ) -> Result<PostCursorPage, BlogClientError> {
+        dispatch!(self.list_posts_by_cursor(cursor, limit))
+    }
+
+    pub async fn get_profile(&self, user_id: i64) -> Result<Profile, BlogClientError> {
+        dispatch!(self.get_profile(user_id))
+    }
+
+    // Поля заменяются целиком: `None` или пустая строка очищает поле.
+    pub async fn update_profile(
+        &mut self,
+        user_id: i64,
+        display_name: Option<&str>,
+        bio: Option<&str>,
+        avatar_url: Option<&str>,
+    ) -> Result<Profile, BlogClientError> {
+        dispatch_authorized!(self.update_profile(user_id, display_name, bio, avatar_url))
+    }
+
+    // Свои неопубликованные посты, как и в `list_posts`, — только после входа.
+    pub async fn list_user_posts(
+        &mut self,
+        user_id: i64,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<PostList, BlogClientError> {
+        if is_published(status) {
+            dispatch!(self.list_user_posts(None, user_id, status, limit, offset))
+        } else {
+            dispatch_authorized!(self.list_user_posts(user_id, status, limit, offset))
+        }
+    }
+}
+
+fn is_published(status: Option<&str>) -> bool {
+    status.is_none_or(|s| s == "published")
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
     use super::*;
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+    const AUTH: &str = r#"{"token":"new","refresh_token":"r2","expires_in":900,
+        "user":{"id":1,"username":"ivan","email":"ivan@example.com","created_at":"2026-01-01T00:00:00Z"}}"#;
+
+    // HTTP-сервер, который отвечает заготовленными ответами по порядку и запоминает
+    // запросы в виде "METHOD path token".
+    async fn stub(responses: Vec<(u16, &'static str)>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&requests);
+        tokio::spawn(async move {
+            for (status, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let head_end = loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break pos + 4;
+                    }
+                };
+                let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+                let header = |name: &str| {
+                    head.lines()
+                        .find_map(|l| l.split_once(':').filter(|(k, _)| k.eq_ignore_ascii_case(name)))
+                        .map(|(_, v)| v.trim().to_string())
+                };
+                let length: usize = header("content-length").map_or(0, |v| v.parse().unwrap());
+                while buf.len() < head_end + length {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+                let mut line = head.lines().next().unwrap().split(' ');
+                let token = header("authorization")
+                    .and_then(|v| v.strip_prefix("Bearer ").map(str::to_string))
+                    .unwrap_or_else(|| "-".into());
+                seen.lock()
+                    .unwrap()
+                    .push(format!("{} {} {token}", line.next().unwrap(), line.next().unwrap()));
+
+                let response = format!(
+                    "HTTP/1.1 {status} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    async fn client(url: String, refresh_token: Option<&str>) -> BlogClient {
+        let mut client = BlogClient::new(Transport::Http(url)).await.unwrap();
+        client.set_token("old");
+        if let Some(refresh_token) = refresh_token {
+            client.set_refresh_token(refresh_token);
+        }
+        client
+    }
+
+    fn requests(log: &Arc<Mutex<Vec<String>>>) -> Vec<String> {
+        log.lock().unwrap().clone()
+    }
+
+    #[tokio::test]
+    async fn unauthorized_call_is_retried_once_after_refresh() {
+        let (url, log) = stub(vec![(401, "{}"), (200, AUTH), (204, "")]).await;
+        let mut client = client(url, Some("r1")).await;
+
+        client.delete_post(7).await.unwrap();
+        assert_eq!(
+            requests(&log),
+            ["DELETE /api/posts/7 old", "POST /api/auth/refresh -", "DELETE /api/posts/7 new"]
+        );
+        assert_eq!(client.get_token(), Some("new"));
+        assert_eq!(client.get_refresh_token(), Some("r2"));
+    }
+
+    #[tokio::test]
+    async fn retry_happens_only_once() {
+        let (url, log) = stub(vec![(401, "{}"), (200, AUTH), (401, "{}")]).await;
+        let mut client = client(url, Some("r1")).await;
+
+        let err = client.delete_post(7).await.unwrap_err();
+        assert!(matches!(err, BlogClientError::Unauthorized), "{err:?}");
+        assert_eq!(requests(&log).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn without_refresh_token_unauthorized_is_returned_as_is() {
+        let (url, log) = stub(vec![(401, "{}")]).await;
+        let mut client = client(url, None).await;
+
+        let err = client.delete_post(7).await.unwrap_err();
+        assert!(matches!(err, BlogClientError::Unauthorized), "{err:?}");
+        assert_eq!(requests(&log), ["DELETE /api/posts/7 old"]);
+    }
+
+    #[tokio::test]
+    async fn rejected_refresh_token_is_forgotten() {
+        let (url, log) = stub(vec![(401, "{}"), (401, "{}")]).await;
+        let mut client = client(url, Some("r1")).await;
+
+        let err = client.delete_post(7).await.unwrap_err();
+        assert!(matches!(err, BlogClientError::Unauthorized), "{err:?}");
+        assert_eq!(client.get_refresh_token(), None);
+        assert_eq!(requests(&log), ["DELETE /api/posts/7 old", "POST /api/auth/refresh -"]);
+
+        // Следующий вызов уже не пытается обновить токены.
+        let err = client.refresh().await.unwrap_err();
+        assert!(matches!(err, BlogClientError::Unauthorized), "{err:?}");
+        assert_eq!(requests(&log).len(), 2);
     }
 }
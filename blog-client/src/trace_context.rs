@@ -0,0 +1,13 @@
+use std::collections::HashMap;
+
+use opentelemetry::{global, Context};
+
+// Заголовки W3C traceparent/tracestate для текущего OpenTelemetry-контекста приложения.
+// Пусто, если приложение не установило глобальный propagator или активной трассы нет.
+pub(crate) fn current() -> HashMap<String, String> {
+    let mut carrier = HashMap::new();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&Context::current(), &mut carrier)
+    });
+    carrier
+}
@@ -0,0 +1,18 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto/blog.proto");
+    // sqlx::migrate! встраивает миграции при компиляции и не замечает новые файлы.
+    println!("cargo:rerun-if-changed=migrations");
+
+    let mut config = prost_build::Config::new();
+    config.protoc_executable(protoc_bin_vendored::protoc_bin_path()?);
+
+    tonic_prost_build::configure()
+        .build_server(true)
+        .build_client(true)
+        .compile_with_config(
+            config,
+            &["proto/blog.proto".into()],
+            &["proto".into(), protoc_bin_vendored::include_path()?],
+        )?;
+    Ok(())
+}
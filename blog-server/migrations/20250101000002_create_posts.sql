@@ -1,8 +1,8 @@
 CREATE TABLE IF NOT EXISTS posts (
-    id BIGSERIAL GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    id BIGSERIAL PRIMARY KEY,
     title VARCHAR NOT NULL,
     content TEXT NOT NULL,
-    author_id BIGSERIAL NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    author_id BIGINT NOT NULL,
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
     updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
     CONSTRAINT fk_posts_author
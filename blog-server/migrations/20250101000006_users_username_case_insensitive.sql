@@ -0,0 +1,5 @@
+-- Имена пользователей уникальны без учёта регистра: "Ivan" и "ivan" — один аккаунт.
+-- Если такие пары уже есть в таблице, миграция не пройдёт и их нужно разрешить вручную.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_username_lower ON users (LOWER(username));
+-- Поиск по имени теперь идёт через LOWER(username), прежний индекс не используется.
+DROP INDEX IF EXISTS idx_users_username;
@@ -0,0 +1,20 @@
+-- Адрес подтверждается по ссылке из письма; до этого пользователь не может публиковать посты.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMP WITH TIME ZONE;
+-- Аккаунты, созданные до появления подтверждения, считаются подтверждёнными.
+UPDATE users SET email_verified_at = COALESCE(created_at, NOW()) WHERE email_verified_at IS NULL;
+
+-- Одноразовые токены из писем. Сам токен подписан и в БД не хранится, здесь
+-- отмечается только его использование; `purpose` совпадает с аудиторией токена.
+CREATE TABLE IF NOT EXISTS action_tokens (
+    jti UUID PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    purpose VARCHAR(32) NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    used_at TIMESTAMP WITH TIME ZONE,
+    CONSTRAINT fk_action_tokens_user
+    FOREIGN KEY (user_id)
+    REFERENCES users(id)
+    ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_action_tokens_user_purpose ON action_tokens(user_id, purpose);
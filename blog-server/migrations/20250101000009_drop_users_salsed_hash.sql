@@ -0,0 +1,2 @@
+-- Колонка не использовалась: соль хранится в PHC-строке password_hash.
+ALTER TABLE users DROP COLUMN IF EXISTS salsed_hash;
@@ -0,0 +1,9 @@
+-- Роль определяет права пользователя, см. domain::role. Существующие и новые
+-- аккаунты получают роль author, как и было до появления ролей; модераторы и
+-- администраторы назначаются отдельно.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role VARCHAR(16) NOT NULL DEFAULT 'author';
+ALTER TABLE users ADD CONSTRAINT users_role_check
+    CHECK (role IN ('reader', 'author', 'moderator', 'admin'));
+
+-- Скрытый модератором пост не виден в ленте и по ссылке, но не удаляется.
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS hidden_at TIMESTAMP WITH TIME ZONE;
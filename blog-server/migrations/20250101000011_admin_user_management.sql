@@ -0,0 +1,16 @@
+-- Заблокированный администратором пользователь не может войти, а выданные ему
+-- токены отклоняются. Первого администратора назначают вручную:
+-- UPDATE users SET role = 'admin' WHERE username = '...';
+ALTER TABLE users ADD COLUMN IF NOT EXISTS suspended_at TIMESTAMP WITH TIME ZONE;
+
+-- Журнал действий администраторов, только добавление. target_user_id без внешнего
+-- ключа: запись об удалении аккаунта должна пережить сам аккаунт.
+CREATE TABLE IF NOT EXISTS admin_actions (
+    id BIGSERIAL PRIMARY KEY,
+    actor_id BIGINT NOT NULL,
+    action VARCHAR(32) NOT NULL,
+    target_user_id BIGINT NOT NULL,
+    details TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_admin_actions_target ON admin_actions(target_user_id);
@@ -0,0 +1,9 @@
+-- Публичный профиль автора. Все поля необязательны: без display_name клиенты
+-- показывают username.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS display_name VARCHAR(64);
+ALTER TABLE users ADD COLUMN IF NOT EXISTS bio TEXT;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS avatar_url VARCHAR(512);
+
+-- Посты автора выбираются в порядке ленты; индекс заодно ускоряет каскадное
+-- удаление постов вместе с аккаунтом.
+CREATE INDEX IF NOT EXISTS idx_posts_author_id ON posts(author_id, created_at DESC, id DESC);
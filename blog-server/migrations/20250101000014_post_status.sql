@@ -0,0 +1,18 @@
+-- Жизненный цикл поста. Существующие посты считаются опубликованными в момент
+-- создания. У запланированного поста published_at — время публикации в будущем,
+-- у черновика — NULL.
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS status VARCHAR(16) NOT NULL DEFAULT 'published';
+ALTER TABLE posts ADD CONSTRAINT posts_status_check
+    CHECK (status IN ('draft', 'scheduled', 'published', 'archived'));
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS published_at TIMESTAMP WITH TIME ZONE;
+UPDATE posts SET published_at = created_at WHERE published_at IS NULL AND status = 'published';
+
+-- Ленты упорядочены по времени публикации, черновики — по времени создания.
+CREATE INDEX IF NOT EXISTS idx_posts_feed
+    ON posts (status, (COALESCE(published_at, created_at)) DESC, id DESC)
+    WHERE hidden_at IS NULL;
+DROP INDEX IF EXISTS idx_posts_author_id;
+CREATE INDEX IF NOT EXISTS idx_posts_author_id
+    ON posts (author_id, status, (COALESCE(published_at, created_at)) DESC, id DESC);
+-- Планировщик выбирает посты, время публикации которых наступило.
+CREATE INDEX IF NOT EXISTS idx_posts_scheduled ON posts (published_at) WHERE status = 'scheduled';
@@ -0,0 +1,16 @@
+-- В исходной схеме username, email, password_hash и created_at допускали NULL,
+-- хотя приложение всегда их заполняет и читает как обязательные. Строки без
+-- значений дополняются заглушками: '#' запрещён в именах при регистрации, домен
+-- .invalid зарезервирован, а хеш '!' не разбирается Argon2, так что войти по
+-- такому аккаунту нельзя до сброса пароля администратором.
+UPDATE users SET username = 'user#' || id WHERE username IS NULL;
+UPDATE users SET email = 'user-' || id || '@example.invalid' WHERE email IS NULL;
+UPDATE users SET password_hash = '!' WHERE password_hash IS NULL;
+UPDATE users SET created_at = NOW() WHERE created_at IS NULL;
+
+ALTER TABLE users
+    ALTER COLUMN username SET NOT NULL,
+    ALTER COLUMN email SET NOT NULL,
+    ALTER COLUMN password_hash SET NOT NULL,
+    ALTER COLUMN created_at SET DEFAULT NOW(),
+    ALTER COLUMN created_at SET NOT NULL;
@@ -0,0 +1,378 @@
+use std::sync::Arc;
+
+use tracing::{info, instrument};
+
+use crate::application::auth_service::AuthService;
+use crate::application::blog_service::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::application::policy::Actor;
+use crate::data::audit_repository::AuditRepository;
+use crate::data::user_repository::{PostgresUserRepository, UserRepository};
+use crate::domain::admin_action::{AdminAction, AdminActionKind};
+use crate::domain::audit::{AuditPage, AuditQuery};
+use crate::domain::error::BlogError;
+use crate::domain::role::{Permission, Role};
+use crate::domain::user::{User, UserPage, UserQuery};
+
+// Управление аккаунтами. Каждый метод требует Permission::ManageUsers, а каждое
+// изменение записывается в журнал admin_actions, см. UserRepository.
+#[derive(Clone)]
+pub struct AdminService<R: UserRepository + 'static = PostgresUserRepository> {
+    users: Arc<R>,
+    auth: Arc<AuthService<R>>,
+    audit: Arc<dyn AuditRepository>,
+}
+
+impl<R> AdminService<R>
+where
+    R: UserRepository + 'static,
+{
+    pub fn new(
+        users: Arc<R>,
+        auth: Arc<AuthService<R>>,
+        audit: Arc<dyn AuditRepository>,
+    ) -> Self {
+        Self { users, auth, audit }
+    }
+
+    #[instrument(skip(self, query), fields(user_id = actor.user_id))]
+    pub async fn list_users(&self, actor: &Actor, query: UserQuery) -> Result<UserPage, BlogError> {
+        actor.require(Permission::ManageUsers)?;
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0).max(0);
+        let search = query.search.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        let users = self.users.list(search, limit, offset).await?;
+        let total = self.users.count(search).await?;
+        Ok(UserPage {
+            users,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    // Журнал аудита, новые события первыми.
+    #[instrument(skip(self, query), fields(user_id = actor.user_id))]
+    pub async fn list_audit_events(&self, actor: &Actor, query: AuditQuery) -> Result<AuditPage, BlogError> {
+        actor.require(Permission::ManageUsers)?;
+        if let (Some(from), Some(to)) = (query.from, query.to)
+            && from > to
+        {
+            return Err(BlogError::Validation("`from` must not be later than `to`".into()));
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0).max(0);
+        let events = self.audit.list(&query, limit, offset).await?;
+        let total = self.audit.count(&query).await?;
+        Ok(AuditPage {
+            events,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    // Блокировка сразу завершает все сессии пользователя; разблокировка их не
+    // возвращает, пользователь входит заново.
+    #[instrument(skip(self), fields(user_id = actor.user_id))]
+    pub async fn set_suspended(&self, actor: &Actor, id: i64, suspended: bool) -> Result<User, BlogError> {
+        self.require_other(actor, id)?;
+        let user = self
+            .users
+            .set_suspended(actor.user_id, id, suspended)
+            .await?
+            .ok_or(BlogError::UserNotFound)?;
+        let kind = if suspended {
+            self.auth.logout_all(id).await?;
+            AdminActionKind::Suspend
+        } else {
+            AdminActionKind::Unsuspend
+        };
+        log_action(kind, id, None);
+        Ok(user)
+    }
+
+    // Токены с прежней ролью перестают приниматься, см. AuthService::authenticate.
+    #[instrument(skip(self), fields(user_id = actor.user_id))]
+    pub async fn set_role(&self, actor: &Actor, id: i64, role: Role) -> Result<User, BlogError> {
+        self.require_other(actor, id)?;
+        let (user, previous) = self
+            .users
+            .set_role(actor.user_id, id, role)
+            .await?
+            .ok_or(BlogError::UserNotFound)?;
+        log_action(AdminActionKind::ChangeRole, id, Some(&format!("{previous} -> {role}")));
+        Ok(user)
+    }
+
+    #[instrument(skip(self), fields(user_id = actor.user_id))]
+    pub async fn force_password_reset(&self, actor: &Actor, id: i64) -> Result<(), BlogError> {
+        actor.require(Permission::ManageUsers)?;
+        self.auth.force_password_reset(id).await?;
+        let kind = AdminActionKind::ForcePasswordReset;
+        self.users
+            .record_admin_action(AdminAction::new(actor.user_id, kind, id, None))
+            .await?;
+        log_action(kind, id, None);
+        Ok(())
+    }
+
+    // Посты и токены удаляются вместе с аккаунтом (ON DELETE CASCADE).
+    #[instrument(skip(self), fields(user_id = actor.user_id))]
+    pub async fn delete_user(&self, actor: &Actor, id: i64) -> Result<(), BlogError> {
+        self.require_other(actor, id)?;
+        if !self.users.delete(actor.user_id, id).await? {
+            return Err(BlogError::UserNotFound);
+        }
+        log_action(AdminActionKind::DeleteAccount, id, None);
+        Ok(())
+    }
+
+    // Собственный аккаунт так не меняется: администратор не должен случайно
+    // лишить себя доступа.
+    fn require_other(&self, actor: &Actor, id: i64) -> Result<(), BlogError> {
+        actor.require(Permission::ManageUsers)?;
+        if id == actor.user_id {
+            return Err(BlogError::Validation(
+                "administrators cannot change their own account here".into(),
+            ));
+        }
+        Ok(())
+    }
+
+}
+
+fn log_action(kind: AdminActionKind, target_user_id: i64, details: Option<&str>) {
+    info!(
+        action = kind.as_str(),
+        target_user_id,
+        details = details.unwrap_or_default(),
+        "admin action"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::auth_service::tests::{ctx, hasher, register_input, service_with, Mailbox};
+    use crate::data::audit_repository::InMemoryAuditRepository;
+    use crate::domain::audit::AuditAction;
+    use crate::data::user_repository::InMemoryUserRepository;
+    use crate::domain::user::LoginUser;
+
+    struct Fixture {
+        admin: AdminService<InMemoryUserRepository>,
+        auth: Arc<AuthService<InMemoryUserRepository>>,
+        users: Arc<InMemoryUserRepository>,
+        audit: Arc<InMemoryAuditRepository>,
+        mailbox: Arc<Mailbox>,
+    }
+
+    fn fixture() -> Fixture {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let audit = Arc::new(InMemoryAuditRepository::new());
+        let (auth, mailbox) = service_with(Arc::clone(&users), hasher(2), audit.clone());
+        let auth = Arc::new(auth);
+        let admin = AdminService::new(Arc::clone(&users), Arc::clone(&auth), audit.clone());
+        Fixture {
+            admin,
+            auth,
+            users,
+            audit,
+            mailbox,
+        }
+    }
+
+    fn actor(role: Role) -> Actor {
+        Actor {
+            user_id: 1000,
+            role,
+            email_verified: true,
+        }
+    }
+
+    fn login(username: &str) -> LoginUser {
+        LoginUser {
+            username: username.to_string(),
+            password: "correct-horse-42".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn only_admins_can_list_and_search_users() {
+        let f = fixture();
+        for name in ["ivan", "maria", "ivanna"] {
+            f.auth.register(register_input(name), &ctx()).await.unwrap();
+        }
+
+        let err = f
+            .admin
+            .list_users(&actor(Role::Moderator), UserQuery::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::Forbidden));
+
+        let page = f
+            .admin
+            .list_users(
+                &actor(Role::Admin),
+                UserQuery {
+                    search: Some("IVAN".into()),
+                    limit: Some(1),
+                    offset: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.users.len(), 1);
+        assert_eq!(page.users[0].username, "ivan");
+    }
+
+    #[tokio::test]
+    async fn suspended_user_loses_sessions_until_unsuspended() {
+        let f = fixture();
+        let session = f.auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let id = session.user.id;
+
+        f.admin.set_suspended(&actor(Role::Admin), id, true).await.unwrap();
+        let err = f.auth.authenticate(&session.token).await.unwrap_err();
+        assert!(matches!(err, BlogError::InvalidToken | BlogError::AccountSuspended), "{err:?}");
+        let err = f.auth.login(login("ivan"), &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::AccountSuspended), "{err:?}");
+
+        f.admin.set_suspended(&actor(Role::Admin), id, false).await.unwrap();
+        f.auth.login(login("ivan"), &ctx()).await.unwrap();
+
+        let kinds: Vec<_> = f.users.admin_actions().iter().map(|a| a.kind).collect();
+        assert_eq!(kinds, [AdminActionKind::Suspend, AdminActionKind::Unsuspend]);
+    }
+
+    #[tokio::test]
+    async fn suspended_login_does_not_reveal_password_or_reset_lockout() {
+        let f = fixture();
+        let session = f.auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let wrong = || LoginUser {
+            password: "wrong-password".to_string(),
+            ..login("ivan")
+        };
+        for _ in 0..2 {
+            assert!(matches!(f.auth.login(wrong(), &ctx()).await, Err(BlogError::InvalidCredentials)));
+        }
+
+        f.admin.set_suspended(&actor(Role::Admin), session.user.id, true).await.unwrap();
+        let err = f.auth.login(wrong(), &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::AccountSuspended), "{err:?}");
+        let err = f.auth.login(login("ivan"), &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::AccountSuspended), "{err:?}");
+
+        // Верный пароль заблокированного аккаунта не сбросил счётчик ошибок.
+        f.admin.set_suspended(&actor(Role::Admin), session.user.id, false).await.unwrap();
+        assert!(matches!(f.auth.login(wrong(), &ctx()).await, Err(BlogError::InvalidCredentials)));
+        let err = f.auth.login(login("ivan"), &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::AccountLocked { .. }), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn role_change_invalidates_tokens_issued_with_old_role() {
+        let f = fixture();
+        let session = f.auth.register(register_input("ivan"), &ctx()).await.unwrap();
+
+        let user = f
+            .admin
+            .set_role(&actor(Role::Admin), session.user.id, Role::Moderator)
+            .await
+            .unwrap();
+        assert_eq!(user.role, Role::Moderator);
+        assert!(f.auth.authenticate(&session.token).await.is_err());
+
+        let refreshed = f.auth.refresh(&session.refresh_token).await.unwrap();
+        let (_, claims) = f.auth.authenticate(&refreshed.token).await.unwrap();
+        assert_eq!(claims.role, Role::Moderator);
+        assert_eq!(f.users.admin_actions()[0].details.as_deref(), Some("author -> moderator"));
+    }
+
+    #[tokio::test]
+    async fn forced_reset_replaces_password_and_sends_link() {
+        let f = fixture();
+        let session = f.auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let sent = f.mailbox.len();
+
+        f.admin
+            .force_password_reset(&actor(Role::Admin), session.user.id)
+            .await
+            .unwrap();
+        assert_eq!(f.mailbox.len(), sent + 1);
+        assert!(f.auth.authenticate(&session.token).await.is_err());
+        let err = f.auth.login(login("ivan"), &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::InvalidCredentials), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn admin_deletes_other_accounts_but_not_own() {
+        let f = fixture();
+        let session = f.auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let admin = Actor {
+            user_id: session.user.id,
+            ..actor(Role::Admin)
+        };
+        let err = f.admin.delete_user(&admin, session.user.id).await.unwrap_err();
+        assert!(matches!(err, BlogError::Validation(_)));
+
+        f.admin
+            .delete_user(&actor(Role::Admin), session.user.id)
+            .await
+            .unwrap();
+        let err = f
+            .admin
+            .delete_user(&actor(Role::Admin), session.user.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::UserNotFound));
+        assert_eq!(f.users.admin_actions()[0].details.as_deref(), Some("ivan"));
+    }
+
+    #[tokio::test]
+    async fn audit_log_is_filtered_by_actor_action_and_time() {
+        let f = fixture();
+        let ivan = f.auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        f.auth.register(register_input("maria"), &ctx()).await.unwrap();
+        f.auth.login(login("ivan"), &ctx()).await.unwrap();
+        assert_eq!(f.audit.all().len(), 3);
+
+        let err = f
+            .admin
+            .list_audit_events(&actor(Role::Moderator), AuditQuery::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::Forbidden));
+
+        let page = f
+            .admin
+            .list_audit_events(
+                &actor(Role::Admin),
+                AuditQuery {
+                    actor_id: Some(ivan.user.id),
+                    ..AuditQuery::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+        // Новые события первыми.
+        assert_eq!(page.events[0].action, AuditAction::LoginSucceeded);
+
+        let page = f
+            .admin
+            .list_audit_events(
+                &actor(Role::Admin),
+                AuditQuery {
+                    action: Some(AuditAction::Register),
+                    to: Some(chrono::Utc::now() - chrono::Duration::hours(1)),
+                    ..AuditQuery::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.total, 0);
+    }
+}
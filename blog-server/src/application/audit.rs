@@ -0,0 +1,14 @@
+use tracing::error;
+
+use crate::data::audit_repository::AuditSink;
+use crate::domain::audit::AuditEvent;
+
+// Событие пишется после того, как действие уже выполнено, поэтому сбой записи
+// не превращается в ошибку запроса: пользователь получил бы ошибку на успешно
+// созданный пост. Такие сбои видны в логе на уровне error.
+pub(crate) async fn record(sink: &dyn AuditSink, event: AuditEvent) {
+    let action = event.action;
+    if let Err(err) = sink.record(event).await {
+        error!(action = %action, error = %err, "failed to write audit event");
+    }
+}
@@ -1,64 +1,922 @@
 use std::sync::Arc;
 
-use tracing::instrument;
+use chrono::{DateTime, Utc};
+use jsonwebtoken::jwk::JwkSet;
+use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
-use crate::data::user_repository::UserRepository;
-use crate::domain::{user::User, BankError};
-use crate::infrastructure::security::{hash_password, verify_password, JwtKeys};
+use crate::application::audit;
+use crate::application::email_verification::EmailVerification;
+use crate::application::password_reset::PasswordReset;
+use crate::application::rate_limiter::RateLimiter;
+use crate::application::validation::{
+    validate_change_password, validate_login, validate_new_password, validate_register,
+};
+use crate::data::audit_repository::AuditSink;
+use crate::data::refresh_token_repository::RefreshTokenRepository;
+use crate::data::revocation_repository::RevocationRepository;
+use crate::data::user_repository::{PostgresUserRepository, UserRepository};
+use crate::domain::audit::{AuditAction, AuditContext, AuditEvent};
+use crate::domain::error::{BlogError, FieldError};
+use crate::domain::token::RefreshToken;
+use crate::domain::user::{
+    AuthResponse, ChangePasswordRequest, LoginUser, RegisterUser, ResetPasswordRequest, User,
+};
+use crate::infrastructure::config::PasswordPolicyConfig;
+use crate::infrastructure::jwt::{generate_opaque_token, hash_opaque_token, Claims, JwtService};
+use crate::infrastructure::metrics::metrics;
+use crate::infrastructure::security::{Argon2Hasher, PasswordCheck};
 
 #[derive(Clone)]
-pub struct AuthService<R: UserRepository + 'static> {
+pub struct AuthService<R: UserRepository + 'static = PostgresUserRepository> {
     repo: Arc<R>,
-    keys: JwtKeys,
+    refresh_tokens: Arc<dyn RefreshTokenRepository>,
+    revocations: Arc<dyn RevocationRepository>,
+    jwt: Arc<JwtService>,
+    limiter: Arc<RateLimiter>,
+    password_policy: PasswordPolicyConfig,
+    hasher: Argon2Hasher,
+    verification: Arc<EmailVerification>,
+    password_reset: Arc<PasswordReset>,
+    audit: Arc<dyn AuditSink>,
 }
 
 impl<R> AuthService<R>
 where
     R: UserRepository + 'static,
 {
-    pub fn new(repo: Arc<R>, keys: JwtKeys) -> Self {
-        Self { repo, keys }
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        repo: Arc<R>,
+        refresh_tokens: Arc<dyn RefreshTokenRepository>,
+        revocations: Arc<dyn RevocationRepository>,
+        jwt: Arc<JwtService>,
+        limiter: Arc<RateLimiter>,
+        password_policy: PasswordPolicyConfig,
+        hasher: Argon2Hasher,
+        verification: Arc<EmailVerification>,
+        password_reset: Arc<PasswordReset>,
+        audit: Arc<dyn AuditSink>,
+    ) -> Self {
+        Self {
+            repo,
+            refresh_tokens,
+            revocations,
+            jwt,
+            limiter,
+            password_policy,
+            hasher,
+            verification,
+            password_reset,
+            audit,
+        }
     }
 
-    pub fn keys(&self) -> &JwtKeys {
-        &self.keys
+    pub fn jwks(&self) -> &JwkSet {
+        self.jwt.jwks()
     }
 
-    #[instrument(skip(self))]
-    pub async fn register(&self, email: String, password: String) -> Result<User, BankError> {
-        let hash = hash_password(&password).map_err(|err| BankError::Internal(err.to_string()))?;
-        let user = User::new(email.to_lowercase(), hash);
-        self.repo.create(user).await.map_err(BankError::from)
+    #[instrument(skip(self, input, ctx), fields(username = %input.username))]
+    pub async fn register(&self, mut input: RegisterUser, ctx: &AuditContext) -> Result<AuthResponse, BlogError> {
+        validate_register(&mut input, &self.password_policy)?;
+
+        let hash = self.hash_password(&input.password).await?;
+        let user = User::new(input.username, input.email, hash);
+        let user = self.repo.create(user).await?;
+        metrics().registrations.inc();
+        self.audit_user(AuditAction::Register, user.id, ctx).await;
+        // Сбой почты не отменяет регистрацию: письмо можно запросить повторно.
+        if let Err(err) = self.verification.send(&user).await {
+            warn!(user_id = user.id, error = %err, "failed to send verification email");
+        }
+        self.issue_tokens(user, Uuid::new_v4()).await
+    }
+
+    // Подтверждает адрес по токену из письма. Токен привязан к адресу, на который
+    // отправлен: после смены email старые ссылки недействительны.
+    #[instrument(skip_all)]
+    pub async fn verify_email(&self, token: &str) -> Result<User, BlogError> {
+        let claims = self.verification.redeem(token).await?;
+        let user = self
+            .repo
+            .find_by_id(claims.user_id)
+            .await?
+            .ok_or(BlogError::InvalidVerificationToken)?;
+        if user.email != claims.subject {
+            return Err(BlogError::InvalidVerificationToken);
+        }
+        self.repo
+            .mark_email_verified(user.id)
+            .await?
+            .ok_or(BlogError::InvalidVerificationToken)
     }
 
     #[instrument(skip(self))]
-    pub async fn login(&self, email: &str, password: &str) -> Result<String, BankError> {
+    pub async fn resend_verification(&self, user_id: i64) -> Result<(), BlogError> {
         let user = self
             .repo
-            .find_by_email(&email.to_lowercase())
-            .await
-            .map_err(BankError::from)?
-            .ok_or_else(|| BankError::Unauthorized)?;
+            .find_by_id(user_id)
+            .await?
+            .ok_or(BlogError::UserNotFound)?;
+        if user.is_email_verified() {
+            return Err(BlogError::EmailAlreadyVerified);
+        }
+        self.verification.send(&user).await
+    }
 
-        let is_valid = verify_password(password, &user.password_hash)
-            .map_err(|_| BankError::Unauthorized)?;
-        if !is_valid {
-            return Err(BankError::Unauthorized);
+    // В журнал аудита попадают успешные входы и отказы из-за пароля, блокировки
+    // входа или блокировки аккаунта.
+    #[instrument(skip(self, input, ctx), fields(username = %input.username))]
+    pub async fn login(&self, mut input: LoginUser, ctx: &AuditContext) -> Result<AuthResponse, BlogError> {
+        validate_login(&mut input, &self.password_policy)?;
+        let result = self.try_login(&input).await;
+        match &result {
+            Ok(res) => self.audit_user(AuditAction::LoginSucceeded, res.user.id, ctx).await,
+            Err(
+                err @ (BlogError::InvalidCredentials
+                | BlogError::AccountLocked { .. }
+                | BlogError::AccountSuspended),
+            ) => {
+                let event = AuditEvent {
+                    details: Some(format!("username={} reason={}", input.username, err.code())),
+                    ..AuditEvent::new(AuditAction::LoginFailed, ctx)
+                };
+                audit::record(self.audit.as_ref(), event).await;
+            }
+            Err(_) => {}
         }
+        result
+    }
+
+    async fn try_login(&self, input: &LoginUser) -> Result<AuthResponse, BlogError> {
+        self.limiter.check_account(&input.username).await?;
+        let user = match self.check_credentials(input).await {
+            Ok(user) => user,
+            Err(err) => {
+                if matches!(err, BlogError::InvalidCredentials) {
+                    metrics().logins.with_label_values(&["failure"]).inc();
+                    self.limiter.record_login_failure(&input.username).await?;
+                }
+                return Err(err);
+            }
+        };
+        metrics().logins.with_label_values(&["success"]).inc();
+        self.limiter.record_login_success(&input.username).await?;
 
-        self.keys
-            .generate_token(user.id)
-            .map_err(|err| BankError::Internal(err.to_string()))
+        self.issue_tokens(user, Uuid::new_v4()).await
     }
 
-    pub async fn get_user(&self, user_id: Uuid) -> Result<User, BankError> {
-        self.repo
+    // Ответ одинаков для известных и неизвестных адресов, иначе по нему можно
+    // проверять, зарегистрирован ли email. По той же причине сбой почты не возвращается.
+    #[instrument(skip_all)]
+    pub async fn forgot_password(&self, email: &str) -> Result<(), BlogError> {
+        let email = email.trim().to_lowercase();
+        let Some(user) = self.repo.find_by_email(&email).await? else {
+            info!("password reset requested for unknown email");
+            return Ok(());
+        };
+        if let Err(err) = self.password_reset.send(&user).await {
+            warn!(user_id = user.id, error = %err, "failed to send password reset email");
+        }
+        Ok(())
+    }
+
+    // Новый пароль проверяется до погашения ссылки, чтобы отклонённый политикой
+    // пароль не расходовал её. После сброса отзываются все сессии и снимается
+    // блокировка входа.
+    #[instrument(skip_all)]
+    pub async fn reset_password(&self, input: ResetPasswordRequest, ctx: &AuditContext) -> Result<(), BlogError> {
+        let user_id = self.password_reset.owner(&input.token).await?;
+        let user = self
+            .repo
             .find_by_id(user_id)
+            .await?
+            .ok_or(BlogError::InvalidResetToken)?;
+        validate_new_password(&input.new_password, &user, &self.password_policy)?;
+        if self.password_reset.redeem(&input.token).await? != user.id {
+            return Err(BlogError::InvalidResetToken);
+        }
+
+        self.set_password(&user, &input.new_password).await?;
+        self.limiter.record_login_success(&user.username).await?;
+        info!(user_id = user.id, "password reset");
+        self.audit_user(AuditAction::PasswordReset, user.id, ctx).await;
+        Ok(())
+    }
+
+    // Смена пароля завершает все сессии, включая текущую; вместо неё выдаётся
+    // новая пара токенов. Неверный текущий пароль учитывается блокировкой входа.
+    #[instrument(skip(self, input, ctx))]
+    pub async fn change_password(
+        &self,
+        user_id: i64,
+        input: ChangePasswordRequest,
+        ctx: &AuditContext,
+    ) -> Result<AuthResponse, BlogError> {
+        let user = self
+            .repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or(BlogError::UserNotFound)?;
+        validate_change_password(&input, &user, &self.password_policy)?;
+        self.limiter.check_account(&user.username).await?;
+        let check = self
+            .hasher
+            .verify(&input.current_password, &user.password_hash)
             .await
-            .map_err(BankError::from)?
-            .ok_or_else(|| BankError::NotFound(format!("user {}", user_id)))
+            .map_err(|err| BlogError::Internal(err.to_string()))?;
+        if check == PasswordCheck::Invalid {
+            self.limiter.record_login_failure(&user.username).await?;
+            return Err(BlogError::InvalidFields(vec![FieldError::new(
+                "current_password",
+                "incorrect",
+                "does not match the current password",
+            )]));
+        }
+
+        self.set_password(&user, &input.new_password).await?;
+        info!(user_id = user.id, "password changed");
+        self.audit_user(AuditAction::PasswordChanged, user.id, ctx).await;
+        self.issue_tokens(user, Uuid::new_v4()).await
+    }
+
+    // Сброс пароля администратором: прежний пароль перестаёт подходить, все сессии
+    // завершаются, а пользователю уходит ссылка для установки нового.
+    #[instrument(skip(self))]
+    pub async fn force_password_reset(&self, user_id: i64) -> Result<(), BlogError> {
+        let user = self
+            .repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or(BlogError::UserNotFound)?;
+        // Случайный пароль, который никто не знает, вместо прежнего.
+        let (unusable, _) = generate_opaque_token();
+        self.set_password(&user, &unusable).await?;
+        self.password_reset.send(&user).await
+    }
+
+    // Сохраняет новый хеш и отзывает всё, что было выдано под старым паролем:
+    // access- и refresh-токены и неиспользованные ссылки сброса.
+    async fn set_password(&self, user: &User, password: &str) -> Result<(), BlogError> {
+        let hash = self.hash_password(password).await?;
+        if !self.repo.update_password(user.id, &hash).await? {
+            return Err(BlogError::UserNotFound);
+        }
+        self.password_reset.revoke(user.id).await?;
+        self.logout_all(user.id).await
+    }
+
+    // Событие, которое пользователь совершает над собственным аккаунтом.
+    async fn audit_user(&self, action: AuditAction, user_id: i64, ctx: &AuditContext) {
+        let event = AuditEvent {
+            actor_id: Some(user_id),
+            subject_id: Some(user_id),
+            ..AuditEvent::new(action, ctx)
+        };
+        audit::record(self.audit.as_ref(), event).await;
+    }
+
+    // Для неизвестного имени Argon2 всё равно выполняется, а заблокированный
+    // аккаунт получает один и тот же ответ при любом пароле и не влияет на
+    // счётчик ошибок входа, чтобы ни подбор, ни время ответа ничего не выдавали.
+    async fn check_credentials(&self, input: &LoginUser) -> Result<User, BlogError> {
+        let Some(user) = self.repo.find_by_username(&input.username).await? else {
+            self.hasher.verify_dummy(&input.password).await;
+            return Err(BlogError::InvalidCredentials);
+        };
+
+        let check = self
+            .hasher
+            .verify(&input.password, &user.password_hash)
+            .await
+            .map_err(|err| {
+                warn!(user_id = user.id, error = %err, "failed to verify password hash");
+                BlogError::InvalidCredentials
+            })?;
+        if user.is_suspended() {
+            return Err(BlogError::AccountSuspended);
+        }
+        match check {
+            PasswordCheck::Invalid => Err(BlogError::InvalidCredentials),
+            PasswordCheck::Valid => Ok(user),
+            PasswordCheck::Outdated => Ok(self.rehash(user, &input.password).await),
+        }
+    }
+
+    async fn hash_password(&self, password: &str) -> Result<String, BlogError> {
+        self.hasher
+            .hash(password)
+            .await
+            .map_err(|err| BlogError::Internal(err.to_string()))
+    }
+
+    // Пароль только что проверен, так что хеш можно пересчитать с текущими
+    // параметрами. Ошибка здесь не мешает входу: попробуем при следующем.
+    async fn rehash(&self, mut user: User, password: &str) -> User {
+        let result = match self.hash_password(password).await {
+            Ok(hash) => self.repo.update_password(user.id, &hash).await.map(|_| hash),
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(hash) => {
+                info!(user_id = user.id, "password hash upgraded");
+                user.password_hash = hash;
+            }
+            Err(err) => warn!(user_id = user.id, error = %err, "failed to upgrade password hash"),
+        }
+        user
+    }
+
+    // Ротация: каждый refresh-токен одноразовый. Повторное предъявление уже
+    // использованного токена означает утечку, поэтому отзывается всё семейство,
+    // включая выданный по нему действующий токен.
+    #[instrument(skip_all)]
+    pub async fn refresh(&self, refresh_token: &str) -> Result<AuthResponse, BlogError> {
+        let stored = self
+            .refresh_tokens
+            .find_by_hash(&hash_opaque_token(refresh_token))
+            .await?
+            .ok_or(BlogError::InvalidRefreshToken)?;
+
+        if stored.revoked_at.is_some() || stored.expires_at <= Utc::now() {
+            return Err(BlogError::InvalidRefreshToken);
+        }
+        if stored.used_at.is_some() || !self.refresh_tokens.mark_used(stored.id).await? {
+            warn!(
+                user_id = stored.user_id,
+                family_id = %stored.family_id,
+                "refresh token reuse detected, revoking token family"
+            );
+            self.refresh_tokens.revoke_family(stored.family_id).await?;
+            return Err(BlogError::InvalidRefreshToken);
+        }
+
+        let user = self
+            .repo
+            .find_by_id(stored.user_id)
+            .await?
+            .ok_or(BlogError::InvalidRefreshToken)?;
+        self.issue_tokens(user, stored.family_id).await
+    }
+
+    // Проверка access-токена для HTTP middleware и gRPC: подпись и срок действия,
+    // отзыв по jti или через "выйти на всех устройствах", существование пользователя.
+    pub async fn authenticate(&self, token: &str) -> Result<(User, Claims), BlogError> {
+        let claims = self
+            .jwt
+            .verify_token(token)
+            .map_err(|_| BlogError::InvalidToken)?;
+        if self
+            .revocations
+            .is_revoked(claims.jti, claims.user_id, claims.generation)
+            .await?
+        {
+            return Err(BlogError::InvalidToken);
+        }
+
+        let user = self
+            .repo
+            .find_by_id(claims.user_id)
+            .await?
+            .ok_or(BlogError::InvalidToken)?;
+        // Права проверяются по роли из токена, поэтому после смены роли старые
+        // токены недействительны: клиент получит новый через refresh.
+        if claims.role != user.role {
+            return Err(BlogError::InvalidToken);
+        }
+        if user.is_suspended() {
+            return Err(BlogError::AccountSuspended);
+        }
+        Ok((user, claims))
+    }
+
+    // Завершает текущую сессию: отзывает предъявленный access-токен и, если клиент
+    // прислал refresh-токен, всё его семейство.
+    #[instrument(skip(self, claims, refresh_token), fields(user_id = claims.user_id))]
+    pub async fn logout(&self, claims: &Claims, refresh_token: Option<&str>) -> Result<(), BlogError> {
+        let expires_at = DateTime::from_timestamp(claims.exp, 0).unwrap_or_else(Utc::now);
+        self.revocations
+            .revoke(claims.jti, claims.user_id, expires_at)
+            .await?;
+
+        if let Some(refresh_token) = refresh_token {
+            let stored = self
+                .refresh_tokens
+                .find_by_hash(&hash_opaque_token(refresh_token))
+                .await?;
+            if let Some(stored) = stored.filter(|t| t.user_id == claims.user_id) {
+                self.refresh_tokens.revoke_family(stored.family_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn logout_all(&self, user_id: i64) -> Result<(), BlogError> {
+        self.revocations.bump_generation(user_id).await?;
+        self.refresh_tokens.revoke_all_for_user(user_id).await
+    }
+
+    // Заблокированному пользователю токены не выдаются ни при входе, ни по refresh-токену.
+    async fn issue_tokens(&self, user: User, family_id: Uuid) -> Result<AuthResponse, BlogError> {
+        if user.is_suspended() {
+            return Err(BlogError::AccountSuspended);
+        }
+        let generation = self.revocations.generation(user.id).await?;
+        let token = self
+            .jwt
+            .generate_token(user.id, &user.username, user.role, generation)
+            .map_err(|err| BlogError::Internal(err.to_string()))?;
+        let refresh = self.jwt.generate_refresh_token();
+        self.refresh_tokens
+            .create(RefreshToken::new(user.id, family_id, refresh.hash, refresh.expires_at))
+            .await?;
+        Ok(AuthResponse {
+            token,
+            refresh_token: refresh.token,
+            expires_in: self.jwt.access_ttl_secs(),
+            user,
+        })
     }
 }
 
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::data::action_token_repository::InMemoryActionTokenRepository;
+    use crate::data::audit_repository::InMemoryAuditRepository;
+    use crate::data::password_reset_repository::InMemoryPasswordResetRepository;
+    use crate::data::rate_limit_repository::InMemoryRateLimitRepository;
+    use crate::data::refresh_token_repository::InMemoryRefreshTokenRepository;
+    use crate::data::revocation_repository::InMemoryRevocationRepository;
+    use crate::data::user_repository::InMemoryUserRepository;
+    use crate::infrastructure::config::{
+        BucketConfig, MailConfig, MailTransport, PasswordHashConfig, RateLimitBackend,
+        RateLimitConfig,
+    };
+    use crate::infrastructure::mailer::{Email, MailError, Mailer};
+
+    // Складывает письма в память, чтобы тесты могли достать ссылку из письма.
+    #[derive(Default)]
+    pub(crate) struct Mailbox(Mutex<Vec<Email>>);
+
+    impl Mailbox {
+        pub(crate) fn len(&self) -> usize {
+            self.0.lock().unwrap().len()
+        }
+
+        pub(crate) fn last_token(&self) -> String {
+            let sent = self.0.lock().unwrap();
+            let body = &sent.last().expect("no email sent").body;
+            let start = body.find("token=").unwrap() + "token=".len();
+            body[start..].split_whitespace().next().unwrap().to_string()
+        }
+    }
+
+    #[async_trait]
+    impl Mailer for Mailbox {
+        async fn send(&self, email: Email) -> Result<(), MailError> {
+            self.0.lock().unwrap().push(email);
+            Ok(())
+        }
+    }
+
+    fn service() -> AuthService<InMemoryUserRepository> {
+        service_with_mailbox().0
+    }
+
+    fn service_with_mailbox() -> (AuthService<InMemoryUserRepository>, Arc<Mailbox>) {
+        service_with(
+            Arc::new(InMemoryUserRepository::new()),
+            hasher(2),
+            Arc::new(InMemoryAuditRepository::new()),
+        )
+    }
+
+    // Дешёвые параметры Argon2, чтобы тесты не тратили время на хеширование.
+    pub(crate) fn hasher(iterations: u32) -> Argon2Hasher {
+        Argon2Hasher::new(&PasswordHashConfig {
+            memory_kib: 1024,
+            iterations,
+            parallelism: 1,
+            pepper: None,
+        })
+        .unwrap()
+    }
+
+    pub(crate) fn service_with<R: UserRepository>(
+        users: Arc<R>,
+        hasher: Argon2Hasher,
+        audit: Arc<dyn AuditSink>,
+    ) -> (AuthService<R>, Arc<Mailbox>) {
+        let jwt = Arc::new(JwtService::new(
+            "test-secret-test-secret-test-secret",
+            Duration::from_secs(900),
+            Duration::from_secs(3600),
+        ));
+        let mailbox = Arc::new(Mailbox::default());
+        let mail = MailConfig {
+            transport: MailTransport::Outbox,
+            from: "Blog <noreply@localhost>".into(),
+            smtp_url: None,
+            outbox_dir: None,
+            verification_url: "http://localhost/verify".into(),
+            verification_ttl: Duration::from_secs(3600),
+            password_reset_url: "http://localhost/reset".into(),
+            password_reset_ttl: Duration::from_secs(3600),
+        };
+        let verification = Arc::new(EmailVerification::new(
+            Arc::new(InMemoryActionTokenRepository::new()),
+            mailbox.clone(),
+            Arc::clone(&jwt),
+            &mail,
+        ));
+        let password_reset = Arc::new(PasswordReset::new(
+            Arc::new(InMemoryPasswordResetRepository::new()),
+            mailbox.clone(),
+            &mail,
+        ));
+        let auth = AuthService::new(
+            users,
+            Arc::new(InMemoryRefreshTokenRepository::new()),
+            Arc::new(InMemoryRevocationRepository::new()),
+            jwt,
+            Arc::new(RateLimiter::new(
+                Arc::new(InMemoryRateLimitRepository::new()),
+                RateLimitConfig {
+                    backend: RateLimitBackend::Memory,
+                    ip: BucketConfig { burst: 100, per_minute: 100 },
+                    account: BucketConfig { burst: 100, per_minute: 100 },
+                    lockout_threshold: 3,
+                    lockout_base: Duration::from_secs(30),
+                    lockout_max: Duration::from_secs(300),
+                    trust_proxy_headers: false,
+                },
+            )),
+            PasswordPolicyConfig {
+                min_length: 8,
+                max_length: 128,
+                reject_common: true,
+            },
+            hasher,
+            verification,
+            password_reset,
+            audit,
+        );
+        (auth, mailbox)
+    }
+
+    pub(crate) fn ctx() -> AuditContext {
+        AuditContext {
+            ip: Some("192.0.2.1".into()),
+            user_agent: Some("tests".into()),
+            request_id: Some("req-1".into()),
+        }
+    }
+
+    pub(crate) fn register_input(username: &str) -> RegisterUser {
+        RegisterUser {
+            username: username.to_string(),
+            email: format!("{username}@Example.com"),
+            password: "correct-horse-42".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn register_then_login_issues_verifiable_tokens() {
+        let auth = service();
+        let registered = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        assert_eq!(registered.user.email, "ivan@example.com");
+
+        let logged_in = auth
+            .login(LoginUser {
+                username: "ivan".to_string(),
+                password: "correct-horse-42".to_string(),
+            }, &ctx())
+            .await
+            .unwrap();
+        let (_, claims) = auth.authenticate(&logged_in.token).await.unwrap();
+        assert_eq!(claims.user_id, registered.user.id);
+        assert_eq!(claims.username, "ivan");
+    }
+
+    #[tokio::test]
+    async fn login_upgrades_outdated_password_hash() {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let (old, _) = service_with(Arc::clone(&users), hasher(1), Arc::new(InMemoryAuditRepository::new()));
+        old.register(register_input("ivan"), &ctx()).await.unwrap();
+        let old_hash = users.find_by_username("ivan").await.unwrap().unwrap().password_hash;
+        assert!(old_hash.contains("t=1"), "{old_hash}");
+
+        let (auth, _) = service_with(Arc::clone(&users), hasher(2), Arc::new(InMemoryAuditRepository::new()));
+        let login = || LoginUser {
+            username: "ivan".to_string(),
+            password: "correct-horse-42".to_string(),
+        };
+        auth.login(login(), &ctx()).await.unwrap();
+        let new_hash = users.find_by_username("ivan").await.unwrap().unwrap().password_hash;
+        assert!(new_hash.contains("t=2"), "{new_hash}");
+        // Новый хеш проверяется, а старые параметры больше не нужны.
+        auth.login(login(), &ctx()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn duplicate_registration_is_rejected() {
+        let auth = service();
+        auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let err = auth.register(register_input("ivan"), &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::UserAlreadyExists));
+    }
+
+    #[tokio::test]
+    async fn usernames_are_case_insensitive() {
+        let auth = service();
+        auth.register(register_input("Ivan"), &ctx()).await.unwrap();
+        let err = auth
+            .register(RegisterUser {
+                email: "other@example.com".to_string(),
+                ..register_input("ｉｖａｎ")
+            }, &ctx())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::UserAlreadyExists), "{err:?}");
+
+        let res = auth
+            .login(LoginUser {
+                username: "IVAN".to_string(),
+                password: "correct-horse-42".to_string(),
+            }, &ctx())
+            .await
+            .unwrap();
+        assert_eq!(res.user.username, "Ivan");
+    }
+
+    #[tokio::test]
+    async fn wrong_password_is_invalid_credentials() {
+        let auth = service();
+        auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let err = auth
+            .login(LoginUser {
+                username: "ivan".to_string(),
+                password: "wrong".to_string(),
+            }, &ctx())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::InvalidCredentials));
+    }
+
+    #[tokio::test]
+    async fn unknown_username_is_invalid_credentials() {
+        let auth = service();
+        let err = auth
+            .login(LoginUser {
+                username: "nobody".to_string(),
+                password: "correct-horse-42".to_string(),
+            }, &ctx())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::InvalidCredentials));
+    }
+
+    #[tokio::test]
+    async fn repeated_wrong_passwords_lock_the_account() {
+        let auth = service();
+        auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let ctx = ctx();
+        let login = |password: &str| {
+            auth.login(LoginUser {
+                username: "ivan".to_string(),
+                password: password.to_string(),
+            }, &ctx)
+        };
+        for _ in 0..3 {
+            assert!(matches!(login("wrong").await, Err(BlogError::InvalidCredentials)));
+        }
+
+        // Блокировка действует и для верного пароля.
+        let err = login("correct-horse-42").await.unwrap_err();
+        assert!(matches!(err, BlogError::AccountLocked { retry_after: 30 }), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn refresh_rotates_the_token_pair() {
+        let auth = service();
+        let registered = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+
+        let refreshed = auth.refresh(&registered.refresh_token).await.unwrap();
+        assert_ne!(refreshed.refresh_token, registered.refresh_token);
+        assert_eq!(refreshed.expires_in, 900);
+        let (user, _) = auth.authenticate(&refreshed.token).await.unwrap();
+        assert_eq!(user.id, registered.user.id);
 
+        let err = auth.refresh("not-a-token").await.unwrap_err();
+        assert!(matches!(err, BlogError::InvalidRefreshToken));
+    }
+
+    #[tokio::test]
+    async fn reused_refresh_token_revokes_the_family() {
+        let auth = service();
+        let registered = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let rotated = auth.refresh(&registered.refresh_token).await.unwrap();
+
+        let err = auth.refresh(&registered.refresh_token).await.unwrap_err();
+        assert!(matches!(err, BlogError::InvalidRefreshToken));
+        // Токен, полученный ротацией, отозван вместе с семейством.
+        let err = auth.refresh(&rotated.refresh_token).await.unwrap_err();
+        assert!(matches!(err, BlogError::InvalidRefreshToken));
+    }
+
+    #[tokio::test]
+    async fn logout_revokes_the_presented_session() {
+        let auth = service();
+        let registered = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let (_, claims) = auth.authenticate(&registered.token).await.unwrap();
+
+        auth.logout(&claims, Some(&registered.refresh_token))
+            .await
+            .unwrap();
+
+        let err = auth.authenticate(&registered.token).await.unwrap_err();
+        assert!(matches!(err, BlogError::InvalidToken));
+        let err = auth.refresh(&registered.refresh_token).await.unwrap_err();
+        assert!(matches!(err, BlogError::InvalidRefreshToken));
+    }
+
+    #[tokio::test]
+    async fn logout_all_revokes_every_device_but_allows_new_login() {
+        let auth = service();
+        let laptop = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let phone = auth
+            .login(LoginUser {
+                username: "ivan".to_string(),
+                password: "correct-horse-42".to_string(),
+            }, &ctx())
+            .await
+            .unwrap();
+
+        auth.logout_all(laptop.user.id).await.unwrap();
+
+        for session in [&laptop, &phone] {
+            assert!(auth.authenticate(&session.token).await.is_err());
+            assert!(auth.refresh(&session.refresh_token).await.is_err());
+        }
+        let fresh = auth
+            .login(LoginUser {
+                username: "ivan".to_string(),
+                password: "correct-horse-42".to_string(),
+            }, &ctx())
+            .await
+            .unwrap();
+        assert!(auth.authenticate(&fresh.token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn email_is_verified_once_by_the_emailed_token() {
+        let (auth, mailbox) = service_with_mailbox();
+        let registered = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        assert!(!registered.user.is_email_verified());
+
+        let token = mailbox.last_token();
+        let user = auth.verify_email(&token).await.unwrap();
+        assert_eq!(user.id, registered.user.id);
+        assert!(user.is_email_verified());
+
+        let err = auth.verify_email(&token).await.unwrap_err();
+        assert!(matches!(err, BlogError::InvalidVerificationToken), "{err:?}");
+        let err = auth.resend_verification(user.id).await.unwrap_err();
+        assert!(matches!(err, BlogError::EmailAlreadyVerified), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn resend_invalidates_the_previous_link() {
+        let (auth, mailbox) = service_with_mailbox();
+        let registered = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let first = mailbox.last_token();
+
+        auth.resend_verification(registered.user.id).await.unwrap();
+        let second = mailbox.last_token();
+
+        let err = auth.verify_email(&first).await.unwrap_err();
+        assert!(matches!(err, BlogError::InvalidVerificationToken), "{err:?}");
+        assert!(auth.verify_email(&second).await.is_ok());
+        assert!(auth.verify_email("garbage").await.is_err());
+    }
+
+    fn login_input(password: &str) -> LoginUser {
+        LoginUser {
+            username: "ivan".to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn forgot_password_link_resets_once_and_revokes_sessions() {
+        let (auth, mailbox) = service_with_mailbox();
+        let registered = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+
+        auth.forgot_password("nobody@example.com").await.unwrap();
+        assert_eq!(mailbox.len(), 1, "unknown address must not get an email");
+        auth.forgot_password(" IVAN@example.com ").await.unwrap();
+        let token = mailbox.last_token();
+
+        // Отклонённый пароль не расходует ссылку.
+        let err = auth
+            .reset_password(ResetPasswordRequest {
+                token: token.clone(),
+                new_password: "short".to_string(),
+            }, &ctx())
+            .await
+            .unwrap_err();
+        assert_eq!(err.field_errors()[0].field, "new_password");
+
+        auth.reset_password(ResetPasswordRequest {
+            token: token.clone(),
+            new_password: "battery-staple-7".to_string(),
+        }, &ctx())
+        .await
+        .unwrap();
+
+        assert!(auth.authenticate(&registered.token).await.is_err());
+        assert!(auth.refresh(&registered.refresh_token).await.is_err());
+        assert!(auth.login(login_input("correct-horse-42"), &ctx()).await.is_err());
+        assert!(auth.login(login_input("battery-staple-7"), &ctx()).await.is_ok());
+        let err = auth
+            .reset_password(ResetPasswordRequest {
+                token,
+                new_password: "another-pass-99".to_string(),
+            }, &ctx())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::InvalidResetToken), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn change_password_requires_current_password_and_reissues_tokens() {
+        let auth = service();
+        let registered = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let ctx = ctx();
+        let change = |current: &str, new: &str| {
+            auth.change_password(
+                registered.user.id,
+                ChangePasswordRequest {
+                    current_password: current.to_string(),
+                    new_password: new.to_string(),
+                },
+                &ctx,
+            )
+        };
+
+        let err = change("wrong-password", "battery-staple-7").await.unwrap_err();
+        assert_eq!(err.field_errors()[0].field, "current_password");
+        let err = change("correct-horse-42", "correct-horse-42").await.unwrap_err();
+        assert_eq!(err.field_errors()[0].code, "unchanged");
+
+        let changed = change("correct-horse-42", "battery-staple-7").await.unwrap();
+        assert!(auth.authenticate(&registered.token).await.is_err());
+        assert!(auth.authenticate(&changed.token).await.is_ok());
+        assert!(auth.login(login_input("battery-staple-7"), &ctx).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn security_events_are_audited_with_request_context() {
+        let audit = Arc::new(InMemoryAuditRepository::new());
+        let (auth, _) = service_with(Arc::new(InMemoryUserRepository::new()), hasher(2), audit.clone());
+        let registered = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        auth.login(login_input("wrong-password"), &ctx()).await.unwrap_err();
+        auth.login(login_input("correct-horse-42"), &ctx()).await.unwrap();
+        auth.change_password(
+            registered.user.id,
+            ChangePasswordRequest {
+                current_password: "correct-horse-42".to_string(),
+                new_password: "battery-staple-7".to_string(),
+            },
+            &ctx(),
+        )
+        .await
+        .unwrap();
+
+        let events = audit.all();
+        let actions: Vec<_> = events.iter().map(|e| e.action).collect();
+        assert_eq!(
+            actions,
+            [
+                AuditAction::Register,
+                AuditAction::LoginFailed,
+                AuditAction::LoginSucceeded,
+                AuditAction::PasswordChanged,
+            ]
+        );
+        let failed = &events[1];
+        assert_eq!(failed.actor_id, None);
+        assert_eq!(failed.details.as_deref(), Some("username=ivan reason=invalid_credentials"));
+        let changed = &events[3];
+        assert_eq!(changed.actor_id, Some(registered.user.id));
+        assert_eq!(changed.ip.as_deref(), Some("192.0.2.1"));
+        assert_eq!(changed.request_id.as_deref(), Some("req-1"));
+    }
+}
@@ -0,0 +1,656 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use tracing::{info, instrument};
+
+use crate::application::audit;
+use crate::application::policy::Actor;
+use crate::data::audit_repository::AuditSink;
+use crate::data::post_repository::{PostRepository, PostgresPostRepository};
+use crate::domain::audit::{content_hash, AuditAction, AuditContext, AuditEvent};
+use crate::domain::error::BlogError;
+use crate::domain::post::{
+    CreatePost, CursorDirection, CursorPage, Post, PostCursor, PostFilter, PostPage, PostStatus, UpdatePost,
+};
+use crate::domain::role::Permission;
+use crate::infrastructure::metrics::metrics;
+
+pub const DEFAULT_PAGE_SIZE: i64 = 10;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+// Какие посты может выбрать `viewer`. Опубликованные видны всем, посты в остальных
+// статусах — только их автору, поэтому такой фильтр всегда сужается до своих постов.
+pub(crate) fn visible_posts(
+    viewer: Option<&Actor>,
+    status: Option<PostStatus>,
+    author_id: Option<i64>,
+) -> Result<PostFilter, BlogError> {
+    let status = status.unwrap_or_default();
+    if status == PostStatus::Published {
+        return Ok(PostFilter { status, author_id });
+    }
+    let viewer = viewer.ok_or(BlogError::MissingToken)?;
+    if author_id.is_some_and(|id| id != viewer.user_id) {
+        return Err(BlogError::Forbidden);
+    }
+    Ok(PostFilter {
+        status,
+        author_id: Some(viewer.user_id),
+    })
+}
+
+#[derive(Clone)]
+pub struct BlogService<P: PostRepository + 'static = PostgresPostRepository> {
+    repo: Arc<P>,
+    audit: Arc<dyn AuditSink>,
+}
+
+impl<P> BlogService<P>
+where
+    P: PostRepository + 'static,
+{
+    pub fn new(repo: Arc<P>, audit: Arc<dyn AuditSink>) -> Self {
+        Self { repo, audit }
+    }
+
+    #[instrument(skip(self, input, ctx), fields(user_id = actor.user_id))]
+    pub async fn create_post(&self, actor: &Actor, input: CreatePost, ctx: &AuditContext) -> Result<Post, BlogError> {
+        actor.require(Permission::CreatePost)?;
+        // Публиковать могут только пользователи с подтверждённым адресом.
+        if !actor.email_verified {
+            return Err(BlogError::EmailNotVerified);
+        }
+        let mut post = Post::new(input.title, input.content, actor.user_id);
+        post.set_status(input.status.unwrap_or_default(), input.published_at, post.created_at)?;
+        let post = self.repo.create(post).await?;
+        metrics().posts_created.inc();
+        let details = format!("status {}", post.status);
+        self.audit_post(AuditAction::PostCreated, Some(actor), &post, None, Some(&post), Some(details), ctx)
+            .await;
+        Ok(post)
+    }
+
+    // Скрытый пост для читателей не существует, неопубликованный — для всех, кроме автора.
+    #[instrument(skip(self, viewer))]
+    pub async fn get_post(&self, viewer: Option<&Actor>, id: i64) -> Result<Post, BlogError> {
+        self.find_post(id)
+            .await?
+            .filter(|post| post.hidden_at.is_none())
+            .filter(|post| {
+                post.status == PostStatus::Published || viewer.is_some_and(|v| v.user_id == post.author_id)
+            })
+            .ok_or(BlogError::PostNotFound)
+    }
+
+    #[instrument(skip(self, input, ctx), fields(user_id = actor.user_id))]
+    pub async fn update_post(
+        &self,
+        actor: &Actor,
+        id: i64,
+        input: UpdatePost,
+        ctx: &AuditContext,
+    ) -> Result<Post, BlogError> {
+        let before = self.authorized_post(actor, id, Permission::EditAnyPost).await?;
+        let mut post = before.clone();
+        post.title = input.title;
+        post.content = input.content;
+        post.updated_at = Utc::now();
+        post.set_status(input.status.unwrap_or(before.status), input.published_at, post.updated_at)?;
+        let post = self.repo.update(post).await?;
+        let details = (post.status != before.status)
+            .then(|| format!("status {} -> {}", before.status, post.status));
+        self.audit_post(AuditAction::PostUpdated, Some(actor), &post, Some(&before), Some(&post), details, ctx)
+            .await;
+        Ok(post)
+    }
+
+    #[instrument(skip(self, ctx), fields(user_id = actor.user_id))]
+    pub async fn delete_post(&self, actor: &Actor, id: i64, ctx: &AuditContext) -> Result<(), BlogError> {
+        let post = self.authorized_post(actor, id, Permission::DeleteAnyPost).await?;
+        self.repo.delete(id).await?;
+        self.audit_post(AuditAction::PostDeleted, Some(actor), &post, Some(&post), None, None, ctx)
+            .await;
+        Ok(())
+    }
+
+    // Модерация: скрытый пост пропадает из ленты, но остаётся у автора в БД
+    // и может быть возвращён.
+    #[instrument(skip(self, ctx), fields(user_id = actor.user_id))]
+    pub async fn set_post_hidden(
+        &self,
+        actor: &Actor,
+        id: i64,
+        hidden: bool,
+        ctx: &AuditContext,
+    ) -> Result<Post, BlogError> {
+        actor.require(Permission::HidePost)?;
+        let post = self
+            .repo
+            .set_hidden(id, hidden)
+            .await?
+            .ok_or(BlogError::PostNotFound)?;
+        info!(post_id = id, hidden, "post visibility changed by moderator");
+        let action = if hidden { AuditAction::PostHidden } else { AuditAction::PostUnhidden };
+        self.audit_post(action, Some(actor), &post, None, None, None, ctx).await;
+        Ok(post)
+    }
+
+    // Вызывается фоновым планировщиком: публикует запланированные посты, время
+    // которых наступило. Возвращает число опубликованных.
+    #[instrument(skip(self))]
+    pub async fn publish_due_posts(&self) -> Result<usize, BlogError> {
+        let published = self.repo.publish_due(Utc::now()).await?;
+        for post in &published {
+            info!(post_id = post.id, author_id = post.author_id, "scheduled post published");
+            let details = Some(format!("status {} -> {}", PostStatus::Scheduled, post.status));
+            self.audit_post(AuditAction::PostPublished, None, post, None, None, details, &AuditContext::default())
+                .await;
+        }
+        Ok(published.len())
+    }
+
+    #[instrument(skip(self, viewer))]
+    pub async fn list_posts(
+        &self,
+        viewer: Option<&Actor>,
+        status: Option<PostStatus>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<PostPage, BlogError> {
+        let filter = visible_posts(viewer, status, None)?;
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let offset = offset.unwrap_or(0).max(0);
+        let posts = self.repo.list(&filter, limit, offset).await?;
+        let total = self.repo.count(&filter).await?;
+        Ok(PostPage {
+            posts,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    #[instrument(skip(self, viewer))]
+    pub async fn list_posts_by_cursor(
+        &self,
+        viewer: Option<&Actor>,
+        status: Option<PostStatus>,
+        cursor: Option<&str>,
+        limit: Option<i64>,
+    ) -> Result<CursorPage, BlogError> {
+        let filter = visible_posts(viewer, status, None)?;
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let cursor = cursor
+            .filter(|c| !c.is_empty())
+            .map(PostCursor::decode)
+            .transpose()?;
+
+        // Запрашиваем на один пост больше, чтобы понять, есть ли продолжение.
+        let mut posts = self.repo.list_by_cursor(&filter, cursor, limit + 1).await?;
+        let has_more = posts.len() as i64 > limit;
+
+        let (next_cursor, prev_cursor) = match cursor.map(|c| c.direction) {
+            None | Some(CursorDirection::Next) => {
+                posts.truncate(limit as usize);
+                let next = posts
+                    .last()
+                    .filter(|_| has_more)
+                    .map(|p| PostCursor::new(CursorDirection::Next, p).encode());
+                let prev = posts
+                    .first()
+                    .filter(|_| cursor.is_some())
+                    .map(|p| PostCursor::new(CursorDirection::Prev, p).encode());
+                (next, prev)
+            }
+            Some(CursorDirection::Prev) => {
+                if has_more {
+                    posts.remove(0);
+                }
+                let next = posts
+                    .last()
+                    .map(|p| PostCursor::new(CursorDirection::Next, p).encode());
+                let prev = posts
+                    .first()
+                    .filter(|_| has_more)
+                    .map(|p| PostCursor::new(CursorDirection::Prev, p).encode());
+                (next, prev)
+            }
+        };
+
+        Ok(CursorPage {
+            posts,
+            limit,
+            next_cursor,
+            prev_cursor,
+        })
+    }
+
+    // Текст поста в журнал не пишется, только отпечатки версий до и после.
+    // Без `actor` действие выполнил сам сервер.
+    #[allow(clippy::too_many_arguments)]
+    async fn audit_post(
+        &self,
+        action: AuditAction,
+        actor: Option<&Actor>,
+        post: &Post,
+        before: Option<&Post>,
+        after: Option<&Post>,
+        details: Option<String>,
+        ctx: &AuditContext,
+    ) {
+        let event = AuditEvent {
+            actor_id: actor.map(|a| a.user_id),
+            subject_id: Some(post.id),
+            before_hash: before.map(content_hash),
+            after_hash: after.map(content_hash),
+            details,
+            ..AuditEvent::new(action, ctx)
+        };
+        audit::record(self.audit.as_ref(), event).await;
+    }
+
+    async fn find_post(&self, id: i64) -> Result<Option<Post>, BlogError> {
+        self.repo.find_by_id(id).await
+    }
+
+    // Единственное место, где проверяется доступ к чужому посту — и для HTTP, и для gRPC:
+    // автору достаточно авторства, остальным нужно `permission`. Чужие неопубликованные
+    // посты не видны даже модераторам.
+    async fn authorized_post(&self, actor: &Actor, id: i64, permission: Permission) -> Result<Post, BlogError> {
+        let post = self
+            .find_post(id)
+            .await?
+            .filter(|p| p.hidden_at.is_none() || p.author_id == actor.user_id || actor.can(Permission::HidePost))
+            .filter(|p| p.status == PostStatus::Published || p.author_id == actor.user_id)
+            .ok_or(BlogError::PostNotFound)?;
+        actor.require_owner_or(post.author_id, permission)?;
+        Ok(post)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::auth_service::tests::ctx;
+    use crate::data::audit_repository::InMemoryAuditRepository;
+    use crate::data::post_repository::InMemoryPostRepository;
+    use crate::data::user_repository::{InMemoryUserRepository, UserRepository};
+    use crate::domain::role::Role;
+    use crate::domain::user::User;
+
+    async fn service() -> BlogService<InMemoryPostRepository> {
+        service_with_audit().await.0
+    }
+
+    // Пользователи с id 1..=4, от имени которых действуют тесты.
+    async fn service_with_audit() -> (BlogService<InMemoryPostRepository>, Arc<InMemoryAuditRepository>) {
+        let users = Arc::new(InMemoryUserRepository::new());
+        for i in 1..=4 {
+            let user = User::new(format!("user{i}"), format!("user{i}@example.com"), "hash".to_string());
+            users.create(user).await.unwrap();
+        }
+        let audit = Arc::new(InMemoryAuditRepository::new());
+        let blog = BlogService::new(Arc::new(InMemoryPostRepository::new(users)), audit.clone());
+        (blog, audit)
+    }
+
+    fn actor(user_id: i64, role: Role) -> Actor {
+        Actor {
+            user_id,
+            role,
+            email_verified: true,
+        }
+    }
+
+    fn author(user_id: i64) -> Actor {
+        actor(user_id, Role::Author)
+    }
+
+    fn create_input(title: &str) -> CreatePost {
+        CreatePost {
+            title: title.to_string(),
+            content: "content".to_string(),
+            status: None,
+            published_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn author_can_update_and_delete_own_post() {
+        let blog = service().await;
+        let post = blog.create_post(&author(1), create_input("first"), &ctx()).await.unwrap();
+        // Подпись автора заполняется и в памяти, как JOIN users в PostgreSQL.
+        assert_eq!(post.author.as_ref().map(|a| a.username.as_str()), Some("user1"));
+
+        let updated = blog
+            .update_post(
+                &author(1),
+                post.id,
+                UpdatePost {
+                    title: "edited".to_string(),
+                    content: "new content".to_string(),
+                    status: None,
+                    published_at: None,
+                },
+                &ctx(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.title, "edited");
+        assert_eq!(updated.author.map(|a| a.id), Some(1));
+
+        blog.delete_post(&author(1), post.id, &ctx()).await.unwrap();
+        assert!(matches!(
+            blog.get_post(None, post.id).await.unwrap_err(),
+            BlogError::PostNotFound
+        ));
+    }
+
+    #[tokio::test]
+    async fn other_users_cannot_modify_post() {
+        let blog = service().await;
+        let post = blog.create_post(&author(1), create_input("first"), &ctx()).await.unwrap();
+
+        let err = blog
+            .update_post(
+                &author(2),
+                post.id,
+                UpdatePost {
+                    title: "hijacked".to_string(),
+                    content: String::new(),
+                    status: None,
+                    published_at: None,
+                },
+                &ctx(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::Forbidden));
+
+        let err = blog.delete_post(&author(2), post.id, &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::Forbidden));
+        assert_eq!(blog.get_post(None, post.id).await.unwrap().title, "first");
+    }
+
+    #[tokio::test]
+    async fn list_returns_newest_first_with_total() {
+        let blog = service().await;
+        for i in 0..3 {
+            blog.create_post(&author(1), create_input(&format!("post {i}")), &ctx()).await.unwrap();
+        }
+
+        let page = blog.list_posts(None, None, Some(2), Some(1)).await.unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.limit, 2);
+        assert_eq!(page.offset, 1);
+        let titles: Vec<_> = page.posts.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, ["post 1", "post 0"]);
+
+        let page = blog.list_posts(None, None, Some(10_000), None).await.unwrap();
+        assert_eq!(page.limit, MAX_PAGE_SIZE);
+        assert_eq!(page.offset, 0);
+    }
+
+    #[tokio::test]
+    async fn cursor_pages_walk_the_feed_in_both_directions() {
+        let blog = service().await;
+        for i in 0..5 {
+            blog.create_post(&author(1), create_input(&format!("post {i}")), &ctx()).await.unwrap();
+        }
+        let titles = |page: &CursorPage| -> Vec<String> {
+            page.posts.iter().map(|p| p.title.clone()).collect()
+        };
+
+        let first = blog.list_posts_by_cursor(None, None, None, Some(2)).await.unwrap();
+        assert_eq!(titles(&first), ["post 4", "post 3"]);
+        assert!(first.prev_cursor.is_none());
+
+        let second = blog
+            .list_posts_by_cursor(None, None, first.next_cursor.as_deref(), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(titles(&second), ["post 2", "post 1"]);
+
+        let last = blog
+            .list_posts_by_cursor(None, None, second.next_cursor.as_deref(), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(titles(&last), ["post 0"]);
+        assert!(last.next_cursor.is_none());
+
+        let back = blog
+            .list_posts_by_cursor(None, None, last.prev_cursor.as_deref(), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(titles(&back), ["post 2", "post 1"]);
+        assert!(back.prev_cursor.is_some());
+    }
+
+    #[tokio::test]
+    async fn malformed_cursor_is_rejected() {
+        let blog = service().await;
+        let err = blog
+            .list_posts_by_cursor(None, None, Some("not-a-cursor"), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::InvalidCursor));
+    }
+
+    #[tokio::test]
+    async fn missing_post_is_not_found() {
+        let blog = service().await;
+        let err = blog.delete_post(&author(1), 42, &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::PostNotFound));
+    }
+
+    #[tokio::test]
+    async fn unverified_author_cannot_create_posts() {
+        let blog = service().await;
+        let err = blog.create_post(&Actor { email_verified: false, ..author(1) }, create_input("first"), &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::EmailNotVerified));
+    }
+
+    #[tokio::test]
+    async fn readers_cannot_create_posts() {
+        let blog = service().await;
+        let err = blog
+            .create_post(&actor(1, Role::Reader), create_input("first"), &ctx())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::Forbidden));
+    }
+
+    #[tokio::test]
+    async fn moderator_can_edit_and_hide_any_post_but_not_delete_it() {
+        let blog = service().await;
+        let post = blog.create_post(&author(1), create_input("first"), &ctx()).await.unwrap();
+        let moderator = actor(2, Role::Moderator);
+
+        let updated = blog
+            .update_post(
+                &moderator,
+                post.id,
+                UpdatePost {
+                    title: "moderated".to_string(),
+                    content: "content".to_string(),
+                    status: None,
+                    published_at: None,
+                },
+                &ctx(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.author_id, 1);
+        let err = blog.delete_post(&moderator, post.id, &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::Forbidden));
+
+        let err = blog.set_post_hidden(&author(1), post.id, true, &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::Forbidden));
+        blog.set_post_hidden(&moderator, post.id, true, &ctx()).await.unwrap();
+        assert!(matches!(blog.get_post(None, post.id).await.unwrap_err(), BlogError::PostNotFound));
+        assert_eq!(blog.list_posts(None, None, None, None).await.unwrap().total, 0);
+        // Для посторонних скрытого поста нет, а администратор может его удалить.
+        let err = blog.delete_post(&author(3), post.id, &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::PostNotFound));
+        blog.delete_post(&actor(4, Role::Admin), post.id, &ctx()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn unhidden_post_returns_to_the_feed() {
+        let (blog, audit) = service_with_audit().await;
+        let post = blog.create_post(&author(1), create_input("first"), &ctx()).await.unwrap();
+        let moderator = actor(2, Role::Moderator);
+        blog.set_post_hidden(&moderator, post.id, true, &ctx()).await.unwrap();
+        let post = blog.set_post_hidden(&moderator, post.id, false, &ctx()).await.unwrap();
+        assert!(post.hidden_at.is_none());
+        assert_eq!(blog.get_post(None, post.id).await.unwrap().title, "first");
+
+        let events = audit.all();
+        let actions: Vec<_> = events.iter().map(|e| e.action).collect();
+        assert_eq!(actions, [AuditAction::PostCreated, AuditAction::PostHidden, AuditAction::PostUnhidden]);
+        for event in &events[1..] {
+            assert_eq!(event.actor_id, Some(2));
+            assert_eq!(event.subject_id, Some(post.id));
+            assert_eq!(event.request_id.as_deref(), Some("req-1"));
+        }
+    }
+
+    #[tokio::test]
+    async fn post_changes_are_audited_with_content_hashes() {
+        let (blog, audit) = service_with_audit().await;
+        let post = blog.create_post(&author(1), create_input("first"), &ctx()).await.unwrap();
+        let updated = blog
+            .update_post(
+                &actor(2, Role::Moderator),
+                post.id,
+                UpdatePost {
+                    title: "edited".to_string(),
+                    content: "content".to_string(),
+                    status: None,
+                    published_at: None,
+                },
+                &ctx(),
+            )
+            .await
+            .unwrap();
+        blog.delete_post(&author(1), post.id, &ctx()).await.unwrap();
+        // Отклонённые действия в журнал не попадают.
+        blog.delete_post(&author(1), post.id, &ctx()).await.unwrap_err();
+
+        let events = audit.all();
+        let actions: Vec<_> = events.iter().map(|e| e.action).collect();
+        assert_eq!(actions, [AuditAction::PostCreated, AuditAction::PostUpdated, AuditAction::PostDeleted]);
+        let (created, edited, deleted) = (&events[0], &events[1], &events[2]);
+        assert_eq!(created.before_hash, None);
+        assert_eq!(created.after_hash, Some(content_hash(&post)));
+        assert_eq!(edited.actor_id, Some(2));
+        assert_eq!(edited.subject_id, Some(post.id));
+        assert_eq!(edited.before_hash, created.after_hash);
+        assert_eq!(edited.after_hash, Some(content_hash(&updated)));
+        assert_ne!(edited.before_hash, edited.after_hash);
+        assert_eq!(deleted.before_hash, edited.after_hash);
+        assert_eq!(deleted.after_hash, None);
+    }
+
+    fn with_status(title: &str, status: PostStatus, published_at: Option<chrono::DateTime<Utc>>) -> CreatePost {
+        CreatePost {
+            status: Some(status),
+            published_at,
+            ..create_input(title)
+        }
+    }
+
+    #[tokio::test]
+    async fn drafts_are_visible_only_to_their_author() {
+        let blog = service().await;
+        let draft = blog
+            .create_post(&author(1), with_status("draft", PostStatus::Draft, None), &ctx())
+            .await
+            .unwrap();
+        assert_eq!(draft.published_at, None);
+        blog.create_post(&author(1), create_input("public"), &ctx()).await.unwrap();
+
+        assert_eq!(blog.get_post(Some(&author(1)), draft.id).await.unwrap().title, "draft");
+        for viewer in [None, Some(author(2)), Some(actor(3, Role::Admin))] {
+            let err = blog.get_post(viewer.as_ref(), draft.id).await.unwrap_err();
+            assert!(matches!(err, BlogError::PostNotFound));
+        }
+        let err = blog.delete_post(&actor(3, Role::Admin), draft.id, &ctx()).await.unwrap_err();
+        assert!(matches!(err, BlogError::PostNotFound));
+
+        // Лента по умолчанию — только опубликованное, свои черновики — по фильтру.
+        let page = blog.list_posts(Some(&author(1)), None, None, None).await.unwrap();
+        assert_eq!(page.total, 1);
+        let page = blog.list_posts(Some(&author(1)), Some(PostStatus::Draft), None, None).await.unwrap();
+        assert_eq!(page.posts[0].id, draft.id);
+        let page = blog.list_posts(Some(&author(2)), Some(PostStatus::Draft), None, None).await.unwrap();
+        assert_eq!(page.total, 0);
+        let err = blog.list_posts(None, Some(PostStatus::Draft), None, None).await.unwrap_err();
+        assert!(matches!(err, BlogError::MissingToken));
+    }
+
+    #[tokio::test]
+    async fn scheduled_posts_are_published_when_due() {
+        let (blog, audit) = service_with_audit().await;
+        let soon = Utc::now() + chrono::Duration::milliseconds(50);
+        let scheduled = blog
+            .create_post(&author(1), with_status("later", PostStatus::Scheduled, Some(soon)), &ctx())
+            .await
+            .unwrap();
+        assert_eq!(scheduled.published_at, Some(soon));
+        assert_eq!(blog.publish_due_posts().await.unwrap(), 0);
+        assert_eq!(blog.list_posts(None, None, None, None).await.unwrap().total, 0);
+
+        tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+        assert_eq!(blog.publish_due_posts().await.unwrap(), 1);
+        assert_eq!(blog.publish_due_posts().await.unwrap(), 0);
+        let post = blog.get_post(None, scheduled.id).await.unwrap();
+        assert_eq!(post.status, PostStatus::Published);
+        assert_eq!(post.published_at, Some(soon));
+
+        let event = audit.all().pop().unwrap();
+        assert_eq!(event.action, AuditAction::PostPublished);
+        assert_eq!(event.actor_id, None);
+        assert_eq!(event.subject_id, Some(scheduled.id));
+    }
+
+    #[tokio::test]
+    async fn status_changes_are_validated() {
+        let blog = service().await;
+        let past = Utc::now() - chrono::Duration::hours(1);
+        let err = blog
+            .create_post(&author(1), with_status("late", PostStatus::Scheduled, Some(past)), &ctx())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::Validation(_)));
+        let err = blog
+            .create_post(&author(1), with_status("when?", PostStatus::Scheduled, None), &ctx())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::Validation(_)));
+        let err = blog
+            .create_post(&author(1), with_status("draft", PostStatus::Draft, Some(Utc::now())), &ctx())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::Validation(_)));
+
+        // Архивирование и возврат в ленту не меняют время публикации.
+        let post = blog.create_post(&author(1), create_input("first"), &ctx()).await.unwrap();
+        let archive = |status| UpdatePost {
+            title: "first".to_string(),
+            content: "content".to_string(),
+            status: Some(status),
+            published_at: None,
+        };
+        let archived = blog
+            .update_post(&author(1), post.id, archive(PostStatus::Archived), &ctx())
+            .await
+            .unwrap();
+        assert_eq!(archived.published_at, post.published_at);
+        let restored = blog
+            .update_post(&author(1), post.id, archive(PostStatus::Published), &ctx())
+            .await
+            .unwrap();
+        assert_eq!(restored.published_at, post.published_at);
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{info, instrument};
+
+use crate::data::action_token_repository::ActionTokenRepository;
+use crate::domain::error::BlogError;
+use crate::domain::user::User;
+use crate::infrastructure::config::MailConfig;
+use crate::infrastructure::jwt::{ActionClaims, JwtService};
+use crate::infrastructure::mailer::{link_with_token, Email, Mailer};
+
+// Аудитория JWT и назначение записи в action_tokens.
+const PURPOSE: &str = "verify-email";
+
+// Ссылки подтверждения email: подписанный токен с адресом внутри, одноразовый
+// за счёт записи в action_tokens.
+pub struct EmailVerification {
+    tokens: Arc<dyn ActionTokenRepository>,
+    mailer: Arc<dyn Mailer>,
+    jwt: Arc<JwtService>,
+    url: String,
+    ttl: Duration,
+}
+
+impl EmailVerification {
+    pub fn new(
+        tokens: Arc<dyn ActionTokenRepository>,
+        mailer: Arc<dyn Mailer>,
+        jwt: Arc<JwtService>,
+        cfg: &MailConfig,
+    ) -> Self {
+        Self {
+            tokens,
+            mailer,
+            jwt,
+            url: cfg.verification_url.clone(),
+            ttl: cfg.verification_ttl,
+        }
+    }
+
+    // Выдаёт новый токен и отправляет письмо; ссылки из прежних писем перестают действовать.
+    #[instrument(skip_all, fields(user_id = user.id))]
+    pub async fn send(&self, user: &User) -> Result<(), BlogError> {
+        let (token, claims) = self
+            .jwt
+            .generate_action_token(PURPOSE, user.id, &user.email, self.ttl)
+            .map_err(|err| BlogError::Internal(err.to_string()))?;
+        let expires_at = DateTime::from_timestamp(claims.exp, 0).unwrap_or_else(Utc::now);
+        self.tokens
+            .replace(user.id, PURPOSE, claims.jti, expires_at)
+            .await?;
+
+        let link = link_with_token(&self.url, &token);
+        let hours = self.ttl.as_secs().div_ceil(3600);
+        self.mailer
+            .send(Email {
+                to: user.email.clone(),
+                subject: "Confirm your email address".into(),
+                body: format!(
+                    "Hello, {}!\n\nTo confirm your email address, open this link:\n{link}\n\n\
+                     The link is valid for {hours} h. If you did not register, ignore this email.\n",
+                    user.username
+                ),
+            })
+            .await
+            .map_err(|err| BlogError::Internal(format!("failed to send verification email: {err}")))?;
+        info!("verification email sent");
+        Ok(())
+    }
+
+    // Проверяет подпись и срок токена и гасит его; адрес сверяет вызывающий.
+    pub async fn redeem(&self, token: &str) -> Result<ActionClaims, BlogError> {
+        let claims = self
+            .jwt
+            .verify_action_token(PURPOSE, token)
+            .map_err(|_| BlogError::InvalidVerificationToken)?;
+        if !self.tokens.consume(claims.jti, claims.user_id, PURPOSE).await? {
+            return Err(BlogError::InvalidVerificationToken);
+        }
+        Ok(claims)
+    }
+}
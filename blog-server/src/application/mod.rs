@@ -1,2 +1,10 @@
+pub(crate) mod admin_service;
+pub(crate) mod audit;
 pub(crate) mod auth_service;
-mod blog_service;
\ No newline at end of file
+pub(crate) mod blog_service;
+pub(crate) mod email_verification;
+pub(crate) mod password_reset;
+pub(crate) mod policy;
+pub(crate) mod profile_service;
+pub(crate) mod rate_limiter;
+pub(crate) mod validation;
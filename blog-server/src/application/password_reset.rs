@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{info, instrument};
+
+use crate::data::password_reset_repository::PasswordResetRepository;
+use crate::domain::error::BlogError;
+use crate::domain::user::User;
+use crate::infrastructure::config::MailConfig;
+use crate::infrastructure::jwt::{generate_opaque_token, hash_opaque_token};
+use crate::infrastructure::mailer::{link_with_token, Email, Mailer};
+
+// Ссылки сброса пароля: случайный одноразовый токен, в БД хранится только хеш.
+pub struct PasswordReset {
+    tokens: Arc<dyn PasswordResetRepository>,
+    mailer: Arc<dyn Mailer>,
+    url: String,
+    ttl: Duration,
+}
+
+impl PasswordReset {
+    pub fn new(
+        tokens: Arc<dyn PasswordResetRepository>,
+        mailer: Arc<dyn Mailer>,
+        cfg: &MailConfig,
+    ) -> Self {
+        Self {
+            tokens,
+            mailer,
+            url: cfg.password_reset_url.clone(),
+            ttl: cfg.password_reset_ttl,
+        }
+    }
+
+    // Выдаёт новый токен и отправляет письмо; ссылки из прежних писем перестают действовать.
+    #[instrument(skip_all, fields(user_id = user.id))]
+    pub async fn send(&self, user: &User) -> Result<(), BlogError> {
+        let (token, hash) = generate_opaque_token();
+        self.tokens
+            .replace(user.id, &hash, Utc::now() + self.ttl)
+            .await?;
+
+        let link = link_with_token(&self.url, &token);
+        let minutes = self.ttl.as_secs().div_ceil(60);
+        self.mailer
+            .send(Email {
+                to: user.email.clone(),
+                subject: "Reset your password".into(),
+                body: format!(
+                    "Hello, {}!\n\nTo set a new password, open this link:\n{link}\n\n\
+                     The link is valid for {minutes} min and can be used once. If you did not \
+                     request a password reset, ignore this email: your password stays the same.\n",
+                    user.username
+                ),
+            })
+            .await
+            .map_err(|err| BlogError::Internal(format!("failed to send password reset email: {err}")))?;
+        info!("password reset email sent");
+        Ok(())
+    }
+
+    // Владелец ссылки без её погашения: новый пароль проверяется до того, как
+    // ссылка будет израсходована.
+    pub async fn owner(&self, token: &str) -> Result<i64, BlogError> {
+        self.tokens
+            .find_user(&hash_opaque_token(token))
+            .await?
+            .ok_or(BlogError::InvalidResetToken)
+    }
+
+    // Гасит токен и возвращает id владельца.
+    pub async fn redeem(&self, token: &str) -> Result<i64, BlogError> {
+        self.tokens
+            .consume(&hash_opaque_token(token))
+            .await?
+            .ok_or(BlogError::InvalidResetToken)
+    }
+
+    // Аннулирует неиспользованные ссылки, например после смены пароля.
+    pub async fn revoke(&self, user_id: i64) -> Result<(), BlogError> {
+        self.tokens.delete_for_user(user_id).await
+    }
+}
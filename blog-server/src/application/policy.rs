@@ -0,0 +1,70 @@
+use crate::domain::error::BlogError;
+use crate::domain::role::{Permission, Role};
+
+// Кто выполняет действие. Собирается из проверенного токена (HTTP middleware или
+// gRPC), а решения о доступе принимают сервисы — одинаково для обоих транспортов.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Actor {
+    pub(crate) user_id: i64,
+    pub(crate) role: Role,
+    pub(crate) email_verified: bool,
+}
+
+impl Actor {
+    pub(crate) fn can(&self, permission: Permission) -> bool {
+        self.role.has(permission)
+    }
+
+    pub(crate) fn require(&self, permission: Permission) -> Result<(), BlogError> {
+        if self.can(permission) {
+            Ok(())
+        } else {
+            Err(BlogError::Forbidden)
+        }
+    }
+
+    // Со своим ресурсом можно работать всегда, с чужим — только при `permission`.
+    pub(crate) fn require_owner_or(&self, owner_id: i64, permission: Permission) -> Result<(), BlogError> {
+        if owner_id == self.user_id {
+            Ok(())
+        } else {
+            self.require(permission)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actor(role: Role) -> Actor {
+        Actor {
+            user_id: 1,
+            role,
+            email_verified: true,
+        }
+    }
+
+    #[test]
+    fn roles_grant_expected_permissions() {
+        assert!(!actor(Role::Reader).can(Permission::CreatePost));
+        assert!(actor(Role::Author).can(Permission::CreatePost));
+        assert!(!actor(Role::Author).can(Permission::EditAnyPost));
+        assert!(actor(Role::Moderator).can(Permission::EditAnyPost));
+        assert!(actor(Role::Moderator).can(Permission::HidePost));
+        assert!(!actor(Role::Moderator).can(Permission::DeleteAnyPost));
+        assert!(!actor(Role::Moderator).can(Permission::ManageUsers));
+        assert!(actor(Role::Admin).can(Permission::ManageUsers));
+    }
+
+    #[test]
+    fn owners_need_no_extra_permission() {
+        let reader = actor(Role::Reader);
+        assert!(reader.require_owner_or(1, Permission::EditAnyPost).is_ok());
+        assert!(matches!(
+            reader.require_owner_or(2, Permission::EditAnyPost),
+            Err(BlogError::Forbidden)
+        ));
+        assert!(actor(Role::Moderator).require_owner_or(2, Permission::EditAnyPost).is_ok());
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use tracing::instrument;
+
+use crate::application::blog_service::{visible_posts, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::application::policy::Actor;
+use crate::application::validation;
+use crate::data::post_repository::PostRepository;
+use crate::data::user_repository::{PostgresUserRepository, UserRepository};
+use crate::domain::error::BlogError;
+use crate::domain::post::{PostFilter, PostPage, PostStatus};
+use crate::domain::role::Permission;
+use crate::domain::user::{PublicProfile, UpdateProfile};
+
+// Публичные профили авторов и их посты. Читать может кто угодно, менять профиль —
+// только владелец или администратор.
+#[derive(Clone)]
+pub struct ProfileService<R: UserRepository + 'static = PostgresUserRepository> {
+    users: Arc<R>,
+    posts: Arc<dyn PostRepository>,
+}
+
+impl<R> ProfileService<R>
+where
+    R: UserRepository + 'static,
+{
+    pub fn new(users: Arc<R>, posts: Arc<dyn PostRepository>) -> Self {
+        Self { users, posts }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_profile(&self, id: i64) -> Result<PublicProfile, BlogError> {
+        let user = self.users.find_by_id(id).await?.ok_or(BlogError::UserNotFound)?;
+        let post_count = self.published_count(id).await?;
+        Ok(PublicProfile::new(user, post_count))
+    }
+
+    #[instrument(skip(self, input), fields(user_id = actor.user_id))]
+    pub async fn update_profile(
+        &self,
+        actor: &Actor,
+        id: i64,
+        mut input: UpdateProfile,
+    ) -> Result<PublicProfile, BlogError> {
+        actor.require_owner_or(id, Permission::ManageUsers)?;
+        validation::validate_profile(&mut input)?;
+        let user = self
+            .users
+            .update_profile(id, &input)
+            .await?
+            .ok_or(BlogError::UserNotFound)?;
+        let post_count = self.published_count(id).await?;
+        Ok(PublicProfile::new(user, post_count))
+    }
+
+    // Несуществующий автор — 404, а не пустая страница. Неопубликованные посты
+    // автор видит только в своём профиле.
+    #[instrument(skip(self, viewer))]
+    pub async fn list_author_posts(
+        &self,
+        viewer: Option<&Actor>,
+        id: i64,
+        status: Option<PostStatus>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<PostPage, BlogError> {
+        let filter = visible_posts(viewer, status, Some(id))?;
+        if self.users.find_by_id(id).await?.is_none() {
+            return Err(BlogError::UserNotFound);
+        }
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let offset = offset.unwrap_or(0).max(0);
+        let posts = self.posts.list(&filter, limit, offset).await?;
+        let total = self.posts.count(&filter).await?;
+        Ok(PostPage {
+            posts,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    async fn published_count(&self, id: i64) -> Result<i64, BlogError> {
+        let filter = PostFilter {
+            status: PostStatus::Published,
+            author_id: Some(id),
+        };
+        self.posts.count(&filter).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::post_repository::InMemoryPostRepository;
+    use crate::data::user_repository::InMemoryUserRepository;
+    use crate::domain::post::Post;
+    use crate::domain::role::Role;
+    use crate::domain::user::User;
+
+    struct Fixture {
+        profiles: ProfileService<InMemoryUserRepository>,
+        users: Arc<InMemoryUserRepository>,
+        posts: Arc<InMemoryPostRepository>,
+    }
+
+    fn fixture() -> Fixture {
+        let users = Arc::new(InMemoryUserRepository::new());
+        let posts = Arc::new(InMemoryPostRepository::new(users.clone()));
+        Fixture {
+            profiles: ProfileService::new(users.clone(), posts.clone()),
+            users,
+            posts,
+        }
+    }
+
+    fn actor(user_id: i64, role: Role) -> Actor {
+        Actor {
+            user_id,
+            role,
+            email_verified: true,
+        }
+    }
+
+    async fn user(f: &Fixture, name: &str) -> i64 {
+        let user = User::new(name.to_string(), format!("{name}@example.com"), "hash".to_string());
+        f.users.create(user).await.unwrap().id
+    }
+
+    fn profile(display_name: &str) -> UpdateProfile {
+        UpdateProfile {
+            display_name: Some(display_name.to_string()),
+            ..UpdateProfile::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn profile_counts_only_visible_posts() {
+        let f = fixture();
+        let ivan = user(&f, "ivan").await;
+        let maria = user(&f, "maria").await;
+        for (author, title) in [(ivan, "a"), (ivan, "b"), (maria, "c")] {
+            f.posts
+                .create(Post::new(title.to_string(), "text".to_string(), author))
+                .await
+                .unwrap();
+        }
+        f.posts.set_hidden(1, true).await.unwrap();
+
+        let profile = f.profiles.get_profile(ivan).await.unwrap();
+        assert_eq!(profile.username, "ivan");
+        assert_eq!(profile.post_count, 1);
+
+        let page = f.profiles.list_author_posts(None, ivan, None, None, None).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.posts[0].title, "b");
+
+        let err = f.profiles.list_author_posts(None, 42, None, None, None).await.unwrap_err();
+        assert!(matches!(err, BlogError::UserNotFound));
+
+        let mut draft = Post::new("draft".to_string(), "text".to_string(), ivan);
+        draft.set_status(PostStatus::Draft, None, draft.created_at).unwrap();
+        f.posts.create(draft).await.unwrap();
+        assert_eq!(f.profiles.get_profile(ivan).await.unwrap().post_count, 1);
+        let drafts = f
+            .profiles
+            .list_author_posts(Some(&actor(ivan, Role::Author)), ivan, Some(PostStatus::Draft), None, None)
+            .await
+            .unwrap();
+        assert_eq!(drafts.posts[0].title, "draft");
+        let err = f
+            .profiles
+            .list_author_posts(Some(&actor(maria, Role::Author)), ivan, Some(PostStatus::Draft), None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::Forbidden));
+    }
+
+    #[tokio::test]
+    async fn only_owner_or_admin_edits_profile() {
+        let f = fixture();
+        let ivan = user(&f, "ivan").await;
+
+        let updated = f
+            .profiles
+            .update_profile(&actor(ivan, Role::Author), ivan, profile(" Иван "))
+            .await
+            .unwrap();
+        assert_eq!(updated.display_name.as_deref(), Some("Иван"));
+
+        let err = f
+            .profiles
+            .update_profile(&actor(ivan + 1, Role::Moderator), ivan, profile("x"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlogError::Forbidden));
+
+        let updated = f
+            .profiles
+            .update_profile(&actor(ivan + 1, Role::Admin), ivan, UpdateProfile::default())
+            .await
+            .unwrap();
+        assert_eq!(updated.display_name, None);
+    }
+}
@@ -0,0 +1,185 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{instrument, warn};
+
+use crate::data::rate_limit_repository::{saturating_add, Bucket, RateLimitRepository};
+use crate::domain::error::BlogError;
+use crate::infrastructure::config::{BucketConfig, RateLimitConfig};
+
+impl From<BucketConfig> for Bucket {
+    fn from(cfg: BucketConfig) -> Self {
+        Bucket {
+            capacity: f64::from(cfg.burst),
+            refill_per_sec: f64::from(cfg.per_minute) / 60.0,
+        }
+    }
+}
+
+// Retry-After в целых секундах, не меньше одной.
+fn retry_after(wait: Duration) -> u64 {
+    wait.as_secs_f64().ceil().max(1.0) as u64
+}
+
+fn account_key(username: &str) -> String {
+    format!("account:{}", username.trim().to_lowercase())
+}
+
+// Защита входа и регистрации: token bucket на IP (middleware HTTP и gRPC) и на
+// аккаунт (AuthService::login), плюс прогрессивная блокировка аккаунта после
+// серии неверных паролей.
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitRepository>,
+    cfg: RateLimitConfig,
+}
+
+impl RateLimiter {
+    pub fn new(store: Arc<dyn RateLimitRepository>, cfg: RateLimitConfig) -> Self {
+        Self { store, cfg }
+    }
+
+    pub fn trust_proxy_headers(&self) -> bool {
+        self.cfg.trust_proxy_headers
+    }
+
+    #[instrument(skip(self))]
+    pub async fn check_ip(&self, ip: IpAddr) -> Result<(), BlogError> {
+        match self.store.take(&format!("ip:{ip}"), self.cfg.ip.into()).await? {
+            None => Ok(()),
+            Some(wait) => {
+                warn!(%ip, "rate limit exceeded");
+                Err(BlogError::RateLimited {
+                    retry_after: retry_after(wait),
+                })
+            }
+        }
+    }
+
+    // Вызывается до проверки пароля: заблокированный аккаунт не тратит время на
+    // хеширование и не даёт подбирать пароль.
+    pub async fn check_account(&self, username: &str) -> Result<(), BlogError> {
+        let key = account_key(username);
+        if let Some(until) = self.store.locked_until(&key).await? {
+            let wait = (until - Utc::now()).to_std().unwrap_or_default();
+            return Err(BlogError::AccountLocked {
+                retry_after: retry_after(wait),
+            });
+        }
+        match self.store.take(&key, self.cfg.account.into()).await? {
+            None => Ok(()),
+            Some(wait) => Err(BlogError::RateLimited {
+                retry_after: retry_after(wait),
+            }),
+        }
+    }
+
+    // Начиная с lockout_threshold ошибок подряд каждая следующая блокирует аккаунт,
+    // удваивая срок: base, 2·base, 4·base, ... но не дольше lockout_max.
+    pub async fn record_login_failure(&self, username: &str) -> Result<(), BlogError> {
+        let key = account_key(username);
+        let failures = self.store.record_failure(&key, self.cfg.lockout_max).await?;
+        if failures < self.cfg.lockout_threshold {
+            return Ok(());
+        }
+
+        let exponent = (failures - self.cfg.lockout_threshold).min(31);
+        let lockout = self
+            .cfg
+            .lockout_base
+            .saturating_mul(1 << exponent)
+            .min(self.cfg.lockout_max);
+        let until = saturating_add(Utc::now(), lockout);
+        warn!(key = %key, failures, lockout_secs = lockout.as_secs(), "account locked after failed logins");
+        self.store.lock(&key, until).await
+    }
+
+    pub async fn record_login_success(&self, username: &str) -> Result<(), BlogError> {
+        self.store.reset(&account_key(username)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::rate_limit_repository::InMemoryRateLimitRepository;
+    use crate::infrastructure::config::RateLimitBackend;
+
+    fn limiter() -> RateLimiter {
+        limiter_with_lockout(Duration::from_secs(30), Duration::from_secs(100))
+    }
+
+    fn limiter_with_lockout(base: Duration, max: Duration) -> RateLimiter {
+        RateLimiter::new(
+            Arc::new(InMemoryRateLimitRepository::new()),
+            RateLimitConfig {
+                backend: RateLimitBackend::Memory,
+                ip: BucketConfig { burst: 2, per_minute: 6 },
+                account: BucketConfig { burst: 100, per_minute: 100 },
+                lockout_threshold: 3,
+                lockout_base: base,
+                lockout_max: max,
+                trust_proxy_headers: false,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn ip_bucket_allows_burst_then_limits() {
+        let limiter = limiter();
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        limiter.check_ip(ip).await.unwrap();
+        limiter.check_ip(ip).await.unwrap();
+
+        // 6 в минуту: следующий токен через ~10 секунд.
+        match limiter.check_ip(ip).await {
+            Err(BlogError::RateLimited { retry_after }) => assert!((9..=10).contains(&retry_after)),
+            other => panic!("expected RateLimited, got {other:?}"),
+        }
+        limiter.check_ip("10.0.0.2".parse().unwrap()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn lockout_grows_and_resets_on_success() {
+        let limiter = limiter();
+        for _ in 0..2 {
+            limiter.record_login_failure("Alice").await.unwrap();
+        }
+        limiter.check_account("alice").await.unwrap();
+
+        limiter.record_login_failure("alice").await.unwrap();
+        match limiter.check_account("alice").await {
+            Err(BlogError::AccountLocked { retry_after }) => assert_eq!(retry_after, 30),
+            other => panic!("expected AccountLocked, got {other:?}"),
+        }
+
+        // Следующие ошибки удваивают срок, но не выше lockout_max.
+        limiter.record_login_failure("alice").await.unwrap();
+        assert!(matches!(
+            limiter.check_account("alice").await,
+            Err(BlogError::AccountLocked { retry_after: 60 })
+        ));
+        limiter.record_login_failure("alice").await.unwrap();
+        assert!(matches!(
+            limiter.check_account("alice").await,
+            Err(BlogError::AccountLocked { retry_after: 100 })
+        ));
+
+        limiter.record_login_success("alice").await.unwrap();
+        limiter.check_account("alice").await.unwrap();
+    }
+
+    // Срок блокировки за пределами DateTime<Utc> не должен ронять сервер.
+    #[tokio::test]
+    async fn huge_lockout_saturates_instead_of_panicking() {
+        let limiter = limiter_with_lockout(Duration::from_secs(u64::MAX / 4), Duration::MAX);
+        for _ in 0..4 {
+            limiter.record_login_failure("alice").await.unwrap();
+        }
+        assert!(matches!(
+            limiter.check_account("alice").await,
+            Err(BlogError::AccountLocked { .. })
+        ));
+    }
+}
@@ -0,0 +1,433 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::domain::error::{BlogError, FieldError};
+use crate::domain::user::{ChangePasswordRequest, LoginUser, RegisterUser, UpdateProfile, User};
+use crate::infrastructure::config::PasswordPolicyConfig;
+
+const USERNAME_MIN_LENGTH: usize = 3;
+const USERNAME_MAX_LENGTH: usize = 32;
+// Ограничения RFC 5321 на длину адреса и его локальной части.
+const EMAIL_MAX_LENGTH: usize = 254;
+const EMAIL_LOCAL_MAX_LENGTH: usize = 64;
+const EMAIL_LABEL_MAX_LENGTH: usize = 63;
+// Совпадают с размерами колонок users, кроме bio: там TEXT.
+const DISPLAY_NAME_MAX_LENGTH: usize = 64;
+const BIO_MAX_LENGTH: usize = 1000;
+const AVATAR_URL_MAX_LENGTH: usize = 512;
+
+static COMMON_PASSWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+    include_str!("../../resources/common-passwords.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+// NFKC сводит совместимые формы (полноширинные буквы, лигатуры, надстрочные цифры)
+// к обычным символам: "ａｌｉｃｅ" и "alice" становятся одним именем. Остальные
+// двойники (кириллическая "а" вместо латинской) отсекаются набором допустимых символов.
+pub(crate) fn normalize_username(username: &str) -> String {
+    username.nfkc().collect::<String>().trim().to_string()
+}
+
+fn check_username(username: &str) -> Option<FieldError> {
+    let len = username.chars().count();
+    if len == 0 {
+        return Some(FieldError::new("username", "required", "is required"));
+    }
+    if len < USERNAME_MIN_LENGTH {
+        return Some(FieldError::new(
+            "username",
+            "too_short",
+            format!("must be at least {USERNAME_MIN_LENGTH} characters"),
+        ));
+    }
+    if len > USERNAME_MAX_LENGTH {
+        return Some(FieldError::new(
+            "username",
+            "too_long",
+            format!("must be at most {USERNAME_MAX_LENGTH} characters"),
+        ));
+    }
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+    {
+        return Some(FieldError::new(
+            "username",
+            "invalid_characters",
+            "may contain only latin letters, digits, '_', '.' and '-'",
+        ));
+    }
+    let edge_ok = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    if !edge_ok(username.chars().next()) || !edge_ok(username.chars().last()) {
+        return Some(FieldError::new(
+            "username",
+            "invalid_format",
+            "must start and end with a letter or digit",
+        ));
+    }
+    None
+}
+
+fn valid_local_part(local: &str) -> bool {
+    const SPECIALS: &str = "!#$%&'*+-/=?^_`{|}~.";
+    !local.is_empty()
+        && local.len() <= EMAIL_LOCAL_MAX_LENGTH
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..")
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || SPECIALS.contains(c))
+}
+
+fn valid_domain(domain: &str) -> bool {
+    let labels: Vec<&str> = domain.split('.').collect();
+    let tld = labels.last().copied().unwrap_or_default();
+    labels.len() >= 2
+        && tld.len() >= 2
+        && tld.chars().all(|c| c.is_ascii_alphabetic())
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label.len() <= EMAIL_LABEL_MAX_LENGTH
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+// Синтаксис addr-spec без комментариев, quoted-string и IP-литералов: такие адреса
+// на практике не встречаются, а разбирать их дороже, чем отказать.
+fn check_email(email: &str) -> Option<FieldError> {
+    if email.is_empty() {
+        return Some(FieldError::new("email", "required", "is required"));
+    }
+    if email.len() > EMAIL_MAX_LENGTH {
+        return Some(FieldError::new(
+            "email",
+            "too_long",
+            format!("must be at most {EMAIL_MAX_LENGTH} characters"),
+        ));
+    }
+    match email.split_once('@') {
+        Some((local, domain)) if valid_local_part(local) && valid_domain(domain) => None,
+        _ => Some(FieldError::new("email", "invalid_format", "is not a valid email address")),
+    }
+}
+
+fn check_password(
+    field: &'static str,
+    password: &str,
+    username: &str,
+    email: &str,
+    policy: &PasswordPolicyConfig,
+) -> Option<FieldError> {
+    let len = password.chars().count();
+    if len < policy.min_length {
+        return Some(FieldError::new(
+            field,
+            "too_short",
+            format!("must be at least {} characters", policy.min_length),
+        ));
+    }
+    if len > policy.max_length {
+        return Some(FieldError::new(
+            field,
+            "too_long",
+            format!("must be at most {} characters", policy.max_length),
+        ));
+    }
+    let lower = password.to_lowercase();
+    if policy.reject_common && COMMON_PASSWORDS.contains(lower.as_str()) {
+        return Some(FieldError::new(
+            field,
+            "too_common",
+            "is too common, choose a less predictable password",
+        ));
+    }
+    let local = email.split('@').next().unwrap_or_default();
+    if lower == username.to_lowercase() || lower == email || lower == local {
+        return Some(FieldError::new(
+            field,
+            "too_similar",
+            "must not match the username or email",
+        ));
+    }
+    None
+}
+
+// Нормализует поля регистрации на месте и проверяет их все сразу, чтобы клиент
+// получил полный список ошибок одним ответом.
+pub(crate) fn validate_register(
+    input: &mut RegisterUser,
+    policy: &PasswordPolicyConfig,
+) -> Result<(), BlogError> {
+    input.username = normalize_username(&input.username);
+    input.email = input.email.trim().to_lowercase();
+
+    let errors: Vec<FieldError> = [
+        check_username(&input.username),
+        check_email(&input.email),
+        check_password("password", &input.password, &input.username, &input.email, policy),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(BlogError::InvalidFields(errors))
+    }
+}
+
+// При входе правила имени и пароля не применяются: они могли измениться после
+// регистрации. Проверяется только наличие полей и верхние границы длины: имя
+// входит в ключ счётчика попыток, rate_limit_buckets.key ограничен VARCHAR(320).
+pub(crate) fn validate_login(input: &mut LoginUser, policy: &PasswordPolicyConfig) -> Result<(), BlogError> {
+    input.username = normalize_username(&input.username);
+
+    let mut errors = Vec::new();
+    if input.username.is_empty() {
+        errors.push(FieldError::new("username", "required", "is required"));
+    } else if input.username.chars().count() > USERNAME_MAX_LENGTH {
+        errors.push(FieldError::new(
+            "username",
+            "too_long",
+            format!("must be at most {USERNAME_MAX_LENGTH} characters"),
+        ));
+    }
+    if input.password.is_empty() {
+        errors.push(FieldError::new("password", "required", "is required"));
+    } else if input.password.chars().count() > policy.max_length {
+        errors.push(FieldError::new(
+            "password",
+            "too_long",
+            format!("must be at most {} characters", policy.max_length),
+        ));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(BlogError::InvalidFields(errors))
+    }
+}
+
+// Новый пароль при сбросе проверяется той же политикой, что и при регистрации.
+pub(crate) fn validate_new_password(
+    password: &str,
+    user: &User,
+    policy: &PasswordPolicyConfig,
+) -> Result<(), BlogError> {
+    match check_password("new_password", password, &user.username, &user.email, policy) {
+        Some(error) => Err(BlogError::InvalidFields(vec![error])),
+        None => Ok(()),
+    }
+}
+
+// Верность текущего пароля проверяет сервис; здесь только наличие полей и политика.
+pub(crate) fn validate_change_password(
+    input: &ChangePasswordRequest,
+    user: &User,
+    policy: &PasswordPolicyConfig,
+) -> Result<(), BlogError> {
+    let mut errors = Vec::new();
+    if input.current_password.is_empty() {
+        errors.push(FieldError::new("current_password", "required", "is required"));
+    }
+    if let Some(error) = check_password(
+        "new_password",
+        &input.new_password,
+        &user.username,
+        &user.email,
+        policy,
+    ) {
+        errors.push(error);
+    } else if input.new_password == input.current_password {
+        errors.push(FieldError::new(
+            "new_password",
+            "unchanged",
+            "must differ from the current password",
+        ));
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(BlogError::InvalidFields(errors))
+    }
+}
+
+fn check_max_length(field: &'static str, value: Option<&str>, max: usize) -> Option<FieldError> {
+    value.filter(|v| v.chars().count() > max).map(|_| {
+        FieldError::new(field, "too_long", format!("must be at most {max} characters"))
+    })
+}
+
+// Аватар загружается браузером читателя, поэтому допускаются только http(s)-ссылки:
+// javascript: и data: отсекаются здесь, а не в каждом клиенте.
+fn check_avatar_url(url: &str) -> Option<FieldError> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+    let host = rest.and_then(|r| r.split(['/', '?', '#']).next()).unwrap_or_default();
+    if host.is_empty() || url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Some(FieldError::new("avatar_url", "invalid_format", "must be an http or https URL"));
+    }
+    None
+}
+
+// Обрезает пробелы и превращает пустые поля в `None`, затем проверяет длины.
+pub(crate) fn validate_profile(input: &mut UpdateProfile) -> Result<(), BlogError> {
+    for field in [&mut input.display_name, &mut input.bio, &mut input.avatar_url] {
+        *field = field
+            .take()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+    }
+
+    let mut errors: Vec<FieldError> = [
+        check_max_length("display_name", input.display_name.as_deref(), DISPLAY_NAME_MAX_LENGTH),
+        check_max_length("bio", input.bio.as_deref(), BIO_MAX_LENGTH),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if input
+        .display_name
+        .as_deref()
+        .is_some_and(|name| name.chars().any(char::is_control))
+    {
+        errors.push(FieldError::new(
+            "display_name",
+            "invalid_characters",
+            "must not contain control characters",
+        ));
+    }
+    if let Some(url) = input.avatar_url.as_deref() {
+        errors.extend(
+            check_max_length("avatar_url", Some(url), AVATAR_URL_MAX_LENGTH).or_else(|| check_avatar_url(url)),
+        );
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(BlogError::InvalidFields(errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> PasswordPolicyConfig {
+        PasswordPolicyConfig {
+            min_length: 8,
+            max_length: 64,
+            reject_common: true,
+        }
+    }
+
+    fn codes(input: RegisterUser) -> Vec<(&'static str, &'static str)> {
+        let mut input = input;
+        match validate_register(&mut input, &policy()) {
+            Ok(()) => Vec::new(),
+            Err(err) => err.field_errors().iter().map(|e| (e.field, e.code)).collect(),
+        }
+    }
+
+    fn register(username: &str, email: &str, password: &str) -> RegisterUser {
+        RegisterUser {
+            username: username.to_string(),
+            email: email.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    #[test]
+    fn normalizes_and_accepts_valid_input() {
+        let mut input = register(" ｉｖａｎ_p ", " Ivan@Example.COM ", "correct-horse-42");
+        validate_register(&mut input, &policy()).unwrap();
+        assert_eq!(input.username, "ivan_p");
+        assert_eq!(input.email, "ivan@example.com");
+    }
+
+    #[test]
+    fn reports_every_invalid_field() {
+        assert_eq!(
+            codes(register("", "not-an-email", "short")),
+            [
+                ("username", "required"),
+                ("email", "invalid_format"),
+                ("password", "too_short")
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_lookalike_and_malformed_usernames() {
+        // Кириллическая "а" визуально неотличима от латинской.
+        assert_eq!(codes(register("ivаn", "a@b.io", "correct-horse-42")), [("username", "invalid_characters")]);
+        assert_eq!(codes(register("_ivan", "a@b.io", "correct-horse-42")), [("username", "invalid_format")]);
+        assert_eq!(codes(register(&"x".repeat(33), "a@b.io", "correct-horse-42")), [("username", "too_long")]);
+    }
+
+    #[test]
+    fn login_checks_presence_and_upper_bounds_only() {
+        let check = |username: &str, password: &str| {
+            let mut input = LoginUser {
+                username: username.to_string(),
+                password: password.to_string(),
+            };
+            match validate_login(&mut input, &policy()) {
+                Ok(()) => Vec::new(),
+                Err(err) => err.field_errors().iter().map(|e| (e.field, e.code)).collect(),
+            }
+        };
+        // Старые имена, не проходящие нынешние правила, при входе допускаются.
+        assert!(check("_ivan", "x").is_empty());
+        assert_eq!(check(" ", ""), [("username", "required"), ("password", "required")]);
+        assert_eq!(
+            check(&"x".repeat(400), &"p".repeat(65)),
+            [("username", "too_long"), ("password", "too_long")]
+        );
+    }
+
+    #[test]
+    fn validates_email_syntax() {
+        for email in ["ivan@localhost", "ivan..p@example.com", "ivan@-example.com", "@example.com", "a@b@c.io"] {
+            assert_eq!(codes(register("ivan", email, "correct-horse-42")), [("email", "invalid_format")], "{email}");
+        }
+        assert!(codes(register("ivan", "ivan.p+blog@mail.example.co", "correct-horse-42")).is_empty());
+    }
+
+    #[test]
+    fn applies_password_policy() {
+        assert_eq!(codes(register("ivan", "a@b.io", "Password123")), [("password", "too_common")]);
+        assert_eq!(codes(register("ivan_petrov", "a@b.io", "IVAN_PETROV")), [("password", "too_similar")]);
+        assert_eq!(codes(register("ivan", "a@b.io", &"p".repeat(65))), [("password", "too_long")]);
+    }
+
+    #[test]
+    fn normalizes_and_validates_profile() {
+        let mut input = UpdateProfile {
+            display_name: Some("  Иван Петров ".to_string()),
+            bio: Some("   ".to_string()),
+            avatar_url: Some("https://cdn.example.com/a.png".to_string()),
+        };
+        validate_profile(&mut input).unwrap();
+        assert_eq!(input.display_name.as_deref(), Some("Иван Петров"));
+        assert_eq!(input.bio, None);
+
+        let mut input = UpdateProfile {
+            display_name: Some("x".repeat(65)),
+            bio: None,
+            avatar_url: Some("javascript:alert(1)".to_string()),
+        };
+        let err = validate_profile(&mut input).unwrap_err();
+        let codes: Vec<_> = err.field_errors().iter().map(|e| (e.field, e.code)).collect();
+        assert_eq!(codes, [("display_name", "too_long"), ("avatar_url", "invalid_format")]);
+    }
+}
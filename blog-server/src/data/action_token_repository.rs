@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::domain::error::BlogError;
+
+#[async_trait]
+pub trait ActionTokenRepository: Send + Sync {
+    // Запоминает выданный токен и аннулирует прежние токены пользователя с тем же
+    // назначением: действует только последняя ссылка.
+    async fn replace(
+        &self,
+        user_id: i64,
+        purpose: &str,
+        jti: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), BlogError>;
+    // Атомарно помечает токен использованным. `false` — токен неизвестен, истёк,
+    // уже использован или заменён более новым.
+    async fn consume(&self, jti: Uuid, user_id: i64, purpose: &str) -> Result<bool, BlogError>;
+}
+
+#[derive(Clone)]
+pub struct PostgresActionTokenRepository {
+    pool: PgPool,
+}
+
+impl PostgresActionTokenRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ActionTokenRepository for PostgresActionTokenRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "action_tokens"))]
+    async fn replace(
+        &self,
+        user_id: i64,
+        purpose: &str,
+        jti: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), BlogError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM action_tokens WHERE user_id = $1 AND purpose = $2")
+            .bind(user_id)
+            .bind(purpose)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO action_tokens (jti, user_id, purpose, expires_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+            .bind(jti)
+            .bind(user_id)
+            .bind(purpose)
+            .bind(expires_at)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "action_tokens"))]
+    async fn consume(&self, jti: Uuid, user_id: i64, purpose: &str) -> Result<bool, BlogError> {
+        let res = sqlx::query(
+            r#"
+            UPDATE action_tokens SET used_at = NOW()
+            WHERE jti = $1 AND user_id = $2 AND purpose = $3
+              AND used_at IS NULL AND expires_at > NOW()
+            "#,
+        )
+            .bind(jti)
+            .bind(user_id)
+            .bind(purpose)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(res.rows_affected() == 1)
+    }
+}
+
+struct ActionToken {
+    user_id: i64,
+    purpose: String,
+    expires_at: DateTime<Utc>,
+    used: bool,
+}
+
+#[derive(Default)]
+pub struct InMemoryActionTokenRepository {
+    tokens: RwLock<HashMap<Uuid, ActionToken>>,
+}
+
+impl InMemoryActionTokenRepository {
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ActionTokenRepository for InMemoryActionTokenRepository {
+    async fn replace(
+        &self,
+        user_id: i64,
+        purpose: &str,
+        jti: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), BlogError> {
+        let mut tokens = self.tokens.write().unwrap();
+        tokens.retain(|_, t| t.user_id != user_id || t.purpose != purpose);
+        tokens.insert(
+            jti,
+            ActionToken {
+                user_id,
+                purpose: purpose.to_string(),
+                expires_at,
+                used: false,
+            },
+        );
+        Ok(())
+    }
+
+    async fn consume(&self, jti: Uuid, user_id: i64, purpose: &str) -> Result<bool, BlogError> {
+        let mut tokens = self.tokens.write().unwrap();
+        match tokens.get_mut(&jti) {
+            Some(t) if t.user_id == user_id && t.purpose == purpose && !t.used && t.expires_at > Utc::now() => {
+                t.used = true;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use tracing::instrument;
+
+use crate::domain::audit::{AuditAction, AuditEvent, AuditQuery};
+use crate::domain::error::BlogError;
+
+// Куда сервисы пишут события аудита.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    async fn record(&self, event: AuditEvent) -> Result<(), BlogError>;
+}
+
+// Чтение журнала для администраторов. limit/offset в запросе уже нормализованы.
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    async fn list(&self, query: &AuditQuery, limit: i64, offset: i64) -> Result<Vec<AuditEvent>, BlogError>;
+    async fn count(&self, query: &AuditQuery) -> Result<i64, BlogError>;
+}
+
+#[derive(Clone)]
+pub struct PostgresAuditRepository {
+    pool: PgPool,
+}
+
+impl PostgresAuditRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+const AUDIT_FILTER: &str = r#"
+    ($1::BIGINT IS NULL OR actor_id = $1)
+    AND ($2::TEXT IS NULL OR action = $2)
+    AND ($3::TIMESTAMPTZ IS NULL OR created_at >= $3)
+    AND ($4::TIMESTAMPTZ IS NULL OR created_at < $4)
+"#;
+
+fn audit_event(r: &sqlx::postgres::PgRow) -> Result<AuditEvent, BlogError> {
+    let action: String = r.get("action");
+    Ok(AuditEvent {
+        id: r.get("id"),
+        action: action.parse().map_err(BlogError::Internal)?,
+        actor_id: r.get("actor_id"),
+        subject_id: r.get("subject_id"),
+        ip: r.get("ip"),
+        user_agent: r.get("user_agent"),
+        request_id: r.get("request_id"),
+        before_hash: r.get("before_hash"),
+        after_hash: r.get("after_hash"),
+        details: r.get("details"),
+        created_at: r.get("created_at"),
+    })
+}
+
+#[async_trait]
+impl AuditSink for PostgresAuditRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "audit_events"))]
+    async fn record(&self, event: AuditEvent) -> Result<(), BlogError> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_events
+                (action, actor_id, subject_id, ip, user_agent, request_id, before_hash, after_hash, details, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+        )
+            .bind(event.action.as_str())
+            .bind(event.actor_id)
+            .bind(event.subject_id)
+            .bind(&event.ip)
+            .bind(&event.user_agent)
+            .bind(&event.request_id)
+            .bind(&event.before_hash)
+            .bind(&event.after_hash)
+            .bind(&event.details)
+            .bind(event.created_at)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AuditRepository for PostgresAuditRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "audit_events"))]
+    async fn list(&self, query: &AuditQuery, limit: i64, offset: i64) -> Result<Vec<AuditEvent>, BlogError> {
+        let sql = format!(
+            r#"
+            SELECT id, action, actor_id, subject_id, ip, user_agent, request_id,
+                   before_hash, after_hash, details, created_at
+            FROM audit_events
+            WHERE {AUDIT_FILTER}
+            ORDER BY created_at DESC, id DESC
+            LIMIT $5 OFFSET $6
+            "#
+        );
+        let rows = sqlx::query(&sql)
+            .bind(query.actor_id)
+            .bind(query.action.map(AuditAction::as_str))
+            .bind(query.from)
+            .bind(query.to)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(audit_event).collect()
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "audit_events"))]
+    async fn count(&self, query: &AuditQuery) -> Result<i64, BlogError> {
+        let sql = format!("SELECT COUNT(*) FROM audit_events WHERE {AUDIT_FILTER}");
+        let total: i64 = sqlx::query_scalar(&sql)
+            .bind(query.actor_id)
+            .bind(query.action.map(AuditAction::as_str))
+            .bind(query.from)
+            .bind(query.to)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(total)
+    }
+}
+
+// Хранилище для unit-тестов сервисов без PostgreSQL.
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Default)]
+pub struct InMemoryAuditRepository {
+    events: Mutex<Vec<AuditEvent>>,
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+impl InMemoryAuditRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn all(&self) -> Vec<AuditEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    fn matching(&self, query: &AuditQuery) -> Vec<AuditEvent> {
+        let mut events: Vec<_> = self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| query.actor_id.is_none_or(|id| e.actor_id == Some(id)))
+            .filter(|e| query.action.is_none_or(|action| e.action == action))
+            .filter(|e| query.from.is_none_or(|from| e.created_at >= from))
+            .filter(|e| query.to.is_none_or(|to| e.created_at < to))
+            .cloned()
+            .collect();
+        events.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+        events
+    }
+}
+
+#[async_trait]
+impl AuditSink for InMemoryAuditRepository {
+    async fn record(&self, mut event: AuditEvent) -> Result<(), BlogError> {
+        // Как VARCHAR(128) в audit_events: слишком длинная строка не вставляется.
+        if event.request_id.as_ref().is_some_and(|id| id.chars().count() > 128) {
+            return Err(BlogError::Internal("audit_events.request_id is too long".into()));
+        }
+        let mut events = self.events.lock().unwrap();
+        event.id = events.len() as i64 + 1;
+        events.push(event);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AuditRepository for InMemoryAuditRepository {
+    async fn list(&self, query: &AuditQuery, limit: i64, offset: i64) -> Result<Vec<AuditEvent>, BlogError> {
+        Ok(self
+            .matching(query)
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
+    async fn count(&self, query: &AuditQuery) -> Result<i64, BlogError> {
+        Ok(self.matching(query).len() as i64)
+    }
+}
@@ -1,2 +1,8 @@
 pub(crate) mod user_repository;
-pub(crate) mod post_repository;
\ No newline at end of file
+pub(crate) mod post_repository;
+pub(crate) mod refresh_token_repository;
+pub(crate) mod revocation_repository;
+pub(crate) mod rate_limit_repository;
+pub(crate) mod action_token_repository;
+pub(crate) mod password_reset_repository;
+pub(crate) mod audit_repository;
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use tracing::instrument;
+
+use crate::domain::error::BlogError;
+
+#[async_trait]
+pub trait PasswordResetRepository: Send + Sync {
+    // Сохраняет хеш нового токена и удаляет прежние токены пользователя:
+    // действует только ссылка из последнего письма.
+    async fn replace(
+        &self,
+        user_id: i64,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), BlogError>;
+    // Владелец действующего токена; токен при этом не гасится.
+    async fn find_user(&self, token_hash: &str) -> Result<Option<i64>, BlogError>;
+    // Атомарно гасит действующий токен и возвращает его владельца. `None` — токен
+    // неизвестен, истёк или уже использован.
+    async fn consume(&self, token_hash: &str) -> Result<Option<i64>, BlogError>;
+    async fn delete_for_user(&self, user_id: i64) -> Result<(), BlogError>;
+}
+
+#[derive(Clone)]
+pub struct PostgresPasswordResetRepository {
+    pool: PgPool,
+}
+
+impl PostgresPasswordResetRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for PostgresPasswordResetRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "password_reset_tokens"))]
+    async fn replace(
+        &self,
+        user_id: i64,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), BlogError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM password_reset_tokens WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO password_reset_tokens (user_id, token_hash, expires_at)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+            .bind(user_id)
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "password_reset_tokens"))]
+    async fn find_user(&self, token_hash: &str) -> Result<Option<i64>, BlogError> {
+        let row = sqlx::query(
+            r#"
+            SELECT user_id FROM password_reset_tokens
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+            "#,
+        )
+            .bind(token_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.get("user_id")))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "password_reset_tokens"))]
+    async fn consume(&self, token_hash: &str) -> Result<Option<i64>, BlogError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE password_reset_tokens
+            SET used_at = NOW()
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > NOW()
+            RETURNING user_id
+            "#,
+        )
+            .bind(token_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.get("user_id")))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "password_reset_tokens"))]
+    async fn delete_for_user(&self, user_id: i64) -> Result<(), BlogError> {
+        sqlx::query("DELETE FROM password_reset_tokens WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+struct StoredResetToken {
+    user_id: i64,
+    expires_at: DateTime<Utc>,
+    used: bool,
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Default)]
+pub struct InMemoryPasswordResetRepository {
+    tokens: RwLock<HashMap<String, StoredResetToken>>,
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+impl InMemoryPasswordResetRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PasswordResetRepository for InMemoryPasswordResetRepository {
+    async fn replace(
+        &self,
+        user_id: i64,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), BlogError> {
+        let mut tokens = self.tokens.write().unwrap();
+        tokens.retain(|_, t| t.user_id != user_id);
+        tokens.insert(
+            token_hash.to_string(),
+            StoredResetToken {
+                user_id,
+                expires_at,
+                used: false,
+            },
+        );
+        Ok(())
+    }
+
+    async fn find_user(&self, token_hash: &str) -> Result<Option<i64>, BlogError> {
+        Ok(self
+            .tokens
+            .read()
+            .unwrap()
+            .get(token_hash)
+            .filter(|t| !t.used && t.expires_at > Utc::now())
+            .map(|t| t.user_id))
+    }
+
+    async fn consume(&self, token_hash: &str) -> Result<Option<i64>, BlogError> {
+        let mut tokens = self.tokens.write().unwrap();
+        match tokens.get_mut(token_hash) {
+            Some(token) if !token.used && token.expires_at > Utc::now() => {
+                token.used = true;
+                Ok(Some(token.user_id))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<(), BlogError> {
+        self.tokens
+            .write()
+            .unwrap()
+            .retain(|_, t| t.user_id != user_id);
+        Ok(())
+    }
+}
@@ -1,7 +1,40 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
 use sqlx::{PgPool, Row};
-use uuid::Uuid;
+use tracing::instrument;
 use chrono::{DateTime, Utc};
 
+use crate::domain::error::BlogError;
+use crate::domain::post::{CursorDirection, Post, PostCursor, PostFilter, PostStatus};
+use crate::data::user_repository::InMemoryUserRepository;
+use crate::domain::user::AuthorSummary;
+
+#[async_trait]
+pub trait PostRepository: Send + Sync {
+    async fn create(&self, post: Post) -> Result<Post, BlogError>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<Post>, BlogError>;
+    async fn update(&self, post: Post) -> Result<Post, BlogError>;
+    async fn delete(&self, id: i64) -> Result<(), BlogError>;
+    // Скрывает пост модератором или возвращает его; `None` — поста нет.
+    async fn set_hidden(&self, id: i64, hidden: bool) -> Result<Option<Post>, BlogError>;
+    // Ленты в порядке (feed_at DESC, id DESC), см. Post::feed_at.
+    async fn list(&self, filter: &PostFilter, limit: i64, offset: i64) -> Result<Vec<Post>, BlogError>;
+    async fn count(&self, filter: &PostFilter) -> Result<i64, BlogError>;
+    // Keyset-выборка: посты строго после курсора в его направлении,
+    // всегда в порядке ленты.
+    async fn list_by_cursor(
+        &self,
+        filter: &PostFilter,
+        cursor: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<Vec<Post>, BlogError>;
+    // Публикует запланированные посты, время которых не позже `now`, и возвращает их.
+    // Каждый пост публикуется ровно один раз, даже если планировщиков несколько.
+    async fn publish_due(&self, now: DateTime<Utc>) -> Result<Vec<Post>, BlogError>;
+}
 
 #[derive(Debug)]
 struct PostRow {
@@ -11,75 +44,449 @@ struct PostRow {
     author_id: i64,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    hidden_at: Option<DateTime<Utc>>,
+    status: String,
+    published_at: Option<DateTime<Utc>>,
+    author_username: String,
+    author_display_name: Option<String>,
+    author_avatar_url: Option<String>,
 }
 
-async fn add_post(
-    pool: &PgPool,
-    title: &str,
-    content: &str,
-    author_id: i64
-) -> Result<(), sqlx::Error> {
-    sqlx::query(
-        r#"
-        INSERT INTO post (title, content, author_id)
-        VALUES ($1, $2, $3)
-        "#,
-    )
-        .bind(title)
-        .bind(content)
-        .bind(author_id)
-        .execute(pool)
-        .await?;
-    Ok(())
+impl From<PostRow> for Post {
+    fn from(row: PostRow) -> Self {
+        Post {
+            id: row.id,
+            title: row.title,
+            content: row.content,
+            author_id: row.author_id,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            hidden_at: row.hidden_at,
+            // Значения ограничены CHECK в БД; неизвестный статус не показывается читателям.
+            status: row.status.parse().unwrap_or(PostStatus::Draft),
+            published_at: row.published_at,
+            author: Some(AuthorSummary {
+                id: row.author_id,
+                username: row.author_username,
+                display_name: row.author_display_name,
+                avatar_url: row.author_avatar_url,
+            }),
+        }
+    }
 }
 
-async fn get_post(pool: &PgPool, id: i64) -> Result<Option<PostRow>, sqlx::Error> {
-    let row = sqlx::query(
-        r#"
-        SELECT id, title, content, author_id, created_at, updated_at
-        FROM post
-        WHERE id = $1
-        "#,
-    )
-        .bind(id)
-        .fetch_optional(pool)
-        .await?;
-
-    Ok(row.map(|r| PostRow {
+fn post_row(r: &sqlx::postgres::PgRow) -> PostRow {
+    PostRow {
         id: r.get("id"),
         title: r.get("title"),
         content: r.get("content"),
         author_id: r.get("author_id"),
         created_at: r.get("created_at"),
-        updated_at: r.get("updated_at")
-    }))
+        updated_at: r.get("updated_at"),
+        hidden_at: r.get("hidden_at"),
+        status: r.get("status"),
+        published_at: r.get("published_at"),
+        author_username: r.get("author_username"),
+        author_display_name: r.get("author_display_name"),
+        author_avatar_url: r.get("author_avatar_url")
+    }
+}
+
+// Колонки поста вместе с подписью автора. Запросы читают `posts p JOIN users u`,
+// а изменяющие оборачивают RETURNING в CTE с именем `p`, чтобы вернуть то же самое.
+const POST_COLUMNS: &str = r#"
+    p.id, p.title, p.content, p.author_id, p.created_at, p.updated_at, p.hidden_at,
+    p.status, p.published_at,
+    u.username AS author_username, u.display_name AS author_display_name,
+    u.avatar_url AS author_avatar_url
+"#;
+
+// Условие PostFilter: $1 — статус, $2 — автор. Выражение FEED_AT совпадает
+// с индексами idx_posts_feed и idx_posts_author_id.
+const POST_FILTER: &str = r#"
+    p.hidden_at IS NULL
+    AND p.status = $1
+    AND ($2::BIGINT IS NULL OR p.author_id = $2)
+"#;
+const FEED_AT: &str = "COALESCE(p.published_at, p.created_at)";
+
+#[derive(Clone)]
+pub struct PostgresPostRepository {
+    pool: PgPool,
+}
+
+impl PostgresPostRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PostRepository for PostgresPostRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "posts"))]
+    async fn create(&self, post: Post) -> Result<Post, BlogError> {
+        let sql = format!(
+            r#"
+            WITH p AS (
+                INSERT INTO posts (title, content, author_id, status, published_at)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING *
+            )
+            SELECT {POST_COLUMNS}
+            FROM p JOIN users u ON u.id = p.author_id
+            "#
+        );
+        let row = sqlx::query(&sql)
+            .bind(&post.title)
+            .bind(&post.content)
+            .bind(post.author_id)
+            .bind(post.status.as_str())
+            .bind(post.published_at)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(post_row(&row).into())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "posts"))]
+    async fn find_by_id(&self, id: i64) -> Result<Option<Post>, BlogError> {
+        let sql = format!(
+            r#"
+            SELECT {POST_COLUMNS}
+            FROM posts p JOIN users u ON u.id = p.author_id
+            WHERE p.id = $1
+            "#
+        );
+        let row = sqlx::query(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| post_row(&r).into()))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "posts"))]
+    async fn update(&self, post: Post) -> Result<Post, BlogError> {
+        let sql = format!(
+            r#"
+            WITH p AS (
+                UPDATE posts
+                SET title = $2, content = $3, status = $4, published_at = $5, updated_at = NOW()
+                WHERE id = $1
+                RETURNING *
+            )
+            SELECT {POST_COLUMNS}
+            FROM p JOIN users u ON u.id = p.author_id
+            "#
+        );
+        let row = sqlx::query(&sql)
+            .bind(post.id)
+            .bind(&post.title)
+            .bind(&post.content)
+            .bind(post.status.as_str())
+            .bind(post.published_at)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|r| post_row(&r).into())
+            .ok_or(BlogError::PostNotFound)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "posts"))]
+    async fn delete(&self, id: i64) -> Result<(), BlogError> {
+        let res = sqlx::query(
+            r#"
+            DELETE FROM posts
+            WHERE id = $1
+            "#,
+        )
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if res.rows_affected() == 0 {
+            return Err(BlogError::PostNotFound);
+        }
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "posts"))]
+    async fn set_hidden(&self, id: i64, hidden: bool) -> Result<Option<Post>, BlogError> {
+        let sql = format!(
+            r#"
+            WITH p AS (
+                UPDATE posts
+                SET hidden_at = CASE WHEN $2 THEN COALESCE(hidden_at, NOW()) END
+                WHERE id = $1
+                RETURNING *
+            )
+            SELECT {POST_COLUMNS}
+            FROM p JOIN users u ON u.id = p.author_id
+            "#
+        );
+        let row = sqlx::query(&sql)
+            .bind(id)
+            .bind(hidden)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| post_row(&r).into()))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "posts"))]
+    async fn list(&self, filter: &PostFilter, limit: i64, offset: i64) -> Result<Vec<Post>, BlogError> {
+        let sql = format!(
+            r#"
+            SELECT {POST_COLUMNS}
+            FROM posts p JOIN users u ON u.id = p.author_id
+            WHERE {POST_FILTER}
+            ORDER BY {FEED_AT} DESC, p.id DESC
+            LIMIT $3 OFFSET $4
+            "#
+        );
+        let rows = sqlx::query(&sql)
+            .bind(filter.status.as_str())
+            .bind(filter.author_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|r| post_row(r).into()).collect())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "posts"))]
+    async fn count(&self, filter: &PostFilter) -> Result<i64, BlogError> {
+        let sql = format!("SELECT COUNT(*) FROM posts p WHERE {POST_FILTER}");
+        let total: i64 = sqlx::query_scalar(&sql)
+            .bind(filter.status.as_str())
+            .bind(filter.author_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(total)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "posts"))]
+    async fn list_by_cursor(
+        &self,
+        filter: &PostFilter,
+        cursor: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<Vec<Post>, BlogError> {
+        let rows = match cursor {
+            None => {
+                let sql = format!(
+                    r#"
+                    SELECT {POST_COLUMNS}
+                    FROM posts p JOIN users u ON u.id = p.author_id
+                    WHERE {POST_FILTER}
+                    ORDER BY {FEED_AT} DESC, p.id DESC
+                    LIMIT $3
+                    "#
+                );
+                sqlx::query(&sql)
+                    .bind(filter.status.as_str())
+                    .bind(filter.author_id)
+                    .bind(limit)
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+            Some(c) if c.direction == CursorDirection::Next => {
+                let sql = format!(
+                    r#"
+                    SELECT {POST_COLUMNS}
+                    FROM posts p JOIN users u ON u.id = p.author_id
+                    WHERE {POST_FILTER} AND ({FEED_AT}, p.id) < ($3, $4)
+                    ORDER BY {FEED_AT} DESC, p.id DESC
+                    LIMIT $5
+                    "#
+                );
+                sqlx::query(&sql)
+                    .bind(filter.status.as_str())
+                    .bind(filter.author_id)
+                    .bind(c.feed_at)
+                    .bind(c.id)
+                    .bind(limit)
+                    .fetch_all(&self.pool)
+                    .await?
+            }
+            Some(c) => {
+                let sql = format!(
+                    r#"
+                    SELECT {POST_COLUMNS}
+                    FROM posts p JOIN users u ON u.id = p.author_id
+                    WHERE {POST_FILTER} AND ({FEED_AT}, p.id) > ($3, $4)
+                    ORDER BY {FEED_AT} ASC, p.id ASC
+                    LIMIT $5
+                    "#
+                );
+                let mut rows = sqlx::query(&sql)
+                    .bind(filter.status.as_str())
+                    .bind(filter.author_id)
+                    .bind(c.feed_at)
+                    .bind(c.id)
+                    .bind(limit)
+                    .fetch_all(&self.pool)
+                    .await?;
+                rows.reverse();
+                rows
+            }
+        };
+
+        Ok(rows.iter().map(|r| post_row(r).into()).collect())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "posts"))]
+    async fn publish_due(&self, now: DateTime<Utc>) -> Result<Vec<Post>, BlogError> {
+        let sql = format!(
+            r#"
+            WITH p AS (
+                UPDATE posts
+                SET status = 'published'
+                WHERE status = 'scheduled' AND published_at <= $1
+                RETURNING *
+            )
+            SELECT {POST_COLUMNS}
+            FROM p JOIN users u ON u.id = p.author_id
+            "#
+        );
+        let rows = sqlx::query(&sql)
+            .bind(now)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|r| post_row(r).into()).collect())
+    }
 }
 
-async fn update_post(pool: &PgPool, id: i64, title: &str, content: &str) -> Result<(), sqlx::Error> {
-    sqlx::query(
-        r#"
-        UPDATE post
-        SET title = $2, content = $3, updated_at = NOW()
-        WHERE id = $1
-        "#,
-    )
-        .bind(id)
-        .bind(title)
-        .bind(content)
-        .execute(pool)
-        .await?;
-    Ok(())
+// Хранилище для unit-тестов сервисов без PostgreSQL.
+// Подпись автора, как и JOIN users в PostgreSQL, берётся из пользователей при
+// каждом чтении, поэтому смена профиля сразу видна в постах.
+#[cfg_attr(not(test), allow(dead_code))]
+pub struct InMemoryPostRepository {
+    posts: RwLock<HashMap<i64, Post>>,
+    users: Arc<InMemoryUserRepository>,
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+impl InMemoryPostRepository {
+    pub fn new(users: Arc<InMemoryUserRepository>) -> Self {
+        Self {
+            posts: RwLock::default(),
+            users,
+        }
+    }
+
+    fn with_author(&self, mut post: Post) -> Post {
+        post.author = self.users.author(post.author_id);
+        post
+    }
+
+    fn matching(&self, filter: &PostFilter) -> Vec<Post> {
+        let mut posts: Vec<Post> = self
+            .posts
+            .read()
+            .unwrap()
+            .values()
+            .filter(|p| filter.matches(p))
+            .map(|p| self.with_author(p.clone()))
+            .collect();
+        posts.sort_by_key(|p| Reverse((p.feed_at(), p.id)));
+        posts
+    }
 }
 
-async fn delete_post(pool: &PgPool, id: i64) -> Result<(), sqlx::Error> {
-    sqlx::query(
-        r#"
-        DELETE post
-        WHERE id = $1
-        "#,
-    )
-        .bind(id)
-        .execute(pool)
-        .await?;
-    Ok(())
-}
\ No newline at end of file
+#[async_trait]
+impl PostRepository for InMemoryPostRepository {
+    async fn create(&self, mut post: Post) -> Result<Post, BlogError> {
+        // Как внешний ключ posts.author_id.
+        if self.users.author(post.author_id).is_none() {
+            return Err(BlogError::UserNotFound);
+        }
+        let mut posts = self.posts.write().unwrap();
+        post.id = posts.keys().max().copied().unwrap_or(0) + 1;
+        posts.insert(post.id, post.clone());
+        Ok(self.with_author(post))
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Post>, BlogError> {
+        let post = self.posts.read().unwrap().get(&id).cloned();
+        Ok(post.map(|p| self.with_author(p)))
+    }
+
+    async fn update(&self, mut post: Post) -> Result<Post, BlogError> {
+        let mut posts = self.posts.write().unwrap();
+        let stored = posts.get_mut(&post.id).ok_or(BlogError::PostNotFound)?;
+        post.updated_at = Utc::now();
+        *stored = post.clone();
+        Ok(self.with_author(post))
+    }
+
+    async fn delete(&self, id: i64) -> Result<(), BlogError> {
+        self.posts
+            .write()
+            .unwrap()
+            .remove(&id)
+            .map(|_| ())
+            .ok_or(BlogError::PostNotFound)
+    }
+
+    async fn set_hidden(&self, id: i64, hidden: bool) -> Result<Option<Post>, BlogError> {
+        let mut posts = self.posts.write().unwrap();
+        Ok(posts.get_mut(&id).map(|post| {
+            post.hidden_at = if hidden { post.hidden_at.or(Some(Utc::now())) } else { None };
+            self.with_author(post.clone())
+        }))
+    }
+
+    async fn list(&self, filter: &PostFilter, limit: i64, offset: i64) -> Result<Vec<Post>, BlogError> {
+        Ok(self
+            .matching(filter)
+            .into_iter()
+            .skip(offset.max(0) as usize)
+            .take(limit.max(0) as usize)
+            .collect())
+    }
+
+    async fn count(&self, filter: &PostFilter) -> Result<i64, BlogError> {
+        Ok(self.matching(filter).len() as i64)
+    }
+
+    async fn list_by_cursor(
+        &self,
+        filter: &PostFilter,
+        cursor: Option<PostCursor>,
+        limit: i64,
+    ) -> Result<Vec<Post>, BlogError> {
+        let posts = self.matching(filter);
+        let limit = limit.max(0) as usize;
+        Ok(match cursor {
+            None => posts.into_iter().take(limit).collect(),
+            Some(c) if c.direction == CursorDirection::Next => posts
+                .into_iter()
+                .filter(|p| (p.feed_at(), p.id) < (c.feed_at, c.id))
+                .take(limit)
+                .collect(),
+            Some(c) => {
+                let newer: Vec<Post> = posts
+                    .into_iter()
+                    .filter(|p| (p.feed_at(), p.id) > (c.feed_at, c.id))
+                    .collect();
+                let skip = newer.len().saturating_sub(limit);
+                newer.into_iter().skip(skip).collect()
+            }
+        })
+    }
+
+    async fn publish_due(&self, now: DateTime<Utc>) -> Result<Vec<Post>, BlogError> {
+        let mut posts = self.posts.write().unwrap();
+        Ok(posts
+            .values_mut()
+            .filter(|p| p.status == PostStatus::Scheduled && p.published_at.is_some_and(|at| at <= now))
+            .map(|post| {
+                post.status = PostStatus::Published;
+                self.with_author(post.clone())
+            })
+            .collect())
+    }
+}
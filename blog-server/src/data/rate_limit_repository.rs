@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use tracing::instrument;
+
+use crate::domain::error::BlogError;
+
+// При таком числе корзин в памяти полностью восстановившиеся удаляются:
+// их состояние совпадает с состоянием новой корзины.
+const MAX_IDLE_BUCKETS: usize = 10_000;
+
+// `at + duration` без паники на переполнении: слишком далёкий момент заменяется
+// максимальным, то есть "никогда".
+pub(crate) fn saturating_add(at: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(duration)
+        .ok()
+        .and_then(|duration| at.checked_add_signed(duration))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+// Параметры token bucket: не больше `capacity` запросов подряд,
+// дальше `refill_per_sec` в секунду.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bucket {
+    pub capacity: f64,
+    pub refill_per_sec: f64,
+}
+
+impl Bucket {
+    // Сколько ждать до следующего токена при `tokens` в корзине.
+    fn wait(&self, tokens: f64) -> Duration {
+        Duration::from_secs_f64(((1.0 - tokens) / self.refill_per_sec).max(0.0))
+    }
+}
+
+#[async_trait]
+pub trait RateLimitRepository: Send + Sync {
+    // Забирает токен из корзины `key`: None — запрос разрешён,
+    // Some(wait) — корзина пуста и следующий токен появится через wait.
+    async fn take(&self, key: &str, bucket: Bucket) -> Result<Option<Duration>, BlogError>;
+    async fn locked_until(&self, key: &str) -> Result<Option<DateTime<Utc>>, BlogError>;
+    // Увеличивает счётчик неудачных входов и возвращает его. Счёт начинается заново,
+    // если предыдущая ошибка была раньше, чем `window` назад.
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, BlogError>;
+    async fn lock(&self, key: &str, until: DateTime<Utc>) -> Result<(), BlogError>;
+    // Успешный вход: сбрасывает счётчик ошибок и блокировку.
+    async fn reset(&self, key: &str) -> Result<(), BlogError>;
+}
+
+#[derive(Clone)]
+pub struct PostgresRateLimitRepository {
+    pool: PgPool,
+}
+
+impl PostgresRateLimitRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RateLimitRepository for PostgresRateLimitRepository {
+    // Пополнение и списание одним UPSERT, чтобы параллельные запросы разных
+    // экземпляров не списали один и тот же токен.
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "rate_limit_buckets"))]
+    async fn take(&self, key: &str, bucket: Bucket) -> Result<Option<Duration>, BlogError> {
+        let taken = sqlx::query(
+            r#"
+            INSERT INTO rate_limit_buckets (key, tokens, updated_at)
+            VALUES ($1, $2 - 1, NOW())
+            ON CONFLICT (key) DO UPDATE SET
+                tokens = LEAST($2, rate_limit_buckets.tokens
+                    + EXTRACT(EPOCH FROM NOW() - rate_limit_buckets.updated_at)::float8 * $3) - 1,
+                updated_at = NOW()
+            WHERE LEAST($2, rate_limit_buckets.tokens
+                + EXTRACT(EPOCH FROM NOW() - rate_limit_buckets.updated_at)::float8 * $3) >= 1
+            RETURNING tokens
+            "#,
+        )
+            .bind(key)
+            .bind(bucket.capacity)
+            .bind(bucket.refill_per_sec)
+            .fetch_optional(&self.pool)
+            .await?;
+        if taken.is_some() {
+            return Ok(None);
+        }
+
+        let row = sqlx::query(
+            r#"
+            SELECT LEAST($2, tokens + EXTRACT(EPOCH FROM NOW() - updated_at)::float8 * $3) AS tokens
+            FROM rate_limit_buckets
+            WHERE key = $1
+            "#,
+        )
+            .bind(key)
+            .bind(bucket.capacity)
+            .bind(bucket.refill_per_sec)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(Some(bucket.wait(row.get("tokens"))))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "login_failures"))]
+    async fn locked_until(&self, key: &str) -> Result<Option<DateTime<Utc>>, BlogError> {
+        let row = sqlx::query(
+            "SELECT locked_until FROM login_failures WHERE key = $1 AND locked_until > NOW()",
+        )
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.get("locked_until")))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "login_failures"))]
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, BlogError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO login_failures (key, failures, last_failure_at)
+            VALUES ($1, 1, NOW())
+            ON CONFLICT (key) DO UPDATE SET
+                failures = CASE
+                    WHEN login_failures.last_failure_at < NOW() - make_interval(secs => $2) THEN 1
+                    ELSE login_failures.failures + 1
+                END,
+                last_failure_at = NOW()
+            RETURNING failures
+            "#,
+        )
+            .bind(key)
+            .bind(window.as_secs_f64())
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.get::<i32, _>("failures").max(0) as u32)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "login_failures"))]
+    async fn lock(&self, key: &str, until: DateTime<Utc>) -> Result<(), BlogError> {
+        sqlx::query("UPDATE login_failures SET locked_until = $2 WHERE key = $1")
+            .bind(key)
+            .bind(until)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "login_failures"))]
+    async fn reset(&self, key: &str) -> Result<(), BlogError> {
+        sqlx::query("DELETE FROM login_failures WHERE key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+struct BucketState {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+struct FailureState {
+    failures: u32,
+    last_failure_at: DateTime<Utc>,
+    locked_until: Option<DateTime<Utc>>,
+}
+
+// Счётчики в памяти процесса: у каждого экземпляра сервера свои.
+#[derive(Default)]
+pub struct InMemoryRateLimitRepository {
+    buckets: Mutex<HashMap<String, BucketState>>,
+    failures: Mutex<HashMap<String, FailureState>>,
+}
+
+impl InMemoryRateLimitRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitRepository for InMemoryRateLimitRepository {
+    async fn take(&self, key: &str, bucket: Bucket) -> Result<Option<Duration>, BlogError> {
+        let now = Instant::now();
+        let refilled = |state: &BucketState| {
+            let elapsed = now.duration_since(state.updated_at).as_secs_f64();
+            (state.tokens + elapsed * bucket.refill_per_sec).min(bucket.capacity)
+        };
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_IDLE_BUCKETS {
+            buckets.retain(|_, state| refilled(state) < bucket.capacity);
+        }
+        let state = buckets.entry(key.to_string()).or_insert(BucketState {
+            tokens: bucket.capacity,
+            updated_at: now,
+        });
+        let tokens = refilled(state);
+        state.updated_at = now;
+        if tokens >= 1.0 {
+            state.tokens = tokens - 1.0;
+            Ok(None)
+        } else {
+            state.tokens = tokens;
+            Ok(Some(bucket.wait(tokens)))
+        }
+    }
+
+    async fn locked_until(&self, key: &str) -> Result<Option<DateTime<Utc>>, BlogError> {
+        let now = Utc::now();
+        Ok(self
+            .failures
+            .lock()
+            .unwrap()
+            .get(key)
+            .and_then(|state| state.locked_until)
+            .filter(|until| *until > now))
+    }
+
+    async fn record_failure(&self, key: &str, window: Duration) -> Result<u32, BlogError> {
+        let now = Utc::now();
+        let mut failures = self.failures.lock().unwrap();
+        // Давно не ошибавшиеся ключи больше не нужны — чистим по пути.
+        failures.retain(|_, state| {
+            saturating_add(state.last_failure_at, window) > now || state.locked_until.is_some_and(|u| u > now)
+        });
+        let state = failures.entry(key.to_string()).or_insert(FailureState {
+            failures: 0,
+            last_failure_at: now,
+            locked_until: None,
+        });
+        state.failures += 1;
+        state.last_failure_at = now;
+        Ok(state.failures)
+    }
+
+    async fn lock(&self, key: &str, until: DateTime<Utc>) -> Result<(), BlogError> {
+        if let Some(state) = self.failures.lock().unwrap().get_mut(key) {
+            state.locked_until = Some(until);
+        }
+        Ok(())
+    }
+
+    async fn reset(&self, key: &str) -> Result<(), BlogError> {
+        self.failures.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
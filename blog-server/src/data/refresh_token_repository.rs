@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{PgPool, Row};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::domain::error::BlogError;
+use crate::domain::token::RefreshToken;
+
+#[async_trait]
+pub trait RefreshTokenRepository: Send + Sync {
+    async fn create(&self, token: RefreshToken) -> Result<RefreshToken, BlogError>;
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<RefreshToken>, BlogError>;
+    // Атомарно помечает токен использованным. `false` — токен уже был использован
+    // или отозван (например, параллельным запросом), ротацию выполнять нельзя.
+    async fn mark_used(&self, id: i64) -> Result<bool, BlogError>;
+    async fn revoke_family(&self, family_id: Uuid) -> Result<(), BlogError>;
+    async fn revoke_all_for_user(&self, user_id: i64) -> Result<(), BlogError>;
+}
+
+fn refresh_token(r: &sqlx::postgres::PgRow) -> RefreshToken {
+    RefreshToken {
+        id: r.get("id"),
+        user_id: r.get("user_id"),
+        family_id: r.get("family_id"),
+        token_hash: r.get("token_hash"),
+        expires_at: r.get("expires_at"),
+        created_at: r.get("created_at"),
+        used_at: r.get("used_at"),
+        revoked_at: r.get("revoked_at"),
+    }
+}
+
+#[derive(Clone)]
+pub struct PostgresRefreshTokenRepository {
+    pool: PgPool,
+}
+
+impl PostgresRefreshTokenRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RefreshTokenRepository for PostgresRefreshTokenRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "refresh_tokens"))]
+    async fn create(&self, token: RefreshToken) -> Result<RefreshToken, BlogError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (user_id, family_id, token_hash, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, family_id, token_hash, expires_at, created_at, used_at, revoked_at
+            "#,
+        )
+            .bind(token.user_id)
+            .bind(token.family_id)
+            .bind(&token.token_hash)
+            .bind(token.expires_at)
+            .bind(token.created_at)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(refresh_token(&row))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "refresh_tokens"))]
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<RefreshToken>, BlogError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, user_id, family_id, token_hash, expires_at, created_at, used_at, revoked_at
+            FROM refresh_tokens
+            WHERE token_hash = $1
+            "#,
+        )
+            .bind(token_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| refresh_token(&r)))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "refresh_tokens"))]
+    async fn mark_used(&self, id: i64) -> Result<bool, BlogError> {
+        let res = sqlx::query(
+            r#"
+            UPDATE refresh_tokens
+            SET used_at = NOW()
+            WHERE id = $1 AND used_at IS NULL AND revoked_at IS NULL
+            "#,
+        )
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(res.rows_affected() == 1)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "refresh_tokens"))]
+    async fn revoke_family(&self, family_id: Uuid) -> Result<(), BlogError> {
+        sqlx::query(
+            r#"
+            UPDATE refresh_tokens
+            SET revoked_at = NOW()
+            WHERE family_id = $1 AND revoked_at IS NULL
+            "#,
+        )
+            .bind(family_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "refresh_tokens"))]
+    async fn revoke_all_for_user(&self, user_id: i64) -> Result<(), BlogError> {
+        sqlx::query(
+            r#"
+            UPDATE refresh_tokens
+            SET revoked_at = NOW()
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+        )
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Default)]
+pub struct InMemoryRefreshTokenRepository {
+    tokens: RwLock<HashMap<i64, RefreshToken>>,
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+impl InMemoryRefreshTokenRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RefreshTokenRepository for InMemoryRefreshTokenRepository {
+    async fn create(&self, mut token: RefreshToken) -> Result<RefreshToken, BlogError> {
+        let mut tokens = self.tokens.write().unwrap();
+        token.id = tokens.keys().max().copied().unwrap_or(0) + 1;
+        tokens.insert(token.id, token.clone());
+        Ok(token)
+    }
+
+    async fn find_by_hash(&self, token_hash: &str) -> Result<Option<RefreshToken>, BlogError> {
+        Ok(self
+            .tokens
+            .read()
+            .unwrap()
+            .values()
+            .find(|t| t.token_hash == token_hash)
+            .cloned())
+    }
+
+    async fn mark_used(&self, id: i64) -> Result<bool, BlogError> {
+        let mut tokens = self.tokens.write().unwrap();
+        match tokens.get_mut(&id) {
+            Some(token) if token.used_at.is_none() && token.revoked_at.is_none() => {
+                token.used_at = Some(Utc::now());
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn revoke_family(&self, family_id: Uuid) -> Result<(), BlogError> {
+        let now = Utc::now();
+        for token in self.tokens.write().unwrap().values_mut() {
+            if token.family_id == family_id && token.revoked_at.is_none() {
+                token.revoked_at = Some(now);
+            }
+        }
+        Ok(())
+    }
+
+    async fn revoke_all_for_user(&self, user_id: i64) -> Result<(), BlogError> {
+        let now = Utc::now();
+        for token in self.tokens.write().unwrap().values_mut() {
+            if token.user_id == user_id && token.revoked_at.is_none() {
+                token.revoked_at = Some(now);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::domain::error::BlogError;
+
+#[async_trait]
+pub trait RevocationRepository: Send + Sync {
+    // Отзывает один access-токен; запись нужна только до истечения токена.
+    async fn revoke(&self, jti: Uuid, user_id: i64, expires_at: DateTime<Utc>) -> Result<(), BlogError>;
+    // Отозван ли access-токен: по jti или потому, что его поколение старше текущего.
+    // Вызывается на каждый аутентифицированный запрос.
+    async fn is_revoked(&self, jti: Uuid, user_id: i64, generation: i64) -> Result<bool, BlogError>;
+    // Текущее поколение сессий пользователя; токены более старых поколений недействительны.
+    async fn generation(&self, user_id: i64) -> Result<i64, BlogError>;
+    async fn bump_generation(&self, user_id: i64) -> Result<i64, BlogError>;
+}
+
+#[derive(Clone)]
+pub struct PostgresRevocationRepository {
+    pool: PgPool,
+}
+
+impl PostgresRevocationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RevocationRepository for PostgresRevocationRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "revoked_tokens"))]
+    async fn revoke(&self, jti: Uuid, user_id: i64, expires_at: DateTime<Utc>) -> Result<(), BlogError> {
+        // Истёкшие токены и так не пройдут проверку подписи — чистим по пути.
+        sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO revoked_tokens (jti, user_id, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+        )
+            .bind(jti)
+            .bind(user_id)
+            .bind(expires_at)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "revoked_tokens"))]
+    async fn is_revoked(&self, jti: Uuid, user_id: i64, generation: i64) -> Result<bool, BlogError> {
+        // Одним запросом к БД, без кеша в памяти: отзыв, сделанный любым экземпляром
+        // сервера, сразу виден всем остальным.
+        let revoked: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM revoked_tokens WHERE jti = $1)
+                OR COALESCE((SELECT generation FROM session_generations WHERE user_id = $2), 0) > $3
+            "#,
+        )
+            .bind(jti)
+            .bind(user_id)
+            .bind(generation)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(revoked)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "session_generations"))]
+    async fn generation(&self, user_id: i64) -> Result<i64, BlogError> {
+        let row = sqlx::query("SELECT generation FROM session_generations WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.get("generation")).unwrap_or(0))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "session_generations"))]
+    async fn bump_generation(&self, user_id: i64) -> Result<i64, BlogError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO session_generations (user_id, generation)
+            VALUES ($1, 1)
+            ON CONFLICT (user_id) DO UPDATE SET generation = session_generations.generation + 1
+            RETURNING generation
+            "#,
+        )
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(row.get("generation"))
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryRevocationRepository {
+    revoked: RwLock<HashMap<Uuid, DateTime<Utc>>>,
+    generations: RwLock<HashMap<i64, i64>>,
+}
+
+impl InMemoryRevocationRepository {
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RevocationRepository for InMemoryRevocationRepository {
+    async fn revoke(&self, jti: Uuid, _user_id: i64, expires_at: DateTime<Utc>) -> Result<(), BlogError> {
+        let now = Utc::now();
+        let mut revoked = self.revoked.write().unwrap();
+        // Истёкшие токены и так не пройдут проверку подписи — чистим по пути.
+        revoked.retain(|_, exp| *exp > now);
+        revoked.insert(jti, expires_at);
+        Ok(())
+    }
+
+    async fn is_revoked(&self, jti: Uuid, user_id: i64, generation: i64) -> Result<bool, BlogError> {
+        Ok(self.revoked.read().unwrap().contains_key(&jti) || generation < self.generation(user_id).await?)
+    }
+
+    async fn generation(&self, user_id: i64) -> Result<i64, BlogError> {
+        Ok(self.generations.read().unwrap().get(&user_id).copied().unwrap_or(0))
+    }
+
+    async fn bump_generation(&self, user_id: i64) -> Result<i64, BlogError> {
+        let mut generations = self.generations.write().unwrap();
+        let generation = generations.entry(user_id).or_insert(0);
+        *generation += 1;
+        Ok(*generation)
+    }
+}
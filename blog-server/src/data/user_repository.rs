@@ -1,51 +1,537 @@
-use sqlx::{PgPool, Row};
-use uuid::Uuid;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+
+use async_trait::async_trait;
+use sqlx::{PgConnection, PgPool, Row};
+use tracing::instrument;
 use chrono::{DateTime, Utc};
 
+use crate::domain::admin_action::{AdminAction, AdminActionKind};
+use crate::domain::error::BlogError;
+use crate::domain::role::Role;
+use crate::domain::user::{AuthorSummary, UpdateProfile, User};
+
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    async fn create(&self, user: User) -> Result<User, BlogError>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<User>, BlogError>;
+    // Поиск без учёта регистра, как и уникальность имени.
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, BlogError>;
+    // Адреса хранятся в нижнем регистре, вызывающий нормализует `email` сам.
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, BlogError>;
+    // `false` — пользователя с таким id нет.
+    async fn update_password(&self, id: i64, password_hash: &str) -> Result<bool, BlogError>;
+    // Отмечает адрес подтверждённым; повторный вызов не меняет исходную дату.
+    async fn mark_email_verified(&self, id: i64) -> Result<Option<User>, BlogError>;
+    // Постраничный список по id; `search` — подстрока имени или адреса без учёта регистра.
+    async fn list(&self, search: Option<&str>, limit: i64, offset: i64) -> Result<Vec<User>, BlogError>;
+    async fn count(&self, search: Option<&str>) -> Result<i64, BlogError>;
+    // Поля профиля заменяются целиком, `None` очищает поле; `None` — пользователя нет.
+    async fn update_profile(&self, id: i64, profile: &UpdateProfile) -> Result<Option<User>, BlogError>;
+
+    // Изменения от имени администратора `actor_id`: пользователь и запись в журнале
+    // admin_actions меняются в одной транзакции. `None`/`false` — пользователя нет,
+    // и тогда журнал не пополняется.
+    async fn set_suspended(&self, actor_id: i64, id: i64, suspended: bool) -> Result<Option<User>, BlogError>;
+    // Вместе с пользователем возвращает роль, которая была у него до изменения.
+    async fn set_role(&self, actor_id: i64, id: i64, role: Role) -> Result<Option<(User, Role)>, BlogError>;
+    // Посты и токены пользователя удаляются каскадно, запись журнала остаётся.
+    async fn delete(&self, actor_id: i64, id: i64) -> Result<bool, BlogError>;
+    // Журнал только пополняется: ни изменения, ни удаления записей не предусмотрено.
+    async fn record_admin_action(&self, action: AdminAction) -> Result<(), BlogError>;
+}
+
 #[derive(Debug)]
 pub struct UserRow {
-    pub id: Uuid,
+    pub id: i64,
     pub username: String,
+    pub email: String,
     pub password_hash: String,
     pub created_at: DateTime<Utc>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub role: String,
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
 }
 
-pub async fn create_user(
-    pool: &PgPool,
-    id: Uuid,
-    email: &str,
-    password_hash: &str,
-) -> Result<(), sqlx::Error> {
+impl From<UserRow> for User {
+    fn from(row: UserRow) -> Self {
+        User {
+            id: row.id,
+            username: row.username,
+            email: row.email,
+            password_hash: row.password_hash,
+            created_at: row.created_at,
+            email_verified_at: row.email_verified_at,
+            // Значения ограничены CHECK в БД; неизвестная роль не даёт никаких прав.
+            role: row.role.parse().unwrap_or(Role::Reader),
+            suspended_at: row.suspended_at,
+            display_name: row.display_name,
+            bio: row.bio,
+            avatar_url: row.avatar_url,
+        }
+    }
+}
+
+fn user_row(r: &sqlx::postgres::PgRow) -> UserRow {
+    UserRow {
+        id: r.get("id"),
+        username: r.get("username"),
+        email: r.get("email"),
+        password_hash: r.get("password_hash"),
+        created_at: r.get("created_at"),
+        email_verified_at: r.get("email_verified_at"),
+        role: r.get("role"),
+        suspended_at: r.get("suspended_at"),
+        display_name: r.get("display_name"),
+        bio: r.get("bio"),
+        avatar_url: r.get("avatar_url"),
+    }
+}
+
+#[derive(Clone)]
+pub struct PostgresUserRepository {
+    pool: PgPool,
+}
+
+impl PostgresUserRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UserRepository for PostgresUserRepository {
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn create(&self, user: User) -> Result<User, BlogError> {
+        let res = sqlx::query(
+            r#"
+            INSERT INTO users (username, email, password_hash, created_at, role)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, username, email, password_hash, created_at, email_verified_at, role, suspended_at,
+                   display_name, bio, avatar_url
+            "#,
+        )
+            .bind(&user.username)
+            .bind(&user.email)
+            .bind(&user.password_hash)
+            .bind(user.created_at)
+            .bind(user.role.as_str())
+            .fetch_one(&self.pool)
+            .await;
+
+        match res {
+            Ok(row) => Ok(user_row(&row).into()),
+            Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+                Err(BlogError::UserAlreadyExists)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn find_by_id(&self, id: i64) -> Result<Option<User>, BlogError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, username, email, password_hash, created_at, email_verified_at, role, suspended_at,
+                   display_name, bio, avatar_url
+            FROM users
+            WHERE id = $1
+            "#,
+        )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| user_row(&r).into()))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, BlogError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, username, email, password_hash, created_at, email_verified_at, role, suspended_at,
+                   display_name, bio, avatar_url
+            FROM users
+            WHERE LOWER(username) = LOWER($1)
+            "#,
+        )
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| user_row(&r).into()))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, BlogError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, username, email, password_hash, created_at, email_verified_at, role, suspended_at,
+                   display_name, bio, avatar_url
+            FROM users
+            WHERE email = $1
+            "#,
+        )
+            .bind(email)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| user_row(&r).into()))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn update_password(&self, id: i64, password_hash: &str) -> Result<bool, BlogError> {
+        let res = sqlx::query("UPDATE users SET password_hash = $2 WHERE id = $1")
+            .bind(id)
+            .bind(password_hash)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(res.rows_affected() == 1)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn mark_email_verified(&self, id: i64) -> Result<Option<User>, BlogError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE users SET email_verified_at = COALESCE(email_verified_at, NOW())
+            WHERE id = $1
+            RETURNING id, username, email, password_hash, created_at, email_verified_at, role, suspended_at,
+                   display_name, bio, avatar_url
+            "#,
+        )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| user_row(&r).into()))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn list(&self, search: Option<&str>, limit: i64, offset: i64) -> Result<Vec<User>, BlogError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, username, email, password_hash, created_at, email_verified_at, role, suspended_at,
+                   display_name, bio, avatar_url
+            FROM users
+            WHERE $1::TEXT IS NULL OR username ILIKE $1 OR email ILIKE $1
+            ORDER BY id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+            .bind(search.map(like_pattern))
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|r| user_row(r).into()).collect())
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn count(&self, search: Option<&str>) -> Result<i64, BlogError> {
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM users WHERE $1::TEXT IS NULL OR username ILIKE $1 OR email ILIKE $1",
+        )
+            .bind(search.map(like_pattern))
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(total)
+    }
+
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn update_profile(&self, id: i64, profile: &UpdateProfile) -> Result<Option<User>, BlogError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE users SET display_name = $2, bio = $3, avatar_url = $4
+            WHERE id = $1
+            RETURNING id, username, email, password_hash, created_at, email_verified_at, role, suspended_at,
+                   display_name, bio, avatar_url
+            "#,
+        )
+            .bind(id)
+            .bind(&profile.display_name)
+            .bind(&profile.bio)
+            .bind(&profile.avatar_url)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| user_row(&r).into()))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn set_suspended(&self, actor_id: i64, id: i64, suspended: bool) -> Result<Option<User>, BlogError> {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            r#"
+            UPDATE users SET suspended_at = CASE WHEN $2 THEN COALESCE(suspended_at, NOW()) END
+            WHERE id = $1
+            RETURNING id, username, email, password_hash, created_at, email_verified_at, role, suspended_at,
+                   display_name, bio, avatar_url
+            "#,
+        )
+            .bind(id)
+            .bind(suspended)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let kind = if suspended {
+            AdminActionKind::Suspend
+        } else {
+            AdminActionKind::Unsuspend
+        };
+        insert_admin_action(&mut tx, &AdminAction::new(actor_id, kind, id, None)).await?;
+        tx.commit().await?;
+        Ok(Some(user_row(&row).into()))
+    }
+
+    // Прежняя роль берётся из той же строки, которую меняет UPDATE, под её блокировкой,
+    // поэтому параллельная смена роли не исказит запись журнала.
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn set_role(&self, actor_id: i64, id: i64, role: Role) -> Result<Option<(User, Role)>, BlogError> {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            r#"
+            UPDATE users u SET role = $2
+            FROM (SELECT id, role FROM users WHERE id = $1 FOR UPDATE) old
+            WHERE u.id = old.id
+            RETURNING u.id, u.username, u.email, u.password_hash, u.created_at, u.email_verified_at,
+                   u.role, u.suspended_at, u.display_name, u.bio, u.avatar_url, old.role AS previous_role
+            "#,
+        )
+            .bind(id)
+            .bind(role.as_str())
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let previous: Role = row.get::<String, _>("previous_role").parse().unwrap_or(Role::Reader);
+        let details = format!("{previous} -> {role}");
+        insert_admin_action(&mut tx, &AdminAction::new(actor_id, AdminActionKind::ChangeRole, id, Some(details)))
+            .await?;
+        tx.commit().await?;
+        Ok(Some((user_row(&row).into(), previous)))
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "users"))]
+    async fn delete(&self, actor_id: i64, id: i64) -> Result<bool, BlogError> {
+        let mut tx = self.pool.begin().await?;
+        let username: Option<String> = sqlx::query_scalar("DELETE FROM users WHERE id = $1 RETURNING username")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(username) = username else {
+            return Ok(false);
+        };
+
+        let action = AdminAction::new(actor_id, AdminActionKind::DeleteAccount, id, Some(username));
+        insert_admin_action(&mut tx, &action).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    #[instrument(skip_all, fields(db.system = "postgresql", db.table = "admin_actions"))]
+    async fn record_admin_action(&self, action: AdminAction) -> Result<(), BlogError> {
+        let mut conn = self.pool.acquire().await?;
+        insert_admin_action(&mut conn, &action).await
+    }
+}
+
+async fn insert_admin_action(conn: &mut PgConnection, action: &AdminAction) -> Result<(), BlogError> {
     sqlx::query(
         r#"
-        INSERT INTO users (id, email, password_hash)
-        VALUES ($1, $2, $3)
+        INSERT INTO admin_actions (actor_id, action, target_user_id, details, created_at)
+        VALUES ($1, $2, $3, $4, $5)
         "#,
     )
-        .bind(id)
-        .bind(email)
-        .bind(password_hash)
-        .execute(pool)
+        .bind(action.actor_id)
+        .bind(action.kind.as_str())
+        .bind(action.target_user_id)
+        .bind(&action.details)
+        .bind(action.created_at)
+        .execute(conn)
         .await?;
     Ok(())
 }
 
-pub async fn find_user(pool: &PgPool, username: &str) -> Result<Option<UserRow>, sqlx::Error> {
-    let row = sqlx::query(
-        r#"
-        SELECT id, username, password_hash, created_at
-        FROM users
-        WHERE username = $1
-        "#,
-    )
-        .bind(username)
-        .fetch_optional(pool)
-        .await?;
+// Подстрока для ILIKE: спецсимволы шаблона из запроса экранируются.
+fn like_pattern(search: &str) -> String {
+    let escaped = search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{escaped}%")
+}
 
-    Ok(row.map(|r| UserRow {
-        id: r.get("id"),
-        username: r.get("username"),
-        password_hash: r.get("password_hash"),
-        created_at: r.get("created_at"),
-    }))
-}
\ No newline at end of file
+// Хранилище для unit-тестов сервисов без PostgreSQL.
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Default)]
+pub struct InMemoryUserRepository {
+    users: RwLock<HashMap<i64, User>>,
+    admin_actions: Mutex<Vec<AdminAction>>,
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+impl InMemoryUserRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn admin_actions(&self) -> Vec<AdminAction> {
+        self.admin_actions.lock().unwrap().clone()
+    }
+
+    fn log(&self, actor_id: i64, kind: AdminActionKind, id: i64, details: Option<String>) {
+        self.admin_actions
+            .lock()
+            .unwrap()
+            .push(AdminAction::new(actor_id, kind, id, details));
+    }
+
+    pub(crate) fn author(&self, id: i64) -> Option<AuthorSummary> {
+        self.users.read().unwrap().get(&id).map(AuthorSummary::from)
+    }
+
+    fn matching(&self, search: Option<&str>) -> Vec<User> {
+        let search = search.map(str::to_lowercase);
+        self.users
+            .read()
+            .unwrap()
+            .values()
+            .filter(|u| match &search {
+                Some(s) => u.username.to_lowercase().contains(s) || u.email.contains(s),
+                None => true,
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn create(&self, mut user: User) -> Result<User, BlogError> {
+        let mut users = self.users.write().unwrap();
+        if users
+            .values()
+            .any(|u| u.username.to_lowercase() == user.username.to_lowercase() || u.email == user.email)
+        {
+            return Err(BlogError::UserAlreadyExists);
+        }
+        user.id = users.keys().max().copied().unwrap_or(0) + 1;
+        users.insert(user.id, user.clone());
+        Ok(user)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<User>, BlogError> {
+        Ok(self.users.read().unwrap().get(&id).cloned())
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, BlogError> {
+        Ok(self
+            .users
+            .read()
+            .unwrap()
+            .values()
+            .find(|u| u.username.to_lowercase() == username.to_lowercase())
+            .cloned())
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, BlogError> {
+        Ok(self
+            .users
+            .read()
+            .unwrap()
+            .values()
+            .find(|u| u.email == email)
+            .cloned())
+    }
+
+    async fn update_password(&self, id: i64, password_hash: &str) -> Result<bool, BlogError> {
+        let mut users = self.users.write().unwrap();
+        Ok(users
+            .get_mut(&id)
+            .map(|user| user.password_hash = password_hash.to_string())
+            .is_some())
+    }
+
+    async fn mark_email_verified(&self, id: i64) -> Result<Option<User>, BlogError> {
+        let mut users = self.users.write().unwrap();
+        Ok(users.get_mut(&id).map(|user| {
+            user.email_verified_at.get_or_insert_with(Utc::now);
+            user.clone()
+        }))
+    }
+
+    async fn list(&self, search: Option<&str>, limit: i64, offset: i64) -> Result<Vec<User>, BlogError> {
+        let mut users = self.matching(search);
+        users.sort_by_key(|u| u.id);
+        Ok(users
+            .into_iter()
+            .skip(offset.max(0) as usize)
+            .take(limit.max(0) as usize)
+            .collect())
+    }
+
+    async fn count(&self, search: Option<&str>) -> Result<i64, BlogError> {
+        Ok(self.matching(search).len() as i64)
+    }
+
+    async fn update_profile(&self, id: i64, profile: &UpdateProfile) -> Result<Option<User>, BlogError> {
+        let mut users = self.users.write().unwrap();
+        Ok(users.get_mut(&id).map(|user| {
+            user.display_name = profile.display_name.clone();
+            user.bio = profile.bio.clone();
+            user.avatar_url = profile.avatar_url.clone();
+            user.clone()
+        }))
+    }
+
+    async fn set_suspended(&self, actor_id: i64, id: i64, suspended: bool) -> Result<Option<User>, BlogError> {
+        let user = self.users.write().unwrap().get_mut(&id).map(|user| {
+            user.suspended_at = if suspended { user.suspended_at.or(Some(Utc::now())) } else { None };
+            user.clone()
+        });
+        if user.is_some() {
+            let kind = if suspended {
+                AdminActionKind::Suspend
+            } else {
+                AdminActionKind::Unsuspend
+            };
+            self.log(actor_id, kind, id, None);
+        }
+        Ok(user)
+    }
+
+    async fn set_role(&self, actor_id: i64, id: i64, role: Role) -> Result<Option<(User, Role)>, BlogError> {
+        let changed = self.users.write().unwrap().get_mut(&id).map(|user| {
+            let previous = std::mem::replace(&mut user.role, role);
+            (user.clone(), previous)
+        });
+        if let Some((_, previous)) = &changed {
+            self.log(actor_id, AdminActionKind::ChangeRole, id, Some(format!("{previous} -> {role}")));
+        }
+        Ok(changed)
+    }
+
+    async fn delete(&self, actor_id: i64, id: i64) -> Result<bool, BlogError> {
+        let removed = self.users.write().unwrap().remove(&id);
+        if let Some(user) = &removed {
+            self.log(actor_id, AdminActionKind::DeleteAccount, id, Some(user.username.clone()));
+        }
+        Ok(removed.is_some())
+    }
+
+    async fn record_admin_action(&self, action: AdminAction) -> Result<(), BlogError> {
+        self.admin_actions.lock().unwrap().push(action);
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AdminActionKind {
+    Suspend,
+    Unsuspend,
+    ChangeRole,
+    ForcePasswordReset,
+    DeleteAccount,
+}
+
+impl AdminActionKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            AdminActionKind::Suspend => "suspend",
+            AdminActionKind::Unsuspend => "unsuspend",
+            AdminActionKind::ChangeRole => "change_role",
+            AdminActionKind::ForcePasswordReset => "force_password_reset",
+            AdminActionKind::DeleteAccount => "delete_account",
+        }
+    }
+}
+
+// Запись журнала admin_actions: кто, что и с каким аккаунтом сделал.
+#[derive(Debug, Clone)]
+pub(crate) struct AdminAction {
+    pub(crate) actor_id: i64,
+    pub(crate) kind: AdminActionKind,
+    pub(crate) target_user_id: i64,
+    // Подробности для человека, например "author -> moderator".
+    pub(crate) details: Option<String>,
+    pub(crate) created_at: DateTime<Utc>,
+}
+
+impl AdminAction {
+    pub(crate) fn new(
+        actor_id: i64,
+        kind: AdminActionKind,
+        target_user_id: i64,
+        details: Option<String>,
+    ) -> Self {
+        Self {
+            actor_id,
+            kind,
+            target_user_id,
+            details,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::domain::post::Post;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum AuditAction {
+    Register,
+    LoginSucceeded,
+    LoginFailed,
+    PasswordChanged,
+    PasswordReset,
+    PostCreated,
+    PostUpdated,
+    PostDeleted,
+    // Модератор скрыл пост из ленты или вернул его.
+    PostHidden,
+    PostUnhidden,
+    // Запланированный пост опубликован планировщиком; actor_id не задан.
+    PostPublished,
+}
+
+impl AuditAction {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::Register => "register",
+            AuditAction::LoginSucceeded => "login_succeeded",
+            AuditAction::LoginFailed => "login_failed",
+            AuditAction::PasswordChanged => "password_changed",
+            AuditAction::PasswordReset => "password_reset",
+            AuditAction::PostCreated => "post_created",
+            AuditAction::PostUpdated => "post_updated",
+            AuditAction::PostDeleted => "post_deleted",
+            AuditAction::PostHidden => "post_hidden",
+            AuditAction::PostUnhidden => "post_unhidden",
+            AuditAction::PostPublished => "post_published",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "register" => Ok(AuditAction::Register),
+            "login_succeeded" => Ok(AuditAction::LoginSucceeded),
+            "login_failed" => Ok(AuditAction::LoginFailed),
+            "password_changed" => Ok(AuditAction::PasswordChanged),
+            "password_reset" => Ok(AuditAction::PasswordReset),
+            "post_created" => Ok(AuditAction::PostCreated),
+            "post_updated" => Ok(AuditAction::PostUpdated),
+            "post_deleted" => Ok(AuditAction::PostDeleted),
+            "post_hidden" => Ok(AuditAction::PostHidden),
+            "post_unhidden" => Ok(AuditAction::PostUnhidden),
+            "post_published" => Ok(AuditAction::PostPublished),
+            other => Err(format!("unknown audit action `{other}`")),
+        }
+    }
+}
+
+// Откуда пришёл запрос. Собирается транспортом (HTTP или gRPC) и передаётся
+// в сервисы вместе с входными данными.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuditContext {
+    pub(crate) ip: Option<String>,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) request_id: Option<String>,
+}
+
+// Запись журнала audit_events. subject_id — объект действия: пользователь для
+// событий входа и пароля, пост для post_*.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct AuditEvent {
+    pub(crate) id: i64,
+    pub(crate) action: AuditAction,
+    pub(crate) actor_id: Option<i64>,
+    pub(crate) subject_id: Option<i64>,
+    pub(crate) ip: Option<String>,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) request_id: Option<String>,
+    // Отпечатки содержимого поста до и после изменения, см. content_hash.
+    pub(crate) before_hash: Option<String>,
+    pub(crate) after_hash: Option<String>,
+    pub(crate) details: Option<String>,
+    pub(crate) created_at: DateTime<Utc>,
+}
+
+impl AuditEvent {
+    pub(crate) fn new(action: AuditAction, ctx: &AuditContext) -> Self {
+        Self {
+            id: 0,
+            action,
+            actor_id: None,
+            subject_id: None,
+            ip: ctx.ip.clone(),
+            user_agent: ctx.user_agent.clone(),
+            request_id: ctx.request_id.clone(),
+            before_hash: None,
+            after_hash: None,
+            details: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+// SHA-256 заголовка и текста поста в hex. Сам текст в журнал не попадает, но по
+// отпечатку можно проверить, какая версия поста была до и после правки.
+pub(crate) fn content_hash(post: &Post) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(post.title.as_bytes());
+    hasher.update([0]);
+    hasher.update(post.content.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+// Фильтр журнала: интервал [from, to), все условия необязательны.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct AuditQuery {
+    pub(crate) actor_id: Option<i64>,
+    pub(crate) action: Option<AuditAction>,
+    pub(crate) from: Option<DateTime<Utc>>,
+    pub(crate) to: Option<DateTime<Utc>>,
+    pub(crate) limit: Option<i64>,
+    pub(crate) offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AuditPage {
+    pub(crate) events: Vec<AuditEvent>,
+    pub(crate) total: i64,
+    pub(crate) limit: i64,
+    pub(crate) offset: i64,
+}
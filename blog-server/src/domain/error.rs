@@ -1,16 +1,144 @@
+use serde::Serialize;
 use thiserror::Error;
 
+// Ошибка конкретного поля запроса: `code` стабилен, как и BlogError::code(),
+// `message` — текст для человека.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &'static str, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+fn join_fields(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("{}: {}", e.field, e.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 #[derive(Error, Debug)]
 pub enum BlogError
 {
+    // ошибки входных данных
+    #[error("Invalid input: {0}")]
+    Validation(String),
+    #[error("Invalid input: {}", join_fields(.0))]
+    InvalidFields(Vec<FieldError>),
+    #[error("Invalid pagination cursor")]
+    InvalidCursor,
+
+    // аутентификация и права доступа
+    #[error("Missing or malformed authorization header")]
+    MissingToken,
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+    #[error("Invalid or expired token")]
+    InvalidToken,
+    #[error("Invalid or expired refresh token")]
+    InvalidRefreshToken,
+    #[error("Forbidden action")]
+    Forbidden,
+    #[error("Invalid or expired verification link")]
+    InvalidVerificationToken,
+    #[error("Invalid or expired password reset link")]
+    InvalidResetToken,
+    #[error("Email address is not verified")]
+    EmailNotVerified,
+    #[error("Email address is already verified")]
+    EmailAlreadyVerified,
+    #[error("Account is suspended")]
+    AccountSuspended,
+
+    // ограничение частоты запросов; retry_after — секунды до следующей попытки
+    #[error("Too many requests, retry in {retry_after}s")]
+    RateLimited { retry_after: u64 },
+    #[error("Account temporarily locked after failed login attempts, retry in {retry_after}s")]
+    AccountLocked { retry_after: u64 },
+
+    // состояние ресурсов
     #[error("User not found")]
     UserNotFound,
     #[error("User already exists")]
     UserAlreadyExists,
-    #[error("Invalid credentials")]
-    InvalidCredentials,
     #[error("Post not found")]
     PostNotFound,
-    #[error("Forbidden action")]
-    Forbidden
-}
\ No newline at end of file
+
+    // инфраструктура; подробности пишутся в лог и клиенту не отдаются
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("Internal error: {0}")]
+    Internal(String)
+}
+
+impl BlogError {
+    // Стабильный машинный код ошибки: одинаков для HTTP (`code` в problem+json)
+    // и gRPC (metadata `x-error-code`), клиенты ветвятся по нему, а не по тексту.
+    pub fn code(&self) -> &'static str {
+        match self {
+            BlogError::Validation(_) | BlogError::InvalidFields(_) => "validation_failed",
+            BlogError::InvalidCursor => "invalid_cursor",
+            BlogError::MissingToken => "missing_token",
+            BlogError::InvalidCredentials => "invalid_credentials",
+            BlogError::InvalidToken => "invalid_token",
+            BlogError::InvalidRefreshToken => "invalid_refresh_token",
+            BlogError::Forbidden => "forbidden",
+            BlogError::InvalidVerificationToken => "invalid_verification_token",
+            BlogError::InvalidResetToken => "invalid_reset_token",
+            BlogError::EmailNotVerified => "email_not_verified",
+            BlogError::EmailAlreadyVerified => "email_already_verified",
+            BlogError::AccountSuspended => "account_suspended",
+            BlogError::RateLimited { .. } => "rate_limited",
+            BlogError::AccountLocked { .. } => "account_locked",
+            BlogError::UserNotFound => "user_not_found",
+            BlogError::UserAlreadyExists => "user_already_exists",
+            BlogError::PostNotFound => "post_not_found",
+            BlogError::Database(sqlx::Error::PoolTimedOut) => "service_unavailable",
+            BlogError::Database(_) => "database_error",
+            BlogError::Internal(_) => "internal_error",
+        }
+    }
+
+    // Значение для заголовка Retry-After (HTTP) и metadata `retry-after` (gRPC).
+    pub fn retry_after(&self) -> Option<u64> {
+        match self {
+            BlogError::RateLimited { retry_after } | BlogError::AccountLocked { retry_after } => {
+                Some(*retry_after)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn field_errors(&self) -> &[FieldError] {
+        match self {
+            BlogError::InvalidFields(errors) => errors,
+            _ => &[],
+        }
+    }
+
+    pub fn is_server_error(&self) -> bool {
+        matches!(self, BlogError::Database(_) | BlogError::Internal(_))
+    }
+
+    // Текст для клиента: у серверных ошибок подробности скрыты.
+    pub fn public_message(&self) -> String {
+        match self {
+            BlogError::Database(sqlx::Error::PoolTimedOut) => {
+                "Service temporarily unavailable".to_string()
+            }
+            err if err.is_server_error() => "Internal server error".to_string(),
+            err => err.to_string(),
+        }
+    }
+}
@@ -1,3 +1,7 @@
 pub(crate) mod post;
 pub(crate) mod user;
-mod error;
\ No newline at end of file
+pub(crate) mod error;
+pub(crate) mod token;
+pub(crate) mod role;
+pub(crate) mod admin_action;
+pub(crate) mod audit;
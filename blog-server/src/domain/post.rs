@@ -1,24 +1,251 @@
+use std::fmt;
+use std::str::FromStr;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-//Реализуйте метод new для создания нового поста.
-#[derive(Debug, Serialize, Deserialize)]
-struct Post {
-    id:  i64,
-    title: String,
-    content: String,
-    author_id: i64,
-    created_at: i64,
-    updated_at: i64
+use crate::domain::error::BlogError;
+use crate::domain::user::AuthorSummary;
+
+// Читателям видны только опубликованные посты, остальные — только автору.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PostStatus {
+    Draft,
+    // Опубликуется планировщиком в published_at.
+    Scheduled,
+    #[default]
+    Published,
+    Archived
+}
+
+impl PostStatus {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            PostStatus::Draft => "draft",
+            PostStatus::Scheduled => "scheduled",
+            PostStatus::Published => "published",
+            PostStatus::Archived => "archived",
+        }
+    }
+
+    fn was_published(self) -> bool {
+        matches!(self, PostStatus::Published | PostStatus::Archived)
+    }
+}
+
+impl fmt::Display for PostStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PostStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "draft" => Ok(PostStatus::Draft),
+            "scheduled" => Ok(PostStatus::Scheduled),
+            "published" => Ok(PostStatus::Published),
+            "archived" => Ok(PostStatus::Archived),
+            other => Err(format!(
+                "unknown post status `{other}` (expected draft, scheduled, published or archived)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Post {
+    pub(crate) id: i64,
+    pub(crate) title: String,
+    pub(crate) content: String,
+    pub(crate) author_id: i64,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) updated_at: DateTime<Utc>,
+    // Когда пост скрыт модератором; скрытый пост не показывается в ленте и по ссылке.
+    pub(crate) hidden_at: Option<DateTime<Utc>>,
+    pub(crate) status: PostStatus,
+    // Когда пост опубликован или, для запланированного, будет опубликован.
+    // У черновика не задано.
+    pub(crate) published_at: Option<DateTime<Utc>>,
+    // Подпись автора, чтобы клиенту не запрашивать профиль для каждого поста.
+    // Заполняется хранилищем при чтении.
+    #[serde(default)]
+    pub(crate) author: Option<AuthorSummary>
+}
+
+impl Post {
+    pub(crate) fn new(title: String, content: String, author_id: i64) -> Self {
+        let now = Utc::now();
+        Self {
+            id: 0,
+            title,
+            content,
+            author_id,
+            created_at: now,
+            updated_at: now,
+            hidden_at: None,
+            status: PostStatus::Published,
+            published_at: Some(now),
+            author: None
+        }
+    }
+
+    // Позиция в ленте: опубликованные посты идут по времени публикации,
+    // черновики — по времени создания.
+    pub(crate) fn feed_at(&self) -> DateTime<Utc> {
+        self.published_at.unwrap_or(self.created_at)
+    }
+
+    // Переводит пост в `status`; `publish_at` задаётся только для запланированных.
+    // Возвращённый в ленту архивный пост сохраняет исходное время публикации.
+    pub(crate) fn set_status(
+        &mut self,
+        status: PostStatus,
+        publish_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>
+    ) -> Result<(), BlogError> {
+        if publish_at.is_some() && status != PostStatus::Scheduled {
+            return Err(BlogError::Validation(
+                "`published_at` can only be set for scheduled posts".into(),
+            ));
+        }
+        self.published_at = match status {
+            PostStatus::Draft => None,
+            PostStatus::Scheduled => {
+                let at = publish_at
+                    .or(self.published_at.filter(|_| self.status == PostStatus::Scheduled))
+                    .ok_or_else(|| {
+                        BlogError::Validation("`published_at` is required for scheduled posts".into())
+                    })?;
+                // Уже запланированный пост, время которого подошло, можно править,
+                // не перенося публикацию: его подхватит планировщик.
+                if publish_at.is_some() && at <= now {
+                    return Err(BlogError::Validation("`published_at` must be in the future".into()));
+                }
+                Some(at)
+            }
+            PostStatus::Published if self.status.was_published() => self.published_at.or(Some(now)),
+            PostStatus::Published => Some(now),
+            PostStatus::Archived => self.published_at.filter(|_| self.status.was_published()),
+        };
+        self.status = status;
+        Ok(())
+    }
 }
 
+// Какие посты выбирает лента: скрытые модератором не попадают никогда.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PostFilter {
+    pub(crate) status: PostStatus,
+    pub(crate) author_id: Option<i64>
+}
+
+impl PostFilter {
+    pub(crate) fn matches(&self, post: &Post) -> bool {
+        post.hidden_at.is_none()
+            && post.status == self.status
+            && self.author_id.is_none_or(|id| post.author_id == id)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PostPage {
+    pub(crate) posts: Vec<Post>,
+    pub(crate) total: i64,
+    pub(crate) limit: i64,
+    pub(crate) offset: i64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CursorDirection {
+    // К более старым постам (следующая страница ленты).
+    Next,
+    // К более новым постам (предыдущая страница ленты).
+    Prev
+}
+
+// Позиция в ленте для keyset-пагинации: (feed_at, id) граничного поста.
+// Клиенту отдаётся в виде непрозрачной base64-строки.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PostCursor {
+    pub(crate) direction: CursorDirection,
+    pub(crate) feed_at: DateTime<Utc>,
+    pub(crate) id: i64
+}
+
+impl PostCursor {
+    pub(crate) fn new(direction: CursorDirection, post: &Post) -> Self {
+        Self {
+            direction,
+            feed_at: post.feed_at(),
+            id: post.id
+        }
+    }
+
+    pub(crate) fn encode(&self) -> String {
+        let direction = match self.direction {
+            CursorDirection::Next => 'n',
+            CursorDirection::Prev => 'p',
+        };
+        let nanos = self.feed_at.timestamp_nanos_opt().unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(format!("{direction}:{nanos}:{}", self.id))
+    }
+
+    pub(crate) fn decode(raw: &str) -> Result<Self, BlogError> {
+        let bytes = URL_SAFE_NO_PAD.decode(raw).map_err(|_| BlogError::InvalidCursor)?;
+        let text = String::from_utf8(bytes).map_err(|_| BlogError::InvalidCursor)?;
+        let mut parts = text.splitn(3, ':');
+        let direction = match parts.next() {
+            Some("n") => CursorDirection::Next,
+            Some("p") => CursorDirection::Prev,
+            _ => return Err(BlogError::InvalidCursor),
+        };
+        let nanos: i64 = parts
+            .next()
+            .and_then(|v| v.parse().ok())
+            .ok_or(BlogError::InvalidCursor)?;
+        let id: i64 = parts
+            .next()
+            .and_then(|v| v.parse().ok())
+            .ok_or(BlogError::InvalidCursor)?;
+        Ok(Self {
+            direction,
+            feed_at: DateTime::from_timestamp_nanos(nanos),
+            id
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CursorPage {
+    pub(crate) posts: Vec<Post>,
+    pub(crate) limit: i64,
+    pub(crate) next_cursor: Option<String>,
+    pub(crate) prev_cursor: Option<String>
+}
+
+// Без status пост публикуется сразу; published_at — время публикации для scheduled.
 #[derive(Debug, Serialize, Deserialize)]
-struct CreatePost {
-    title: String,
-    content: String
+pub(crate) struct CreatePost {
+    pub(crate) title: String,
+    pub(crate) content: String,
+    #[serde(default)]
+    pub(crate) status: Option<PostStatus>,
+    #[serde(default)]
+    pub(crate) published_at: Option<DateTime<Utc>>
 }
 
+// Без status статус поста не меняется.
 #[derive(Debug, Serialize, Deserialize)]
-struct UpdatePost {
-    title: String,
-    content: String
-}
\ No newline at end of file
+pub(crate) struct UpdatePost {
+    pub(crate) title: String,
+    pub(crate) content: String,
+    #[serde(default)]
+    pub(crate) status: Option<PostStatus>,
+    #[serde(default)]
+    pub(crate) published_at: Option<DateTime<Utc>>
+}
@@ -0,0 +1,72 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+// Роль пользователя; хранится в `users.role` и передаётся в `Claims`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Role {
+    // Только чтение. Токены, выданные до появления ролей, считаются токенами читателя.
+    #[default]
+    Reader,
+    Author,
+    Moderator,
+    Admin,
+}
+
+// Действия, на которые нужны права сверх владения ресурсом: свой пост автор
+// редактирует и удаляет без отдельного разрешения.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Permission {
+    CreatePost,
+    EditAnyPost,
+    HidePost,
+    DeleteAnyPost,
+    // Управление аккаунтами, только у администраторов.
+    ManageUsers,
+}
+
+impl Role {
+    // Роль по умолчанию для новых аккаунтов, совпадает с DEFAULT в миграции.
+    pub(crate) const DEFAULT_FOR_NEW_USERS: Role = Role::Author;
+
+    pub(crate) fn has(self, permission: Permission) -> bool {
+        use Permission::*;
+        match self {
+            Role::Reader => false,
+            Role::Author => matches!(permission, CreatePost),
+            Role::Moderator => matches!(permission, CreatePost | EditAnyPost | HidePost),
+            Role::Admin => true,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Role::Reader => "reader",
+            Role::Author => "author",
+            Role::Moderator => "moderator",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "reader" => Ok(Role::Reader),
+            "author" => Ok(Role::Author),
+            "moderator" => Ok(Role::Moderator),
+            "admin" => Ok(Role::Admin),
+            other => Err(format!("unknown role `{other}`")),
+        }
+    }
+}
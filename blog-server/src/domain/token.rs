@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+// Refresh-токен в хранилище: сам токен не сохраняется, только его хеш.
+// Все токены, полученные ротацией от одного логина, образуют семейство `family_id`.
+#[derive(Debug, Clone)]
+pub(crate) struct RefreshToken {
+    pub(crate) id: i64,
+    pub(crate) user_id: i64,
+    pub(crate) family_id: Uuid,
+    pub(crate) token_hash: String,
+    pub(crate) expires_at: DateTime<Utc>,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) used_at: Option<DateTime<Utc>>,
+    pub(crate) revoked_at: Option<DateTime<Utc>>,
+}
+
+impl RefreshToken {
+    pub(crate) fn new(
+        user_id: i64,
+        family_id: Uuid,
+        token_hash: String,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: 0,
+            user_id,
+            family_id,
+            token_hash,
+            expires_at,
+            created_at: Utc::now(),
+            used_at: None,
+            revoked_at: None,
+        }
+    }
+}
@@ -1,16 +1,56 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
-struct User {
-    id: i32,
-    name: String,
-    email: String,
-    password_hash: String,
-    created_at: i64
+use crate::domain::role::Role;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct User {
+    pub(crate) id: i64,
+    pub(crate) username: String,
+    pub(crate) email: String,
+    #[serde(skip_serializing)]
+    pub(crate) password_hash: String,
+    pub(crate) created_at: DateTime<Utc>,
+    // None — адрес ещё не подтверждён по ссылке из письма.
+    pub(crate) email_verified_at: Option<DateTime<Utc>>,
+    pub(crate) role: Role,
+    // Когда аккаунт заблокирован администратором.
+    pub(crate) suspended_at: Option<DateTime<Utc>>,
+    // Публичный профиль, см. PublicProfile.
+    pub(crate) display_name: Option<String>,
+    pub(crate) bio: Option<String>,
+    pub(crate) avatar_url: Option<String>,
 }
+
+impl User {
+    pub(crate) fn new(username: String, email: String, password_hash: String) -> Self {
+        Self {
+            id: 0,
+            username,
+            email,
+            password_hash,
+            created_at: Utc::now(),
+            email_verified_at: None,
+            role: Role::DEFAULT_FOR_NEW_USERS,
+            suspended_at: None,
+            display_name: None,
+            bio: None,
+            avatar_url: None,
+        }
+    }
+
+    pub(crate) fn is_email_verified(&self) -> bool {
+        self.email_verified_at.is_some()
+    }
+
+    pub(crate) fn is_suspended(&self) -> bool {
+        self.suspended_at.is_some()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct RegisterUser {
-    username: String,
+    pub(crate) username: String,
     pub(crate) email: String,
     pub(crate) password: String
 }
@@ -20,3 +60,119 @@ pub(crate) struct LoginUser {
     pub(crate) password: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct RefreshRequest {
+    pub(crate) refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct VerifyEmailRequest {
+    pub(crate) token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ForgotPasswordRequest {
+    pub(crate) email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ResetPasswordRequest {
+    pub(crate) token: String,
+    pub(crate) new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ChangePasswordRequest {
+    pub(crate) current_password: String,
+    pub(crate) new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct LogoutRequest {
+    pub(crate) refresh_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AuthResponse {
+    pub(crate) token: String,
+    pub(crate) refresh_token: String,
+    // Через сколько секунд истекает access-токен `token`.
+    pub(crate) expires_in: i64,
+    pub(crate) user: User,
+}
+
+// Выборка пользователей для администратора; `search` ищет подстроку в имени и адресе.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct UserQuery {
+    pub(crate) search: Option<String>,
+    pub(crate) limit: Option<i64>,
+    pub(crate) offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct UserPage {
+    pub(crate) users: Vec<User>,
+    pub(crate) total: i64,
+    pub(crate) limit: i64,
+    pub(crate) offset: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SetRoleRequest {
+    pub(crate) role: Role,
+}
+
+// Автор поста в ответах API: ровно столько, сколько нужно для подписи под постом.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AuthorSummary {
+    pub(crate) id: i64,
+    pub(crate) username: String,
+    pub(crate) display_name: Option<String>,
+    pub(crate) avatar_url: Option<String>,
+}
+
+impl From<&User> for AuthorSummary {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username.clone(),
+            display_name: user.display_name.clone(),
+            avatar_url: user.avatar_url.clone(),
+        }
+    }
+}
+
+// Профиль, видимый всем: без адреса, роли и прочих служебных полей.
+#[derive(Debug, Serialize)]
+pub(crate) struct PublicProfile {
+    pub(crate) id: i64,
+    pub(crate) username: String,
+    pub(crate) display_name: Option<String>,
+    pub(crate) bio: Option<String>,
+    pub(crate) avatar_url: Option<String>,
+    pub(crate) joined_at: DateTime<Utc>,
+    // Только видимые посты, скрытые модератором не считаются.
+    pub(crate) post_count: i64,
+}
+
+impl PublicProfile {
+    pub(crate) fn new(user: User, post_count: i64) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            display_name: user.display_name,
+            bio: user.bio,
+            avatar_url: user.avatar_url,
+            joined_at: user.created_at,
+            post_count,
+        }
+    }
+}
+
+// Профиль заменяется целиком: отсутствующее или пустое поле очищается.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct UpdateProfile {
+    pub(crate) display_name: Option<String>,
+    pub(crate) bio: Option<String>,
+    pub(crate) avatar_url: Option<String>,
+}
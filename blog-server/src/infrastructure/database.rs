@@ -1,8 +1,32 @@
-use sqlx::migrate::MigrateError;
+use sqlx::migrate::{Migrate, MigrateError};
+use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 
+use crate::infrastructure::config::DatabaseConfig;
+
 static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
 
+pub async fn create_pool(cfg: &DatabaseConfig) -> Result<PgPool, sqlx::Error> {
+    PgPoolOptions::new()
+        .max_connections(cfg.max_connections)
+        .min_connections(cfg.min_connections)
+        .acquire_timeout(cfg.acquire_timeout)
+        .connect(&cfg.url)
+        .await
+}
+
 pub async fn run(pool: &PgPool) -> Result<(), MigrateError> {
     MIGRATOR.run(pool).await
-}
\ No newline at end of file
+}
+
+// Версии миграций из MIGRATOR, ещё не применённые к базе (или применённые с ошибкой).
+pub async fn pending_migrations(pool: &PgPool) -> Result<Vec<i64>, MigrateError> {
+    let mut conn = pool.acquire().await?;
+    let applied = conn.list_applied_migrations().await?;
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| m.version)
+        .filter(|version| !applied.iter().any(|a| a.version == *version))
+        .collect())
+}
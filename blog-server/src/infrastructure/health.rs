@@ -0,0 +1,224 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::infrastructure::database;
+
+// Проверка зависимостей не должна висеть дольше, чем ждёт балансировщик.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ListenerState {
+    Starting,
+    Serving,
+    Stopping,
+}
+
+impl ListenerState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => ListenerState::Serving,
+            2 => ListenerState::Stopping,
+            _ => ListenerState::Starting,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DependencyCheck {
+    pub(crate) up: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl DependencyCheck {
+    fn up(latency: Option<Duration>) -> Self {
+        Self {
+            up: true,
+            latency_ms: latency.map(|d| d.as_millis()),
+            detail: None,
+        }
+    }
+
+    fn down(detail: impl Into<String>) -> Self {
+        Self {
+            up: false,
+            latency_ms: None,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Checks {
+    pub(crate) database: DependencyCheck,
+    pub(crate) migrations: DependencyCheck,
+    pub(crate) grpc: DependencyCheck,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Readiness {
+    pub(crate) ready: bool,
+    pub(crate) checks: Checks,
+}
+
+// Внешние зависимости, которые проверяет readiness.
+#[async_trait]
+pub(crate) trait Dependencies: Send + Sync {
+    async fn database(&self) -> DependencyCheck;
+    async fn migrations(&self) -> DependencyCheck;
+}
+
+struct PgDependencies {
+    pool: PgPool,
+}
+
+#[async_trait]
+impl Dependencies for PgDependencies {
+    async fn database(&self) -> DependencyCheck {
+        let start = Instant::now();
+        let ping = sqlx::query("SELECT 1").execute(&self.pool);
+        match tokio::time::timeout(CHECK_TIMEOUT, ping).await {
+            Ok(Ok(_)) => DependencyCheck::up(Some(start.elapsed())),
+            Ok(Err(err)) => DependencyCheck::down(err.to_string()),
+            Err(_) => DependencyCheck::down("timed out"),
+        }
+    }
+
+    async fn migrations(&self) -> DependencyCheck {
+        let pending = database::pending_migrations(&self.pool);
+        match tokio::time::timeout(CHECK_TIMEOUT, pending).await {
+            Ok(Ok(pending)) if pending.is_empty() => DependencyCheck::up(None),
+            Ok(Ok(pending)) => DependencyCheck::down(format!("pending migrations: {pending:?}")),
+            Ok(Err(err)) => DependencyCheck::down(err.to_string()),
+            Err(_) => DependencyCheck::down("timed out"),
+        }
+    }
+}
+
+// Готовность сервиса принимать трафик: доступность PostgreSQL, применённые миграции
+// и состояние gRPC-листенера, которое выставляет server::run.
+pub(crate) struct HealthCheck {
+    dependencies: Arc<dyn Dependencies>,
+    grpc: AtomicU8,
+}
+
+impl HealthCheck {
+    pub(crate) fn new(pool: PgPool) -> Self {
+        Self::with_dependencies(Arc::new(PgDependencies { pool }))
+    }
+
+    pub(crate) fn with_dependencies(dependencies: Arc<dyn Dependencies>) -> Self {
+        Self {
+            dependencies,
+            grpc: AtomicU8::new(ListenerState::Starting as u8),
+        }
+    }
+
+    pub(crate) fn set_grpc(&self, state: ListenerState) {
+        self.grpc.store(state as u8, Ordering::Relaxed);
+    }
+
+    pub(crate) async fn readiness(&self) -> Readiness {
+        let checks = Checks {
+            database: self.dependencies.database().await,
+            migrations: self.dependencies.migrations().await,
+            grpc: match ListenerState::from_u8(self.grpc.load(Ordering::Relaxed)) {
+                ListenerState::Serving => DependencyCheck::up(None),
+                state => DependencyCheck::down(format!("listener is {state:?}").to_lowercase()),
+            },
+        };
+        Readiness {
+            ready: checks.database.up && checks.migrations.up && checks.grpc.up,
+            checks,
+        }
+    }
+
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use actix_web::{http::StatusCode, test, web, App};
+
+    use super::*;
+    use crate::presentation::http_handlers;
+
+    // Зависимости, доступность БД которых переключается из теста.
+    pub(crate) struct FakeDependencies {
+        pub(crate) database_up: AtomicBool,
+    }
+
+    #[async_trait]
+    impl Dependencies for FakeDependencies {
+        async fn database(&self) -> DependencyCheck {
+            if self.database_up.load(Ordering::Relaxed) {
+                DependencyCheck::up(Some(Duration::from_millis(1)))
+            } else {
+                DependencyCheck::down("connection refused")
+            }
+        }
+
+        async fn migrations(&self) -> DependencyCheck {
+            DependencyCheck::up(None)
+        }
+    }
+
+    pub(crate) fn health() -> (Arc<HealthCheck>, Arc<FakeDependencies>) {
+        let dependencies = Arc::new(FakeDependencies {
+            database_up: AtomicBool::new(true),
+        });
+        let health = Arc::new(HealthCheck::with_dependencies(dependencies.clone()));
+        (health, dependencies)
+    }
+
+    async fn ready(health: &Arc<HealthCheck>) -> (StatusCode, serde_json::Value) {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::from(Arc::clone(health)))
+                .configure(http_handlers::configure),
+        )
+        .await;
+        let res = test::call_service(&app, test::TestRequest::get().uri("/health/ready").to_request()).await;
+        let status = res.status();
+        (status, test::read_body_json(res).await)
+    }
+
+    #[actix_web::test]
+    async fn ready_only_while_grpc_serves_and_database_is_up() {
+        let (health, dependencies) = health();
+        let (status, json) = ready(&health).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(json["checks"]["grpc"]["detail"], "listener is starting");
+
+        health.set_grpc(ListenerState::Serving);
+        let (status, json) = ready(&health).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["ready"], true);
+        assert_eq!(json["checks"]["database"]["up"], true);
+        assert_eq!(json["checks"]["database"]["latency_ms"], 1);
+        assert_eq!(json["checks"]["migrations"]["up"], true);
+        assert_eq!(json["checks"]["grpc"]["up"], true);
+
+        dependencies.database_up.store(false, Ordering::Relaxed);
+        let (status, json) = ready(&health).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(json["ready"], false);
+        assert_eq!(json["checks"]["database"]["detail"], "connection refused");
+
+        dependencies.database_up.store(true, Ordering::Relaxed);
+        health.set_grpc(ListenerState::Stopping);
+        let (status, json) = ready(&health).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(json["checks"]["grpc"]["up"], false);
+        assert_eq!(json["checks"]["grpc"]["detail"], "listener is stopping");
+    }
+}
@@ -1,51 +1,409 @@
+use std::collections::HashMap;
+use std::path::Path;
 
-use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey,
+use jsonwebtoken::{encode, decode, decode_header, Algorithm, Header, Validation, EncodingKey, DecodingKey,
                    errors::Error as JwtError};
+use jsonwebtoken::jwk::{AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+                        OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType};
 use serde::{Deserialize, Serialize};
-use chrono::{Utc, Duration};
-use anyhow::Result;
+use chrono::{DateTime, Utc, Duration};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ed25519_dalek::VerifyingKey;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::traits::PublicKeyParts;
+use rsa::RsaPublicKey;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
 
+use crate::domain::role::Role;
+use crate::infrastructure::config::{JwtAlgorithm, JwtConfig};
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Claims {
-    user_id: i64,
-    username: String,
-    exp: i64,
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Claims {
+    pub(crate) jti: Uuid,
+    pub(crate) user_id: i64,
+    pub(crate) username: String,
+    // Роль на момент выдачи; AuthService::authenticate отклоняет токен, если роль
+    // с тех пор изменилась.
+    #[serde(default)]
+    pub(crate) role: Role,
+    // Поколение сессий пользователя на момент выдачи, см. `RevocationRepository`.
+    pub(crate) generation: i64,
+    pub(crate) iat: i64,
+    pub(crate) exp: i64,
+}
+
+// Токен для ссылок из писем (подтверждение email и т.п.). Подписывается тем же ключом,
+// что и access-токен, но с аудиторией `aud`: access-токены без `aud`, поэтому один вид
+// нельзя предъявить вместо другого.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ActionClaims {
+    pub(crate) jti: Uuid,
+    pub(crate) aud: String,
+    pub(crate) user_id: i64,
+    // Значение, к которому привязан токен, например адрес email: если оно изменилось,
+    // токен недействителен.
+    pub(crate) subject: String,
+    pub(crate) iat: i64,
+    pub(crate) exp: i64,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum KeyError {
+    #[error("failed to read key file {path}: {source}")]
+    Read {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("invalid key `{kid}`: {reason}")]
+    Invalid { kid: String, reason: String },
 }
 
-#[derive(Debug)]
-struct JwtService {
+// Подписывает токены одним ключом, а проверяет любым из известных: ключ выбирается
+// по `kid` из заголовка, поэтому после ротации старые токены остаются действительными,
+// пока их ключ не уберут из конфигурации.
+pub(crate) struct JwtService {
+    algorithm: Algorithm,
+    signing_kid: Option<String>,
     encoding: EncodingKey,
-    decoding: DecodingKey,
+    decoding: HashMap<Option<String>, DecodingKey>,
+    jwks: JwkSet,
+    ttl: Duration,
+    refresh_ttl: Duration,
+}
+
+// Непрозрачный refresh-токен, выдаётся клиенту; в БД хранится только `hash`.
+pub(crate) struct IssuedRefreshToken {
+    pub(crate) token: String,
+    pub(crate) hash: String,
+    pub(crate) expires_at: DateTime<Utc>,
 }
 
 impl JwtService {
-    fn new(secret: &str) -> Self {
+    // HS256 с общим секретом; `kid` не используется, JWKS пуст.
+    pub(crate) fn new(
+        secret: &str,
+        ttl: std::time::Duration,
+        refresh_ttl: std::time::Duration,
+    ) -> Self {
         JwtService {
+            algorithm: Algorithm::HS256,
+            signing_kid: None,
             encoding: EncodingKey::from_secret(secret.as_bytes()),
-            decoding: DecodingKey::from_secret(secret.as_bytes()),
+            decoding: HashMap::from([(None, DecodingKey::from_secret(secret.as_bytes()))]),
+            jwks: JwkSet { keys: Vec::new() },
+            ttl: Duration::from_std(ttl).unwrap_or(Duration::minutes(15)),
+            refresh_ttl: Duration::from_std(refresh_ttl).unwrap_or(Duration::days(30)),
+        }
+    }
+
+    pub(crate) fn from_config(cfg: &JwtConfig) -> Result<Self, KeyError> {
+        let algorithm = match cfg.algorithm {
+            JwtAlgorithm::Hs256 => return Ok(Self::new(&cfg.secret, cfg.ttl, cfg.refresh_ttl)),
+            JwtAlgorithm::Rs256 => Algorithm::RS256,
+            JwtAlgorithm::EdDsa => Algorithm::EdDSA,
+        };
+
+        let mut signing = None;
+        let mut decoding = HashMap::new();
+        let mut jwks = JwkSet { keys: Vec::new() };
+        for key in &cfg.keys {
+            let invalid = |reason: String| KeyError::Invalid {
+                kid: key.kid.clone(),
+                reason,
+            };
+            let jwk = public_jwk(algorithm, &key.kid, &read_key(&key.public_key)?).map_err(invalid)?;
+            decoding.insert(
+                Some(key.kid.clone()),
+                DecodingKey::from_jwk(&jwk).map_err(|err| invalid(err.to_string()))?,
+            );
+            jwks.keys.push(jwk);
+
+            if let Some(path) = &key.private_key {
+                let pem = read_key(path)?;
+                let encoding = match algorithm {
+                    Algorithm::RS256 => EncodingKey::from_rsa_pem(pem.as_bytes()),
+                    _ => EncodingKey::from_ed_pem(pem.as_bytes()),
+                }
+                .map_err(|err| invalid(err.to_string()))?;
+                signing = Some((key.kid.clone(), encoding));
+            }
         }
+        let (signing_kid, encoding) = signing.ok_or_else(|| KeyError::Invalid {
+            kid: String::new(),
+            reason: "no signing key configured".into(),
+        })?;
+
+        let service = JwtService {
+            algorithm,
+            signing_kid: Some(signing_kid.clone()),
+            encoding,
+            decoding,
+            jwks,
+            ttl: Duration::from_std(cfg.ttl).unwrap_or(Duration::minutes(15)),
+            refresh_ttl: Duration::from_std(cfg.refresh_ttl).unwrap_or(Duration::days(30)),
+        };
+        // Приватный и публичный ключ могут оказаться от разных пар — проверяем при старте.
+        let probe = service
+            .generate_token(0, "key-check", Role::Reader, 0)
+            .map_err(|err| KeyError::Invalid {
+                kid: signing_kid.clone(),
+                reason: err.to_string(),
+            })?;
+        service.verify_token(&probe).map_err(|_| KeyError::Invalid {
+            kid: signing_kid,
+            reason: "private key does not match public key".into(),
+        })?;
+        Ok(service)
+    }
+
+    // Публичные ключи для `/.well-known/jwks.json`.
+    pub(crate) fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+
+    // Время жизни access-токена в секундах (поле `expires_in` ответа).
+    pub(crate) fn access_ttl_secs(&self) -> i64 {
+        self.ttl.num_seconds()
     }
 
-    fn generate_token(&self, user_id: i64, username: &str) -> Result<String, JwtError> {
+    pub(crate) fn generate_token(
+        &self,
+        user_id: i64,
+        username: &str,
+        role: Role,
+        generation: i64,
+    ) -> Result<String, JwtError> {
+        let now = Utc::now();
         let claims = Claims {
+            jti: Uuid::new_v4(),
             user_id,
             username: username.to_string(),
-            exp: (Utc::now() +
-                Duration::hours(24)).timestamp()
+            role,
+            generation,
+            iat: now.timestamp(),
+            exp: (now + self.ttl).timestamp()
         };
-        encode(&Header::default(), &claims, &self.encoding)
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.signing_kid.clone();
+        encode(&header, &claims, &self.encoding)
     }
 
-    fn verify_token(&self, token: &str) -> Result<Claims, JwtError> {
+    pub(crate) fn verify_token(&self, token: &str) -> Result<Claims, JwtError> {
+        let kid = decode_header(token)?.kid.or_else(|| self.signing_kid.clone());
+        let key = self
+            .decoding
+            .get(&kid)
+            .ok_or_else(|| JwtError::from(jsonwebtoken::errors::ErrorKind::InvalidKeyFormat))?;
         let token_data = decode::<Claims>(
             token,
-            &self.decoding,
-            &Validation::default()
+            key,
+            &Validation::new(self.algorithm)
         )?;
         if token_data.claims.exp < Utc::now().timestamp() {
             return Err(JwtError::from(jsonwebtoken::errors::ErrorKind::ExpiredSignature));
         }
         Ok(token_data.claims)
     }
-}
\ No newline at end of file
+
+    pub(crate) fn generate_action_token(
+        &self,
+        audience: &str,
+        user_id: i64,
+        subject: &str,
+        ttl: std::time::Duration,
+    ) -> Result<(String, ActionClaims), JwtError> {
+        let now = Utc::now();
+        let claims = ActionClaims {
+            jti: Uuid::new_v4(),
+            aud: audience.to_string(),
+            user_id,
+            subject: subject.to_string(),
+            iat: now.timestamp(),
+            exp: (now + Duration::from_std(ttl).unwrap_or(Duration::days(1))).timestamp(),
+        };
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.signing_kid.clone();
+        Ok((encode(&header, &claims, &self.encoding)?, claims))
+    }
+
+    pub(crate) fn verify_action_token(&self, audience: &str, token: &str) -> Result<ActionClaims, JwtError> {
+        let kid = decode_header(token)?.kid.or_else(|| self.signing_kid.clone());
+        let key = self
+            .decoding
+            .get(&kid)
+            .ok_or_else(|| JwtError::from(jsonwebtoken::errors::ErrorKind::InvalidKeyFormat))?;
+        let mut validation = Validation::new(self.algorithm);
+        validation.set_audience(&[audience]);
+        validation.set_required_spec_claims(&["exp", "aud"]);
+        Ok(decode::<ActionClaims>(token, key, &validation)?.claims)
+    }
+
+    pub(crate) fn generate_refresh_token(&self) -> IssuedRefreshToken {
+        let (token, hash) = generate_opaque_token();
+        IssuedRefreshToken {
+            token,
+            hash,
+            expires_at: Utc::now() + self.refresh_ttl,
+        }
+    }
+}
+
+// Случайный токен для refresh и сброса пароля и его хеш для хранения в БД.
+pub(crate) fn generate_opaque_token() -> (String, String) {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token = URL_SAFE_NO_PAD.encode(bytes);
+    let hash = hash_opaque_token(&token);
+    (token, hash)
+}
+
+// У токена 256 бит энтропии, поэтому достаточно SHA-256 без соли:
+// поиск по хешу остаётся возможным, а утечка таблицы не раскрывает токены.
+pub(crate) fn hash_opaque_token(token: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(token.as_bytes()))
+}
+
+fn read_key(path: &Path) -> Result<String, KeyError> {
+    std::fs::read_to_string(path).map_err(|source| KeyError::Read {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+fn public_jwk(algorithm: Algorithm, kid: &str, pem: &str) -> Result<Jwk, String> {
+    let (key_algorithm, parameters) = match algorithm {
+        Algorithm::RS256 => {
+            let key = RsaPublicKey::from_public_key_pem(pem)
+                .or_else(|_| RsaPublicKey::from_pkcs1_pem(pem))
+                .map_err(|err| err.to_string())?;
+            let parameters = AlgorithmParameters::RSA(RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: URL_SAFE_NO_PAD.encode(key.n().to_bytes_be()),
+                e: URL_SAFE_NO_PAD.encode(key.e().to_bytes_be()),
+            });
+            (KeyAlgorithm::RS256, parameters)
+        }
+        Algorithm::EdDSA => {
+            let key = VerifyingKey::from_public_key_pem(pem).map_err(|err| err.to_string())?;
+            let parameters = AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(key.as_bytes()),
+            });
+            (KeyAlgorithm::EdDSA, parameters)
+        }
+        other => return Err(format!("{other:?} keys are not published in JWKS")),
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ed25519_dalek::pkcs8::spki::der::pem::LineEnding;
+    use ed25519_dalek::pkcs8::{EncodePrivateKey, EncodePublicKey};
+    use ed25519_dalek::SigningKey;
+
+    use super::*;
+    use crate::infrastructure::config::JwtKeyConfig;
+
+    // Пишет пару Ed25519-ключей во временный каталог и возвращает описание ключа.
+    fn key_pair(dir: &Path, kid: &str, seed: u8, signing: bool) -> JwtKeyConfig {
+        let key = SigningKey::from_bytes(&[seed; 32]);
+        let public_key = dir.join(format!("{kid}.pub.pem"));
+        let private_key = dir.join(format!("{kid}.pem"));
+        std::fs::write(&public_key, key.verifying_key().to_public_key_pem(LineEnding::LF).unwrap()).unwrap();
+        std::fs::write(&private_key, key.to_pkcs8_pem(LineEnding::LF).unwrap().as_bytes()).unwrap();
+        JwtKeyConfig {
+            kid: kid.to_string(),
+            public_key,
+            private_key: signing.then_some(private_key),
+        }
+    }
+
+    fn config(keys: Vec<JwtKeyConfig>) -> JwtConfig {
+        JwtConfig {
+            algorithm: JwtAlgorithm::EdDsa,
+            secret: String::new(),
+            keys,
+            ttl: std::time::Duration::from_secs(900),
+            refresh_ttl: std::time::Duration::from_secs(3600),
+        }
+    }
+
+    #[test]
+    fn rotated_keys_keep_verifying_old_tokens() {
+        let dir: PathBuf = std::env::temp_dir().join(format!("blog-jwt-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let old = JwtService::from_config(&config(vec![key_pair(&dir, "old", 1, true)])).unwrap();
+        let old_token = old.generate_token(1, "ivan", Role::Author, 0).unwrap();
+
+        let rotated = JwtService::from_config(&config(vec![
+            key_pair(&dir, "new", 2, true),
+            key_pair(&dir, "old", 1, false),
+        ]))
+        .unwrap();
+        assert_eq!(rotated.verify_token(&old_token).unwrap().user_id, 1);
+        let new_token = rotated.generate_token(1, "ivan", Role::Author, 0).unwrap();
+        assert_eq!(decode_header(&new_token).unwrap().kid.as_deref(), Some("new"));
+        assert!(rotated.jwks().find("old").is_some() && rotated.jwks().find("new").is_some());
+
+        // Когда старый ключ убран из конфигурации, его токены больше не принимаются.
+        let retired = JwtService::from_config(&config(vec![key_pair(&dir, "new", 2, true)])).unwrap();
+        assert!(retired.verify_token(&old_token).is_err());
+        assert!(retired.verify_token(&new_token).is_ok());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn mismatched_key_pair_is_rejected() {
+        let dir: PathBuf = std::env::temp_dir().join(format!("blog-jwt-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut key = key_pair(&dir, "a", 3, true);
+        key.public_key = key_pair(&dir, "b", 4, false).public_key;
+        assert!(matches!(
+            JwtService::from_config(&config(vec![key])),
+            Err(KeyError::Invalid { .. })
+        ));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn action_tokens_are_bound_to_their_audience() {
+        let jwt = JwtService::new(
+            "test-secret-test-secret-test-secret",
+            std::time::Duration::from_secs(900),
+            std::time::Duration::from_secs(3600),
+        );
+        let (token, issued) = jwt
+            .generate_action_token("verify-email", 7, "ivan@example.com", std::time::Duration::from_secs(60))
+            .unwrap();
+        let claims = jwt.verify_action_token("verify-email", &token).unwrap();
+        assert_eq!((claims.jti, claims.user_id), (issued.jti, 7));
+        assert_eq!(claims.subject, "ivan@example.com");
+
+        assert!(jwt.verify_action_token("password-reset", &token).is_err());
+        assert!(jwt.verify_token(&token).is_err());
+        let access = jwt.generate_token(7, "ivan", Role::Author, 0).unwrap();
+        assert!(jwt.verify_action_token("verify-email", &access).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{InitError, RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer, Registry};
+
+use crate::infrastructure::config::{LogConfig, LogFileConfig, LogFormat, LogRotation};
+
+pub(crate) type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+// Держит фоновый поток записи в файл; при drop буфер сбрасывается на диск,
+// поэтому guard должен жить до конца main.
+pub(crate) struct LogGuard {
+    _file: Option<WorkerGuard>,
+}
+
+fn layer<W>(format: LogFormat, writer: W, ansi: bool) -> BoxedLayer
+where
+    W: for<'w> fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = fmt::layer().with_writer(writer).with_ansi(ansi);
+    match format {
+        LogFormat::Pretty => layer.boxed(),
+        // В `spans` попадает вся цепочка, поэтому request_id и user_id видны и в записях
+        // из вложенных span сервисов и репозиториев.
+        LogFormat::Json => layer.json().with_current_span(false).with_span_list(true).boxed(),
+    }
+}
+
+fn file_appender(cfg: &LogFileConfig) -> Result<RollingFileAppender, InitError> {
+    let rotation = match cfg.rotation {
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Never => Rotation::NEVER,
+    };
+    RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(&cfg.prefix)
+        .filename_suffix("log")
+        .build(&cfg.directory)
+}
+
+// Настраивает глобальный subscriber: stdout, при наличии каталога файл с ротацией
+// и дополнительный слой трассировки. Записи библиотек на `log` (sqlx, actix) тоже
+// проходят через фильтр.
+pub(crate) fn init(cfg: &LogConfig, tracing_layer: BoxedLayer) -> anyhow::Result<LogGuard> {
+    let mut layers: Vec<BoxedLayer> = vec![layer(cfg.format, std::io::stdout, true), tracing_layer];
+
+    let mut guard = None;
+    if let Some(file) = &cfg.file {
+        let (writer, file_guard) = tracing_appender::non_blocking(file_appender(file)?);
+        layers.push(layer(cfg.format, writer, false));
+        guard = Some(file_guard);
+    }
+
+    tracing_subscriber::registry()
+        .with(layers)
+        .with(EnvFilter::try_new(&cfg.filter)?)
+        .try_init()?;
+
+    Ok(LogGuard { _file: guard })
+}
@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use lettre::message::header::ContentType;
+use lettre::message::{Mailbox, Message};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use thiserror::Error;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::infrastructure::config::{MailConfig, MailTransport};
+
+// Текстовое письмо; отправитель берётся из конфигурации транспорта.
+#[derive(Debug, Clone)]
+pub(crate) struct Email {
+    pub(crate) to: String,
+    pub(crate) subject: String,
+    pub(crate) body: String,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum MailError {
+    #[error("invalid address: {0}")]
+    Address(#[from] lettre::address::AddressError),
+    #[error("failed to build message: {0}")]
+    Message(#[from] lettre::error::Error),
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+    #[error("failed to write outbox: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), MailError>;
+}
+
+fn message(from: &Mailbox, email: Email) -> Result<Message, MailError> {
+    Ok(Message::builder()
+        .from(from.clone())
+        .to(email.to.parse()?)
+        .subject(email.subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(email.body)?)
+}
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub(crate) fn new(url: &str, from: Mailbox) -> Result<Self, MailError> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(url)?.build();
+        Ok(Self { transport, from })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: Email) -> Result<(), MailError> {
+        self.transport.send(message(&self.from, email)?).await?;
+        Ok(())
+    }
+}
+
+// Транспорт для локальной разработки: письмо целиком сохраняется файлом .eml в `dir`,
+// а без каталога пишется в лог.
+pub struct OutboxMailer {
+    dir: Option<PathBuf>,
+    from: Mailbox,
+}
+
+impl OutboxMailer {
+    pub(crate) fn new(dir: Option<PathBuf>, from: Mailbox) -> Self {
+        Self { dir, from }
+    }
+}
+
+#[async_trait]
+impl Mailer for OutboxMailer {
+    async fn send(&self, email: Email) -> Result<(), MailError> {
+        let to = email.to.clone();
+        let formatted = message(&self.from, email)?.formatted();
+        match &self.dir {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)?;
+                let path = dir.join(format!("{}-{}.eml", Utc::now().format("%Y%m%dT%H%M%S"), Uuid::new_v4()));
+                std::fs::write(&path, formatted)?;
+                info!(to = %to, path = %path.display(), "email written to outbox");
+            }
+            None => info!(to = %to, message = %String::from_utf8_lossy(&formatted), "outbox email"),
+        }
+        Ok(())
+    }
+}
+
+// Ссылка из письма: токен добавляется к настроенному URL как параметр запроса.
+pub(crate) fn link_with_token(url: &str, token: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{url}{separator}token={token}")
+}
+
+pub(crate) fn from_config(cfg: &MailConfig) -> anyhow::Result<Arc<dyn Mailer>> {
+    let from: Mailbox = cfg.from.parse()?;
+    Ok(match (cfg.transport, &cfg.smtp_url) {
+        (MailTransport::Smtp, Some(url)) => Arc::new(SmtpMailer::new(url, from)?),
+        (MailTransport::Smtp, None) => anyhow::bail!("SMTP transport requires smtp_url"),
+        (MailTransport::Outbox, _) => Arc::new(OutboxMailer::new(cfg.outbox_dir.clone(), from)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn outbox_writes_eml_file() {
+        let dir = std::env::temp_dir().join(format!("blog-outbox-{}", Uuid::new_v4()));
+        let mailer = OutboxMailer::new(Some(dir.clone()), "Blog <noreply@localhost>".parse().unwrap());
+        mailer
+            .send(Email {
+                to: "ivan@example.com".into(),
+                subject: "Hello".into(),
+                body: "link: http://localhost/verify?token=abc".into(),
+            })
+            .await
+            .unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(files.len(), 1);
+        let eml = std::fs::read_to_string(files[0].as_ref().unwrap().path()).unwrap();
+        assert!(eml.contains("To: ivan@example.com"), "{eml}");
+        assert!(eml.contains("token=abc"), "{eml}");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
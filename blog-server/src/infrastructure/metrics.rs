@@ -0,0 +1,129 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use sqlx::PgPool;
+
+// Границы бакетов задержки в секундах: от 5 мс до 10 с.
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+// Метрики процесса. Счётчики общие для HTTP и gRPC, поэтому это глобальный реестр,
+// а не часть AppState: инкременты встречаются и в сервисах, и в middleware.
+pub(crate) fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+pub(crate) struct Metrics {
+    registry: Registry,
+    pub(crate) http_requests: IntCounterVec,
+    pub(crate) http_duration: HistogramVec,
+    pub(crate) grpc_requests: IntCounterVec,
+    pub(crate) grpc_duration: HistogramVec,
+    db_pool_connections: IntGauge,
+    db_pool_idle: IntGauge,
+    db_pool_max: IntGauge,
+    pub(crate) db_pool_timeouts: IntCounter,
+    pub(crate) registrations: IntCounter,
+    pub(crate) logins: IntCounterVec,
+    pub(crate) posts_created: IntCounter,
+}
+
+fn register<T: prometheus::core::Collector + Clone + 'static>(registry: &Registry, metric: T) -> T {
+    registry
+        .register(Box::new(metric.clone()))
+        .expect("metric names are unique");
+    metric
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("blog".into()), None).expect("valid prefix");
+        let histogram = |name: &str, help: &str, labels: &[&str]| {
+            let opts = HistogramOpts::new(name, help).buckets(LATENCY_BUCKETS.to_vec());
+            register(&registry, HistogramVec::new(opts, labels).unwrap())
+        };
+        let counter_vec = |name: &str, help: &str, labels: &[&str]| {
+            register(&registry, IntCounterVec::new(Opts::new(name, help), labels).unwrap())
+        };
+        let counter = |name: &str, help: &str| register(&registry, IntCounter::new(name, help).unwrap());
+        let gauge = |name: &str, help: &str| register(&registry, IntGauge::new(name, help).unwrap());
+
+        Self {
+            http_requests: counter_vec(
+                "http_requests_total",
+                "HTTP requests by method, route and status",
+                &["method", "route", "status"],
+            ),
+            http_duration: histogram(
+                "http_request_duration_seconds",
+                "HTTP request latency",
+                &["method", "route", "status"],
+            ),
+            grpc_requests: counter_vec(
+                "grpc_requests_total",
+                "gRPC calls by method and status code",
+                &["method", "code"],
+            ),
+            grpc_duration: histogram(
+                "grpc_request_duration_seconds",
+                "gRPC call latency",
+                &["method", "code"],
+            ),
+            db_pool_connections: gauge("db_pool_connections", "Open database connections"),
+            db_pool_idle: gauge("db_pool_idle_connections", "Idle database connections"),
+            db_pool_max: gauge("db_pool_max_connections", "Configured database pool size"),
+            db_pool_timeouts: counter(
+                "db_pool_acquire_timeouts_total",
+                "Requests that failed waiting for a database connection",
+            ),
+            registrations: counter("registrations_total", "Registered users"),
+            logins: counter_vec("logins_total", "Login attempts by result", &["result"]),
+            posts_created: counter("posts_created_total", "Created posts"),
+            registry,
+        }
+    }
+
+    // Состояние пула снимается в момент scrape, отдельная фоновая задача не нужна.
+    pub(crate) fn render(&self, pool: &PgPool) -> String {
+        self.db_pool_connections.set(i64::from(pool.size()));
+        self.db_pool_idle.set(pool.num_idle() as i64);
+        self.db_pool_max
+            .set(i64::from(pool.options().get_max_connections()));
+
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .expect("text encoding does not fail");
+        String::from_utf8(buf).expect("prometheus text format is UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn renders_pool_and_domain_metrics() {
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .max_connections(7)
+            .connect_lazy("postgres://localhost/blog")
+            .unwrap();
+        metrics().posts_created.inc();
+        metrics()
+            .http_requests
+            .with_label_values(&["GET", "/api/posts/{id}", "200"])
+            .inc();
+
+        let text = metrics().render(&pool);
+        assert!(text.contains("blog_db_pool_max_connections 7"), "{text}");
+        assert!(text.contains("blog_posts_created_total"), "{text}");
+        assert!(
+            text.contains(r#"blog_http_requests_total{method="GET",route="/api/posts/{id}",status="200"}"#),
+            "{text}"
+        );
+    }
+}
@@ -1,4 +1,8 @@
 pub(crate) mod database;
+pub(crate) mod health;
 pub(crate) mod jwt;
-mod logging;
-pub(crate) mod config;
\ No newline at end of file
+pub(crate) mod logging;
+pub(crate) mod metrics;
+pub(crate) mod config;
+pub(crate) mod security;
+pub(crate) mod telemetry;pub(crate) mod mailer;
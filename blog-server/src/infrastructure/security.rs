@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, KeyId, Params, ParamsBuilder, Version, ARGON2ID_IDENT,
+};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::infrastructure::config::PasswordHashConfig;
+
+#[derive(Debug, Error)]
+pub(crate) enum PasswordHashError {
+    #[error("password hashing failed: {0}")]
+    Hash(password_hash::Error),
+    #[error("invalid Argon2 parameters: {0}")]
+    Params(argon2::Error),
+    #[error("password hash requires pepper {0}, which is not configured")]
+    UnknownPepper(String),
+    #[error("password hashing task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+// Ошибки argon2/password-hash без std::error::Error, поэтому без #[from].
+impl From<password_hash::Error> for PasswordHashError {
+    fn from(err: password_hash::Error) -> Self {
+        Self::Hash(err)
+    }
+}
+
+impl From<argon2::Error> for PasswordHashError {
+    fn from(err: argon2::Error) -> Self {
+        Self::Params(err)
+    }
+}
+
+// `Outdated` — пароль верный, но хеш получен с прежними параметрами или без
+// текущего перца, и его стоит пересчитать.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PasswordCheck {
+    Invalid,
+    Valid,
+    Outdated,
+}
+
+struct Pepper {
+    secret: Vec<u8>,
+    id: KeyId,
+}
+
+struct Inner {
+    params: Params,
+    pepper: Option<Pepper>,
+    // Хеш с текущими параметрами для проверки паролей несуществующих пользователей.
+    dummy: String,
+}
+
+// Argon2id с параметрами из конфигурации. Перец передаётся в Argon2 как секретный
+// ключ, а в PHC-строке хеша остаётся только его отпечаток (`keyid`): по нему видно,
+// нужен ли перец для проверки. Хеширование занимает десятки миллисекунд CPU,
+// поэтому выполняется в пуле блокирующих потоков, а не в воркерах actix/tokio.
+#[derive(Clone)]
+pub struct Argon2Hasher {
+    inner: Arc<Inner>,
+}
+
+impl Argon2Hasher {
+    pub(crate) fn new(cfg: &PasswordHashConfig) -> Result<Self, PasswordHashError> {
+        let mut builder = ParamsBuilder::new();
+        builder
+            .m_cost(cfg.memory_kib)
+            .t_cost(cfg.iterations)
+            .p_cost(cfg.parallelism);
+        let pepper = match &cfg.pepper {
+            Some(secret) => {
+                let id = KeyId::new(&Sha256::digest(secret.as_bytes())[..4])?;
+                builder.keyid(id);
+                Some(Pepper {
+                    secret: secret.as_bytes().to_vec(),
+                    id,
+                })
+            }
+            None => None,
+        };
+        let mut hasher = Self {
+            inner: Arc::new(Inner {
+                params: builder.build()?,
+                pepper,
+                dummy: String::new(),
+            }),
+        };
+        let dummy = hasher.hash_blocking("dummy-password")?;
+        Arc::get_mut(&mut hasher.inner).expect("hasher is not shared yet").dummy = dummy;
+        Ok(hasher)
+    }
+
+    pub(crate) async fn hash(&self, password: &str) -> Result<String, PasswordHashError> {
+        let hasher = self.clone();
+        let password = password.to_owned();
+        tokio::task::spawn_blocking(move || hasher.hash_blocking(&password)).await?
+    }
+
+    pub(crate) async fn verify(&self, password: &str, hash: &str) -> Result<PasswordCheck, PasswordHashError> {
+        let hasher = self.clone();
+        let password = password.to_owned();
+        let hash = hash.to_owned();
+        tokio::task::spawn_blocking(move || hasher.verify_blocking(&password, &hash)).await?
+    }
+
+    // Та же работа, что и при проверке настоящего пароля, но с заведомо неверным
+    // результатом: по времени ответа нельзя понять, существует ли пользователь.
+    pub(crate) async fn verify_dummy(&self, password: &str) {
+        let dummy = self.inner.dummy.clone();
+        let _ = self.verify(password, &dummy).await;
+    }
+
+    fn argon2(&self, peppered: bool) -> Result<Argon2<'_>, PasswordHashError> {
+        let params = self.inner.params.clone();
+        Ok(match (&self.inner.pepper, peppered) {
+            (Some(pepper), true) => {
+                Argon2::new_with_secret(&pepper.secret, Algorithm::Argon2id, Version::V0x13, params)?
+            }
+            _ => Argon2::new(Algorithm::Argon2id, Version::V0x13, params),
+        })
+    }
+
+    fn hash_blocking(&self, password: &str) -> Result<String, PasswordHashError> {
+        let salt = SaltString::generate(&mut OsRng);
+        let hash = self.argon2(true)?.hash_password(password.as_bytes(), &salt)?;
+        Ok(hash.to_string())
+    }
+
+    // Проверка идёт с параметрами из самого хеша, поэтому старые хеши остаются
+    // рабочими после смены настроек.
+    fn verify_blocking(&self, password: &str, hash: &str) -> Result<PasswordCheck, PasswordHashError> {
+        let parsed = PasswordHash::new(hash)?;
+        let params = Params::try_from(&parsed)?;
+        let peppered = !params.keyid().is_empty();
+        let pepper_id = self.inner.pepper.as_ref().map(|p| p.id.as_bytes());
+        if peppered && pepper_id != Some(params.keyid()) {
+            return Err(PasswordHashError::UnknownPepper(hex(params.keyid())));
+        }
+
+        match self.argon2(peppered)?.verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => {}
+            Err(password_hash::Error::Password) => return Ok(PasswordCheck::Invalid),
+            Err(err) => return Err(err.into()),
+        }
+
+        let current = &self.inner.params;
+        let outdated = parsed.algorithm != ARGON2ID_IDENT
+            || parsed.version != Some(Version::V0x13.into())
+            || params.m_cost() != current.m_cost()
+            || params.t_cost() != current.t_cost()
+            || params.p_cost() != current.p_cost()
+            || params.keyid() != current.keyid();
+        Ok(if outdated {
+            PasswordCheck::Outdated
+        } else {
+            PasswordCheck::Valid
+        })
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hasher(iterations: u32, pepper: Option<&str>) -> Argon2Hasher {
+        Argon2Hasher::new(&PasswordHashConfig {
+            memory_kib: 1024,
+            iterations,
+            parallelism: 1,
+            pepper: pepper.map(str::to_string),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn detects_hashes_made_with_older_parameters() {
+        let old = hasher(1, None);
+        let hash = old.hash("correct-horse-42").await.unwrap();
+        assert_eq!(old.verify("correct-horse-42", &hash).await.unwrap(), PasswordCheck::Valid);
+        assert_eq!(old.verify("wrong", &hash).await.unwrap(), PasswordCheck::Invalid);
+
+        let current = hasher(2, None);
+        assert_eq!(current.verify("correct-horse-42", &hash).await.unwrap(), PasswordCheck::Outdated);
+        assert_eq!(current.verify("wrong", &hash).await.unwrap(), PasswordCheck::Invalid);
+    }
+
+    #[tokio::test]
+    async fn pepper_is_required_for_peppered_hashes() {
+        let plain = hasher(1, None).hash("correct-horse-42").await.unwrap();
+        let peppered = hasher(1, Some("pepper-pepper-pepper"));
+        // Хеш без перца проверяется и помечается для пересчёта.
+        assert_eq!(peppered.verify("correct-horse-42", &plain).await.unwrap(), PasswordCheck::Outdated);
+
+        let hash = peppered.hash("correct-horse-42").await.unwrap();
+        assert!(hash.contains("keyid="), "{hash}");
+        assert_eq!(peppered.verify("correct-horse-42", &hash).await.unwrap(), PasswordCheck::Valid);
+        let err = hasher(1, Some("another-pepper-value")).verify("correct-horse-42", &hash).await;
+        assert!(matches!(err, Err(PasswordHashError::UnknownPepper(_))), "{err:?}");
+        assert!(hasher(1, None).verify("correct-horse-42", &hash).await.is_err());
+    }
+}
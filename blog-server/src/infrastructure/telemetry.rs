@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use opentelemetry::global;
+use opentelemetry::trace::{TraceContextExt, TracerProvider};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing::{field, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::Layer;
+
+use crate::infrastructure::config::TelemetryConfig;
+use crate::infrastructure::logging::BoxedLayer;
+
+// Заголовки W3C Trace Context: их читает и пишет TraceContextPropagator.
+const TRACE_HEADERS: [&str; 2] = ["traceparent", "tracestate"];
+
+// Провайдер трасс; при drop досылает накопленные span-ы в коллектор.
+pub(crate) struct Telemetry {
+    provider: SdkTracerProvider,
+}
+
+impl Telemetry {
+    pub(crate) fn init(cfg: &TelemetryConfig) -> anyhow::Result<Self> {
+        let mut builder = SdkTracerProvider::builder()
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                cfg.sample_ratio,
+            ))))
+            .with_resource(
+                Resource::builder()
+                    .with_service_name(cfg.service_name.clone())
+                    .build(),
+            );
+        if let Some(endpoint) = &cfg.otlp_endpoint {
+            let exporter = SpanExporter::builder()
+                .with_http()
+                .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+                .build()?;
+            builder = builder.with_batch_exporter(exporter);
+        }
+        let provider = builder.build();
+
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        global::set_tracer_provider(provider.clone());
+        Ok(Self { provider })
+    }
+
+    // Слой tracing → OpenTelemetry: span-ы tracing (запросы, сервисы, репозитории)
+    // становятся span-ами трассы.
+    pub(crate) fn layer(&self) -> BoxedLayer {
+        let tracer = self.provider.tracer("blog-server");
+        tracing_opentelemetry::layer().with_tracer(tracer).boxed()
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(err) = self.provider.shutdown() {
+            eprintln!("failed to flush traces: {err}");
+        }
+    }
+}
+
+// Делает span продолжением входящей трассы и записывает в него trace_id для логов.
+// `header` читает заголовок из транспорта: HTTP и gRPC используют разные HeaderMap.
+pub(crate) fn continue_trace<'a>(span: &Span, header: impl Fn(&str) -> Option<&'a str>) {
+    let carrier: HashMap<String, String> = TRACE_HEADERS
+        .iter()
+        .filter_map(|name| header(name).map(|value| (name.to_string(), value.to_string())))
+        .collect();
+    let parent = global::get_text_map_propagator(|propagator| propagator.extract(&carrier));
+    // Ошибка означает, что span отфильтрован и в трассу всё равно не попадёт.
+    let _ = span.set_parent(parent);
+
+    let cx = span.context();
+    let span_context = cx.span().span_context().clone();
+    if span_context.is_valid() {
+        span.record("trace_id", field::display(span_context.trace_id()));
+    }
+}
+
+// traceparent/tracestate текущего span для ответа, чтобы клиент мог связать
+// свой вызов с трассой сервера.
+pub(crate) fn trace_headers(span: &Span) -> HashMap<String, String> {
+    let mut carrier = HashMap::new();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&span.context(), &mut carrier)
+    });
+    carrier
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::info_span;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn continues_incoming_trace() {
+        let telemetry = Telemetry::init(&TelemetryConfig {
+            otlp_endpoint: None,
+            service_name: "test".into(),
+            sample_ratio: 0.0,
+        })
+        .unwrap();
+        let subscriber = tracing_subscriber::registry().with(telemetry.layer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = info_span!("http_request", trace_id = field::Empty);
+            continue_trace(&span, |name| (name == "traceparent").then_some(TRACEPARENT));
+
+            // Тот же trace-id, новый span-id; сэмплирование наследуется от родителя.
+            let traceparent = &trace_headers(&span)["traceparent"];
+            assert!(traceparent.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+            assert!(traceparent.ends_with("-01"));
+            assert_ne!(traceparent, TRACEPARENT);
+        });
+    }
+}
@@ -6,21 +6,108 @@ mod data;
 mod infrastructure;
 mod presentation;
 
-use infrastructure::{config::Config, database};
-use sqlx::postgres::PgPoolOptions;
+use std::sync::Arc;
+
+use anyhow::Context;
+
+use application::{
+    admin_service::AdminService, auth_service::AuthService, blog_service::BlogService, email_verification::EmailVerification,
+    password_reset::PasswordReset, profile_service::ProfileService, rate_limiter::RateLimiter,
+};
+use data::{
+    action_token_repository::PostgresActionTokenRepository,
+    audit_repository::PostgresAuditRepository,
+    password_reset_repository::PostgresPasswordResetRepository,
+    post_repository::PostgresPostRepository,
+    rate_limit_repository::{InMemoryRateLimitRepository, PostgresRateLimitRepository, RateLimitRepository},
+    refresh_token_repository::PostgresRefreshTokenRepository,
+    revocation_repository::PostgresRevocationRepository,
+    user_repository::PostgresUserRepository,
+};
+use infrastructure::{
+    config::{AppConfig, RateLimitBackend}, database, jwt::JwtService, logging, mailer, security::Argon2Hasher,
+    telemetry::Telemetry,
+};
+use server::{AppState, ServerSettings};
 
 #[actix_web::main]
-async fn main() -> std::io::Result<()> {
+async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
-    let cfg = Config::from_env().expect("invalid config");
+    let cfg = match AppConfig::load() {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(2);
+        }
+    };
+
+    let telemetry = Telemetry::init(&cfg.telemetry).context("failed to initialize tracing")?;
+    let _log_guard =
+        logging::init(&cfg.log, telemetry.layer()).context("failed to initialize logging")?;
 
-    let pool = PgPoolOptions::new()
-        .max_connections(10)
-        .connect(&cfg.database_url)
+    let pool = database::create_pool(&cfg.database)
         .await
-        .expect("failed to connect to database");
+        .context("failed to connect to database")?;
 
     // миграции
-    database::run(&pool).await.expect("migrations failed");
-    Ok(())
+    database::run(&pool).await.context("migrations failed")?;
+
+    let jwt = Arc::new(JwtService::from_config(&cfg.jwt).context("failed to load JWT keys")?);
+    let users = Arc::new(PostgresUserRepository::new(pool.clone()));
+    let refresh_tokens = Arc::new(PostgresRefreshTokenRepository::new(pool.clone()));
+    let revocations = Arc::new(PostgresRevocationRepository::new(pool.clone()));
+    let posts = Arc::new(PostgresPostRepository::new(pool.clone()));
+    let rate_limits: Arc<dyn RateLimitRepository> = match cfg.rate_limit.backend {
+        RateLimitBackend::Memory => Arc::new(InMemoryRateLimitRepository::new()),
+        RateLimitBackend::Postgres => Arc::new(PostgresRateLimitRepository::new(pool.clone())),
+    };
+    let limiter = Arc::new(RateLimiter::new(rate_limits, cfg.rate_limit));
+    let mailer = mailer::from_config(&cfg.mail).context("failed to configure mail transport")?;
+    let verification = Arc::new(EmailVerification::new(
+        Arc::new(PostgresActionTokenRepository::new(pool.clone())),
+        Arc::clone(&mailer),
+        Arc::clone(&jwt),
+        &cfg.mail,
+    ));
+    let hasher = Argon2Hasher::new(&cfg.password_hash).context("invalid password hashing parameters")?;
+    let password_reset = Arc::new(PasswordReset::new(
+        Arc::new(PostgresPasswordResetRepository::new(pool.clone())),
+        mailer,
+        &cfg.mail,
+    ));
+    // Один репозиторий и для записи событий сервисами, и для чтения администратором.
+    let audit = Arc::new(PostgresAuditRepository::new(pool.clone()));
+    let auth = Arc::new(AuthService::new(
+        Arc::clone(&users),
+        refresh_tokens,
+        revocations,
+        jwt,
+        Arc::clone(&limiter),
+        cfg.password,
+        hasher,
+        verification,
+        password_reset,
+        audit.clone(),
+    ));
+    let profiles = Arc::new(ProfileService::new(users.clone(), posts.clone()));
+    let admin = Arc::new(AdminService::new(
+        users,
+        Arc::clone(&auth),
+        audit.clone(),
+    ));
+    let state = AppState {
+        auth,
+        blog: Arc::new(BlogService::new(posts, audit)),
+        admin,
+        profiles,
+        limiter,
+    };
+
+    let settings = ServerSettings {
+        http_addr: cfg.http_addr,
+        grpc_addr: cfg.grpc_addr,
+        cors: cfg.cors,
+        scheduler: cfg.scheduler,
+    };
+    server::run(settings, state, pool).await
 }
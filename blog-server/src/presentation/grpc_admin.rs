@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+
+use crate::application::admin_service::AdminService;
+use crate::application::policy::Actor;
+use crate::domain::audit::{AuditEvent, AuditQuery};
+use crate::domain::error::BlogError;
+use crate::domain::role::Role;
+use crate::domain::user::{User, UserQuery};
+use crate::presentation::grpc_auth::GrpcIdentity;
+use crate::presentation::grpc_service::{datetime, proto, timestamp};
+
+use proto::admin_service_server::{AdminService as AdminRpc, AdminServiceServer};
+
+impl From<AuditEvent> for proto::AuditEvent {
+    fn from(event: AuditEvent) -> Self {
+        proto::AuditEvent {
+            id: event.id,
+            action: event.action.to_string(),
+            actor_id: event.actor_id,
+            subject_id: event.subject_id,
+            ip: event.ip,
+            user_agent: event.user_agent,
+            request_id: event.request_id,
+            before_hash: event.before_hash,
+            after_hash: event.after_hash,
+            details: event.details,
+            created_at: Some(timestamp(event.created_at)),
+        }
+    }
+}
+
+fn user_response(user: User) -> Response<proto::AdminUserResponse> {
+    Response::new(proto::AdminUserResponse {
+        user: Some(user.into()),
+    })
+}
+
+pub struct AdminGrpcService {
+    admin: Arc<AdminService>,
+}
+
+impl AdminGrpcService {
+    pub fn new(admin: Arc<AdminService>) -> Self {
+        Self { admin }
+    }
+
+    pub fn into_server(self) -> AdminServiceServer<Self> {
+        AdminServiceServer::new(self)
+    }
+
+    // Все методы сервиса закрыты GrpcAuthLayer, так что проверенный пользователь
+    // всегда лежит в extensions; без него запрос не дошёл бы до обработчика.
+    fn actor<T>(request: &Request<T>) -> Result<Actor, Status> {
+        let identity = request
+            .extensions()
+            .get::<GrpcIdentity>()
+            .ok_or(BlogError::MissingToken)?;
+        Ok(Actor {
+            user_id: identity.user.id,
+            role: identity.claims.role,
+            email_verified: identity.user.is_email_verified(),
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl AdminRpc for AdminGrpcService {
+    async fn list_users(
+        &self,
+        request: Request<proto::ListUsersRequest>,
+    ) -> Result<Response<proto::ListUsersResponse>, Status> {
+        let actor = Self::actor(&request)?;
+        let req = request.into_inner();
+        let query = UserQuery {
+            search: Some(req.search),
+            limit: (req.limit > 0).then_some(req.limit),
+            offset: Some(req.offset),
+        };
+        let page = self.admin.list_users(&actor, query).await?;
+        Ok(Response::new(proto::ListUsersResponse {
+            users: page.users.into_iter().map(Into::into).collect(),
+            total: page.total,
+            limit: page.limit,
+            offset: page.offset,
+        }))
+    }
+
+    async fn list_audit_events(
+        &self,
+        request: Request<proto::ListAuditEventsRequest>,
+    ) -> Result<Response<proto::ListAuditEventsResponse>, Status> {
+        let actor = Self::actor(&request)?;
+        let req = request.into_inner();
+        let action = Some(req.action)
+            .filter(|a| !a.is_empty())
+            .map(|a| a.parse())
+            .transpose()
+            .map_err(BlogError::Validation)?;
+        let query = AuditQuery {
+            actor_id: req.actor_id,
+            action,
+            from: datetime("from", req.from)?,
+            to: datetime("to", req.to)?,
+            limit: (req.limit > 0).then_some(req.limit),
+            offset: Some(req.offset),
+        };
+        let page = self.admin.list_audit_events(&actor, query).await?;
+        Ok(Response::new(proto::ListAuditEventsResponse {
+            events: page.events.into_iter().map(Into::into).collect(),
+            total: page.total,
+            limit: page.limit,
+            offset: page.offset,
+        }))
+    }
+
+    async fn suspend_user(
+        &self,
+        request: Request<proto::AdminUserRequest>,
+    ) -> Result<Response<proto::AdminUserResponse>, Status> {
+        let actor = Self::actor(&request)?;
+        let user = self
+            .admin
+            .set_suspended(&actor, request.into_inner().id, true)
+            .await?;
+        Ok(user_response(user))
+    }
+
+    async fn unsuspend_user(
+        &self,
+        request: Request<proto::AdminUserRequest>,
+    ) -> Result<Response<proto::AdminUserResponse>, Status> {
+        let actor = Self::actor(&request)?;
+        let user = self
+            .admin
+            .set_suspended(&actor, request.into_inner().id, false)
+            .await?;
+        Ok(user_response(user))
+    }
+
+    async fn set_user_role(
+        &self,
+        request: Request<proto::SetUserRoleRequest>,
+    ) -> Result<Response<proto::AdminUserResponse>, Status> {
+        let actor = Self::actor(&request)?;
+        let req = request.into_inner();
+        let role: Role = req.role.parse().map_err(BlogError::Validation)?;
+        let user = self.admin.set_role(&actor, req.id, role).await?;
+        Ok(user_response(user))
+    }
+
+    async fn force_password_reset(
+        &self,
+        request: Request<proto::AdminUserRequest>,
+    ) -> Result<Response<proto::ForcePasswordResetResponse>, Status> {
+        let actor = Self::actor(&request)?;
+        self.admin
+            .force_password_reset(&actor, request.into_inner().id)
+            .await?;
+        Ok(Response::new(proto::ForcePasswordResetResponse {}))
+    }
+
+    async fn delete_user(
+        &self,
+        request: Request<proto::AdminUserRequest>,
+    ) -> Result<Response<proto::DeleteUserResponse>, Status> {
+        let actor = Self::actor(&request)?;
+        self.admin.delete_user(&actor, request.into_inner().id).await?;
+        Ok(Response::new(proto::DeleteUserResponse {}))
+    }
+}
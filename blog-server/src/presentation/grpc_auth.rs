@@ -0,0 +1,225 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::future::BoxFuture;
+use tonic::codegen::http;
+use tonic::Status;
+use tower::{Layer, Service};
+use tracing::Span;
+
+use crate::application::auth_service::AuthService;
+use crate::data::user_repository::{PostgresUserRepository, UserRepository};
+use crate::domain::error::BlogError;
+use crate::domain::role::Permission;
+use crate::domain::user::User;
+use crate::infrastructure::jwt::Claims;
+
+// Методы, для которых нужна роль с правом, как RequirePermission для HTTP.
+const PROTECTED_METHODS: [(&str, Permission); 3] = [
+    ("/blog.BlogService/CreatePost", Permission::CreatePost),
+    ("/blog.BlogService/HidePost", Permission::HidePost),
+    ("/blog.BlogService/UnhidePost", Permission::HidePost),
+];
+
+// Все методы AdminService закрыты целиком, как область /api/admin.
+const ADMIN_SERVICE_PREFIX: &str = "/blog.AdminService/";
+
+fn required_permission(path: &str) -> Option<Permission> {
+    if path.starts_with(ADMIN_SERVICE_PREFIX) {
+        return Some(Permission::ManageUsers);
+    }
+    PROTECTED_METHODS
+        .iter()
+        .find(|(method, _)| *method == path)
+        .map(|(_, permission)| *permission)
+}
+
+// Пользователь, проверенный слоем; BlogGrpcService::authenticate берёт его из
+// extensions запроса, чтобы не проверять токен второй раз.
+#[derive(Clone)]
+pub(crate) struct GrpcIdentity {
+    pub(crate) user: User,
+    pub(crate) claims: Claims,
+}
+
+pub(crate) struct GrpcAuthLayer<R: UserRepository + 'static = PostgresUserRepository> {
+    auth: Arc<AuthService<R>>,
+}
+
+impl<R: UserRepository> GrpcAuthLayer<R> {
+    pub(crate) fn new(auth: Arc<AuthService<R>>) -> Self {
+        Self { auth }
+    }
+}
+
+impl<R: UserRepository> Clone for GrpcAuthLayer<R> {
+    fn clone(&self) -> Self {
+        Self {
+            auth: Arc::clone(&self.auth),
+        }
+    }
+}
+
+impl<S, R: UserRepository> Layer<S> for GrpcAuthLayer<R> {
+    type Service = GrpcAuth<S, R>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcAuth {
+            inner,
+            auth: Arc::clone(&self.auth),
+        }
+    }
+}
+
+pub(crate) struct GrpcAuth<S, R: UserRepository + 'static = PostgresUserRepository> {
+    inner: S,
+    auth: Arc<AuthService<R>>,
+}
+
+impl<S: Clone, R: UserRepository> Clone for GrpcAuth<S, R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            auth: Arc::clone(&self.auth),
+        }
+    }
+}
+
+fn bearer_token<B>(request: &http::Request<B>) -> Option<String> {
+    request
+        .headers()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_owned)
+}
+
+impl<S, R, ReqBody, ResBody> Service<http::Request<ReqBody>> for GrpcAuth<S, R>
+where
+    R: UserRepository,
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Default,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<ReqBody>) -> Self::Future {
+        let Some(permission) = required_permission(request.uri().path()) else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        // Готовность проверена у self.inner, см. GrpcRateLimit.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let auth = Arc::clone(&self.auth);
+        let token = bearer_token(&request);
+        Box::pin(async move {
+            let identity = async {
+                let token = token.ok_or(BlogError::MissingToken)?;
+                let (user, claims) = auth.authenticate(&token).await?;
+                Span::current().record("user_id", user.id);
+                if !claims.role.has(permission) {
+                    return Err(BlogError::Forbidden);
+                }
+                Ok(GrpcIdentity { user, claims })
+            };
+            match identity.await {
+                Ok(identity) => {
+                    request.extensions_mut().insert(identity);
+                    inner.call(request).await
+                }
+                Err(err) => Ok(Status::from(err).into_http()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tonic::Code;
+    use tower::service_fn;
+
+    use super::*;
+    use crate::application::auth_service::tests::{ctx, hasher, register_input, service_with};
+    use crate::data::audit_repository::InMemoryAuditRepository;
+    use crate::data::user_repository::InMemoryUserRepository;
+
+    type Auth = AuthService<InMemoryUserRepository>;
+
+    fn auth() -> Arc<Auth> {
+        let (auth, _) = service_with(
+            Arc::new(InMemoryUserRepository::new()),
+            hasher(1),
+            Arc::new(InMemoryAuditRepository::new()),
+        );
+        Arc::new(auth)
+    }
+
+    // Внутренний сервис отвечает 200 и сообщает, видел ли он GrpcIdentity.
+    async fn call(auth: &Arc<Auth>, path: &str, token: Option<&str>) -> http::Response<String> {
+        let inner = service_fn(|request: http::Request<()>| async move {
+            let identity = request.extensions().get::<GrpcIdentity>().map(|i| i.user.username.clone());
+            Ok::<_, Infallible>(http::Response::new(identity.unwrap_or_default()))
+        });
+        let mut request = http::Request::builder().uri(path);
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {token}"));
+        }
+        GrpcAuthLayer::new(Arc::clone(auth))
+            .layer(inner)
+            .call(request.body(()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    fn status_code(response: &http::Response<String>) -> Option<Code> {
+        Status::from_header_map(response.headers()).map(|s| s.code())
+    }
+
+    #[tokio::test]
+    async fn protected_method_requires_token() {
+        let auth = auth();
+        let response = call(&auth, "/blog.BlogService/CreatePost", None).await;
+        assert_eq!(status_code(&response), Some(Code::Unauthenticated));
+        let response = call(&auth, "/blog.BlogService/CreatePost", Some("garbage")).await;
+        assert_eq!(status_code(&response), Some(Code::Unauthenticated));
+    }
+
+    #[tokio::test]
+    async fn protected_method_passes_identity_through() {
+        let auth = auth();
+        let session = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let response = call(&auth, "/blog.BlogService/CreatePost", Some(&session.token)).await;
+        assert_eq!(status_code(&response), None);
+        assert_eq!(response.body(), "ivan");
+    }
+
+    #[tokio::test]
+    async fn admin_service_is_closed_to_non_admins() {
+        let auth = auth();
+        let session = auth.register(register_input("ivan"), &ctx()).await.unwrap();
+        let response = call(&auth, "/blog.AdminService/ListUsers", Some(&session.token)).await;
+        assert_eq!(status_code(&response), Some(Code::PermissionDenied));
+        let response = call(&auth, "/blog.AdminService/ListUsers", None).await;
+        assert_eq!(status_code(&response), Some(Code::Unauthenticated));
+    }
+
+    #[tokio::test]
+    async fn public_methods_pass_without_token() {
+        let auth = auth();
+        for path in ["/blog.BlogService/ListPosts", "/blog.BlogService/Login", "/grpc.health.v1.Health/Check"] {
+            let response = call(&auth, path, None).await;
+            assert_eq!(status_code(&response), None, "{path}");
+            assert_eq!(response.status(), http::StatusCode::OK, "{path}");
+        }
+    }
+}
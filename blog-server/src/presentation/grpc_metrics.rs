@@ -0,0 +1,77 @@
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use futures_util::future::BoxFuture;
+use tonic::codegen::http;
+use tonic::Code;
+use tower::{Layer, Service};
+
+use crate::infrastructure::metrics::metrics;
+
+// Tower-слой для tonic: счётчик и гистограмма задержки по методу и коду ответа,
+// аналог метрик TimingMiddleware для HTTP.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct GrpcMetricsLayer;
+
+impl<S> Layer<S> for GrpcMetricsLayer {
+    type Service = GrpcMetrics<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcMetrics { inner }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GrpcMetrics<S> {
+    inner: S,
+}
+
+// Ошибки обработчиков tonic отдаёт trailers-only ответом, поэтому `grpc-status`
+// ищется в заголовках; его отсутствие означает успешный ответ.
+fn status_code<B>(response: &http::Response<B>) -> Code {
+    response
+        .headers()
+        .get("grpc-status")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<i32>().ok())
+        .map(Code::from)
+        .unwrap_or(Code::Ok)
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for GrpcMetrics<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let start = Instant::now();
+        let method = request.uri().path().to_owned();
+        let fut = self.inner.call(request);
+
+        Box::pin(async move {
+            let res = fut.await;
+            let code = match &res {
+                Ok(response) => status_code(response),
+                Err(_) => Code::Internal,
+            };
+            // Несуществующие методы не должны раздувать число серий.
+            let method = if code == Code::Unimplemented { "unknown" } else { &method };
+            let code = format!("{code:?}");
+            let labels = [method, code.as_str()];
+            metrics().grpc_requests.with_label_values(&labels).inc();
+            metrics()
+                .grpc_duration
+                .with_label_values(&labels)
+                .observe(start.elapsed().as_secs_f64());
+            res
+        })
+    }
+}
@@ -0,0 +1,108 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::future::BoxFuture;
+use tonic::codegen::http;
+use tonic::transport::server::TcpConnectInfo;
+use tonic::Status;
+use tower::{Layer, Service};
+
+use crate::application::rate_limiter::RateLimiter;
+
+// Методы, к которым применяется лимит по IP, как RateLimitMiddleware для HTTP.
+const LIMITED_METHODS: [&str; 6] = [
+    "/blog.BlogService/Register",
+    "/blog.BlogService/Login",
+    "/blog.BlogService/ResendVerification",
+    "/blog.BlogService/ForgotPassword",
+    "/blog.BlogService/ResetPassword",
+    "/blog.BlogService/ChangePassword",
+];
+
+// Адрес клиента, определённый слоем; кладётся в extensions каждого запроса,
+// по нему BlogGrpcService заполняет журнал аудита.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ClientIp(pub(crate) IpAddr);
+
+#[derive(Clone)]
+pub(crate) struct GrpcRateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl GrpcRateLimitLayer {
+    pub(crate) fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S> Layer<S> for GrpcRateLimitLayer {
+    type Service = GrpcRateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcRateLimit {
+            inner,
+            limiter: Arc::clone(&self.limiter),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct GrpcRateLimit<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+fn client_ip<B>(request: &http::Request<B>, trust_proxy_headers: bool) -> Option<IpAddr> {
+    let forwarded = trust_proxy_headers
+        .then(|| request.headers().get("x-forwarded-for"))
+        .flatten()
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .and_then(|v| v.trim().parse().ok());
+    forwarded.or_else(|| {
+        request
+            .extensions()
+            .get::<TcpConnectInfo>()
+            .and_then(|info| info.remote_addr())
+            .map(|addr| addr.ip())
+    })
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for GrpcRateLimit<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Default,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<ReqBody>) -> Self::Future {
+        let ip = client_ip(&request, self.limiter.trust_proxy_headers());
+        if let Some(ip) = ip {
+            request.extensions_mut().insert(ClientIp(ip));
+        }
+        let Some(ip) = ip.filter(|_| LIMITED_METHODS.contains(&request.uri().path())) else {
+            return Box::pin(self.inner.call(request));
+        };
+
+        // Готовность проверена у self.inner, поэтому вызывается именно он,
+        // а в self остаётся свежий клон.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = Arc::clone(&self.limiter);
+        Box::pin(async move {
+            if let Err(err) = limiter.check_ip(ip).await {
+                return Ok(Status::from(err).into_http());
+            }
+            inner.call(request).await
+        })
+    }
+}
@@ -0,0 +1,667 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use prost::Message;
+use tonic::codegen::Bytes;
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::{Code, Request, Response, Status};
+use tracing::Span;
+
+use crate::application::auth_service::AuthService;
+use crate::application::blog_service::BlogService;
+use crate::application::policy::Actor;
+use crate::application::profile_service::ProfileService;
+use crate::domain::audit::AuditContext;
+use crate::domain::error::BlogError;
+use crate::domain::post::{CreatePost, Post, PostStatus, UpdatePost};
+use crate::domain::user::{
+    AuthResponse, AuthorSummary, ChangePasswordRequest, LoginUser, PublicProfile, RegisterUser,
+    ResetPasswordRequest, UpdateProfile, User,
+};
+use crate::infrastructure::jwt::Claims;
+use crate::infrastructure::metrics::metrics;
+use crate::presentation::grpc_auth::GrpcIdentity;
+use crate::presentation::grpc_rate_limit::ClientIp;
+use crate::presentation::middleware::RequestId;
+
+pub mod proto {
+    tonic::include_proto!("blog");
+}
+
+use proto::blog_service_server::{BlogService as BlogRpc, BlogServiceServer};
+use proto::list_posts_request::Pagination;
+
+// Тот же стабильный код, что и в HTTP problem+json, передаётся в metadata `x-error-code`,
+// ошибки по полям — в details как blog.BadRequest.
+impl From<BlogError> for Status {
+    fn from(err: BlogError) -> Self {
+        let code = match err {
+            BlogError::Validation(_)
+            | BlogError::InvalidFields(_)
+            | BlogError::InvalidCursor
+            | BlogError::InvalidVerificationToken
+            | BlogError::InvalidResetToken => Code::InvalidArgument,
+            BlogError::MissingToken
+            | BlogError::InvalidCredentials
+            | BlogError::InvalidToken
+            | BlogError::InvalidRefreshToken => Code::Unauthenticated,
+            BlogError::Forbidden | BlogError::AccountSuspended => Code::PermissionDenied,
+            BlogError::EmailNotVerified | BlogError::EmailAlreadyVerified => {
+                Code::FailedPrecondition
+            }
+            BlogError::RateLimited { .. } | BlogError::AccountLocked { .. } => {
+                Code::ResourceExhausted
+            }
+            BlogError::UserNotFound | BlogError::PostNotFound => Code::NotFound,
+            BlogError::UserAlreadyExists => Code::AlreadyExists,
+            BlogError::Database(sqlx::Error::PoolTimedOut) => Code::Unavailable,
+            BlogError::Database(_) | BlogError::Internal(_) => Code::Internal,
+        };
+        if err.is_server_error() {
+            tracing::error!(error = %err, code = err.code(), "rpc failed");
+        }
+        if matches!(err, BlogError::Database(sqlx::Error::PoolTimedOut)) {
+            metrics().db_pool_timeouts.inc();
+        }
+
+        let mut metadata = MetadataMap::new();
+        metadata.insert("x-error-code", MetadataValue::from_static(err.code()));
+        if let Some(secs) = err.retry_after() {
+            metadata.insert("retry-after", MetadataValue::from(secs));
+        }
+        let details = match err.field_errors() {
+            [] => Bytes::new(),
+            errors => proto::BadRequest {
+                field_violations: errors
+                    .iter()
+                    .map(|e| proto::bad_request::FieldViolation {
+                        field: e.field.to_string(),
+                        code: e.code.to_string(),
+                        description: e.message.clone(),
+                    })
+                    .collect(),
+            }
+            .encode_to_vec()
+            .into(),
+        };
+        Status::with_details_and_metadata(code, err.public_message(), details, metadata)
+    }
+}
+
+pub(crate) fn timestamp(value: DateTime<Utc>) -> prost_types::Timestamp {
+    prost_types::Timestamp {
+        seconds: value.timestamp(),
+        nanos: value.timestamp_subsec_nanos() as i32,
+    }
+}
+
+pub(crate) fn datetime(field: &str, ts: Option<prost_types::Timestamp>) -> Result<Option<DateTime<Utc>>, BlogError> {
+    ts.map(|ts| {
+        u32::try_from(ts.nanos)
+            .ok()
+            .and_then(|nanos| DateTime::from_timestamp(ts.seconds, nanos))
+            .ok_or_else(|| BlogError::Validation(format!("`{field}` is not a valid timestamp")))
+    })
+    .transpose()
+}
+
+// Пустая строка в proto3 означает, что статус не передан.
+fn post_status(value: String) -> Result<Option<PostStatus>, BlogError> {
+    Some(value)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse())
+        .transpose()
+        .map_err(BlogError::Validation)
+}
+
+impl From<User> for proto::User {
+    fn from(user: User) -> Self {
+        proto::User {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            created_at: Some(timestamp(user.created_at)),
+            email_verified_at: user.email_verified_at.map(timestamp),
+            role: user.role.to_string(),
+            suspended_at: user.suspended_at.map(timestamp),
+            display_name: user.display_name,
+            bio: user.bio,
+            avatar_url: user.avatar_url,
+        }
+    }
+}
+
+impl From<AuthorSummary> for proto::Author {
+    fn from(author: AuthorSummary) -> Self {
+        proto::Author {
+            id: author.id,
+            username: author.username,
+            display_name: author.display_name,
+            avatar_url: author.avatar_url,
+        }
+    }
+}
+
+impl From<PublicProfile> for proto::Profile {
+    fn from(profile: PublicProfile) -> Self {
+        proto::Profile {
+            id: profile.id,
+            username: profile.username,
+            display_name: profile.display_name,
+            bio: profile.bio,
+            avatar_url: profile.avatar_url,
+            joined_at: Some(timestamp(profile.joined_at)),
+            post_count: profile.post_count,
+        }
+    }
+}
+
+impl From<Post> for proto::Post {
+    fn from(post: Post) -> Self {
+        proto::Post {
+            id: post.id,
+            title: post.title,
+            content: post.content,
+            author_id: post.author_id,
+            created_at: Some(timestamp(post.created_at)),
+            updated_at: Some(timestamp(post.updated_at)),
+            hidden_at: post.hidden_at.map(timestamp),
+            author: post.author.map(Into::into),
+            status: post.status.to_string(),
+            published_at: post.published_at.map(timestamp),
+        }
+    }
+}
+
+impl From<AuthResponse> for proto::AuthResponse {
+    fn from(res: AuthResponse) -> Self {
+        proto::AuthResponse {
+            token: res.token,
+            user: Some(res.user.into()),
+            refresh_token: res.refresh_token,
+            expires_in: res.expires_in,
+        }
+    }
+}
+
+fn actor(user: &User, claims: &Claims) -> Actor {
+    Actor {
+        user_id: user.id,
+        role: claims.role,
+        email_verified: user.is_email_verified(),
+    }
+}
+
+// Аналог экстрактора AuditContext для HTTP: адрес и идентификатор запроса кладут
+// в extensions GrpcRateLimitLayer и GrpcTraceLayer.
+fn audit_context<T>(request: &Request<T>) -> AuditContext {
+    AuditContext {
+        ip: request.extensions().get::<ClientIp>().map(|ip| ip.0.to_string()),
+        user_agent: request
+            .metadata()
+            .get("user-agent")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned),
+        request_id: request.extensions().get::<RequestId>().map(|id| id.0.clone()),
+    }
+}
+
+fn post_response(post: Post) -> Response<proto::PostResponse> {
+    Response::new(proto::PostResponse {
+        post: Some(post.into()),
+    })
+}
+
+fn profile_response(profile: PublicProfile) -> Response<proto::ProfileResponse> {
+    Response::new(proto::ProfileResponse {
+        profile: Some(profile.into()),
+    })
+}
+
+pub struct BlogGrpcService {
+    auth: Arc<AuthService>,
+    blog: Arc<BlogService>,
+    profiles: Arc<ProfileService>,
+}
+
+impl BlogGrpcService {
+    pub fn new(auth: Arc<AuthService>, blog: Arc<BlogService>, profiles: Arc<ProfileService>) -> Self {
+        Self { auth, blog, profiles }
+    }
+
+    pub fn into_server(self) -> BlogServiceServer<Self> {
+        BlogServiceServer::new(self)
+    }
+
+    // Аналог JwtAuthMiddleware: токен из metadata `authorization: Bearer <token>`.
+    // Для методов из GrpcAuthLayer пользователь уже проверен слоем.
+    async fn authenticate<T>(&self, request: &Request<T>) -> Result<(User, Claims), Status> {
+        if let Some(identity) = request.extensions().get::<GrpcIdentity>() {
+            return Ok((identity.user.clone(), identity.claims.clone()));
+        }
+        let token = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .ok_or(BlogError::MissingToken)?;
+
+        let (user, claims) = self.auth.authenticate(token).await?;
+        Span::current().record("user_id", user.id);
+        Ok((user, claims))
+    }
+
+    // Для публичных методов: без токена читатель анонимный, но переданный
+    // недействительный токен — ошибка, как в OptionalJwtAuthMiddleware.
+    async fn viewer<T>(&self, request: &Request<T>) -> Result<Option<Actor>, Status> {
+        if request.extensions().get::<GrpcIdentity>().is_none()
+            && request.metadata().get("authorization").is_none()
+        {
+            return Ok(None);
+        }
+        let (user, claims) = self.authenticate(request).await?;
+        Ok(Some(actor(&user, &claims)))
+    }
+
+    async fn offset_page(
+        &self,
+        viewer: Option<&Actor>,
+        status: Option<PostStatus>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<proto::ListPostsResponse, BlogError> {
+        let page = self.blog.list_posts(viewer, status, limit, offset).await?;
+        Ok(proto::ListPostsResponse {
+            posts: page.posts.into_iter().map(Into::into).collect(),
+            limit: page.limit,
+            total: page.total,
+            offset: page.offset,
+            next_cursor: None,
+            prev_cursor: None,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl BlogRpc for BlogGrpcService {
+    async fn register(
+        &self,
+        request: Request<proto::RegisterRequest>,
+    ) -> Result<Response<proto::AuthResponse>, Status> {
+        let ctx = audit_context(&request);
+        let req = request.into_inner();
+        let res = self
+            .auth
+            .register(
+                RegisterUser {
+                    username: req.username,
+                    email: req.email,
+                    password: req.password,
+                },
+                &ctx,
+            )
+            .await?;
+        Ok(Response::new(res.into()))
+    }
+
+    async fn login(
+        &self,
+        request: Request<proto::LoginRequest>,
+    ) -> Result<Response<proto::AuthResponse>, Status> {
+        let ctx = audit_context(&request);
+        let req = request.into_inner();
+        let res = self
+            .auth
+            .login(
+                LoginUser {
+                    username: req.username,
+                    password: req.password,
+                },
+                &ctx,
+            )
+            .await?;
+        Ok(Response::new(res.into()))
+    }
+
+    async fn refresh(
+        &self,
+        request: Request<proto::RefreshRequest>,
+    ) -> Result<Response<proto::AuthResponse>, Status> {
+        let res = self.auth.refresh(&request.into_inner().refresh_token).await?;
+        Ok(Response::new(res.into()))
+    }
+
+    async fn logout(
+        &self,
+        request: Request<proto::LogoutRequest>,
+    ) -> Result<Response<proto::LogoutResponse>, Status> {
+        let (_, claims) = self.authenticate(&request).await?;
+        let refresh_token = request.into_inner().refresh_token;
+        self.auth
+            .logout(&claims, Some(refresh_token.as_str()).filter(|t| !t.is_empty()))
+            .await?;
+        Ok(Response::new(proto::LogoutResponse {}))
+    }
+
+    async fn logout_all(
+        &self,
+        request: Request<proto::LogoutAllRequest>,
+    ) -> Result<Response<proto::LogoutResponse>, Status> {
+        let (user, _) = self.authenticate(&request).await?;
+        self.auth.logout_all(user.id).await?;
+        Ok(Response::new(proto::LogoutResponse {}))
+    }
+
+    async fn verify_email(
+        &self,
+        request: Request<proto::VerifyEmailRequest>,
+    ) -> Result<Response<proto::VerifyEmailResponse>, Status> {
+        let user = self.auth.verify_email(&request.into_inner().token).await?;
+        Ok(Response::new(proto::VerifyEmailResponse {
+            user: Some(user.into()),
+        }))
+    }
+
+    async fn resend_verification(
+        &self,
+        request: Request<proto::ResendVerificationRequest>,
+    ) -> Result<Response<proto::ResendVerificationResponse>, Status> {
+        let (user, _) = self.authenticate(&request).await?;
+        self.auth.resend_verification(user.id).await?;
+        Ok(Response::new(proto::ResendVerificationResponse {}))
+    }
+
+    async fn forgot_password(
+        &self,
+        request: Request<proto::ForgotPasswordRequest>,
+    ) -> Result<Response<proto::ForgotPasswordResponse>, Status> {
+        self.auth.forgot_password(&request.into_inner().email).await?;
+        Ok(Response::new(proto::ForgotPasswordResponse {}))
+    }
+
+    async fn reset_password(
+        &self,
+        request: Request<proto::ResetPasswordRequest>,
+    ) -> Result<Response<proto::ResetPasswordResponse>, Status> {
+        let ctx = audit_context(&request);
+        let req = request.into_inner();
+        self.auth
+            .reset_password(
+                ResetPasswordRequest {
+                    token: req.token,
+                    new_password: req.new_password,
+                },
+                &ctx,
+            )
+            .await?;
+        Ok(Response::new(proto::ResetPasswordResponse {}))
+    }
+
+    async fn change_password(
+        &self,
+        request: Request<proto::ChangePasswordRequest>,
+    ) -> Result<Response<proto::AuthResponse>, Status> {
+        let (user, _) = self.authenticate(&request).await?;
+        let ctx = audit_context(&request);
+        let req = request.into_inner();
+        let res = self
+            .auth
+            .change_password(
+                user.id,
+                ChangePasswordRequest {
+                    current_password: req.current_password,
+                    new_password: req.new_password,
+                },
+                &ctx,
+            )
+            .await?;
+        Ok(Response::new(res.into()))
+    }
+
+    async fn create_post(
+        &self,
+        request: Request<proto::CreatePostRequest>,
+    ) -> Result<Response<proto::PostResponse>, Status> {
+        let (user, claims) = self.authenticate(&request).await?;
+        let ctx = audit_context(&request);
+        let req = request.into_inner();
+        let post = self
+            .blog
+            .create_post(
+                &actor(&user, &claims),
+                CreatePost {
+                    title: req.title,
+                    content: req.content,
+                    status: post_status(req.status)?,
+                    published_at: datetime("published_at", req.published_at)?,
+                },
+                &ctx,
+            )
+            .await?;
+        Ok(post_response(post))
+    }
+
+    async fn get_post(
+        &self,
+        request: Request<proto::GetPostRequest>,
+    ) -> Result<Response<proto::PostResponse>, Status> {
+        let viewer = self.viewer(&request).await?;
+        let post = self
+            .blog
+            .get_post(viewer.as_ref(), request.into_inner().id)
+            .await?;
+        Ok(post_response(post))
+    }
+
+    async fn update_post(
+        &self,
+        request: Request<proto::UpdatePostRequest>,
+    ) -> Result<Response<proto::PostResponse>, Status> {
+        let (user, claims) = self.authenticate(&request).await?;
+        let ctx = audit_context(&request);
+        let req = request.into_inner();
+        let post = self
+            .blog
+            .update_post(
+                &actor(&user, &claims),
+                req.id,
+                UpdatePost {
+                    title: req.title,
+                    content: req.content,
+                    status: post_status(req.status)?,
+                    published_at: datetime("published_at", req.published_at)?,
+                },
+                &ctx,
+            )
+            .await?;
+        Ok(post_response(post))
+    }
+
+    async fn delete_post(
+        &self,
+        request: Request<proto::DeletePostRequest>,
+    ) -> Result<Response<proto::DeletePostResponse>, Status> {
+        let (user, claims) = self.authenticate(&request).await?;
+        let ctx = audit_context(&request);
+        self.blog
+            .delete_post(&actor(&user, &claims), request.into_inner().id, &ctx)
+            .await?;
+        Ok(Response::new(proto::DeletePostResponse {}))
+    }
+
+    async fn hide_post(
+        &self,
+        request: Request<proto::HidePostRequest>,
+    ) -> Result<Response<proto::PostResponse>, Status> {
+        let (user, claims) = self.authenticate(&request).await?;
+        let ctx = audit_context(&request);
+        let post = self
+            .blog
+            .set_post_hidden(&actor(&user, &claims), request.into_inner().id, true, &ctx)
+            .await?;
+        Ok(post_response(post))
+    }
+
+    async fn unhide_post(
+        &self,
+        request: Request<proto::HidePostRequest>,
+    ) -> Result<Response<proto::PostResponse>, Status> {
+        let (user, claims) = self.authenticate(&request).await?;
+        let ctx = audit_context(&request);
+        let post = self
+            .blog
+            .set_post_hidden(&actor(&user, &claims), request.into_inner().id, false, &ctx)
+            .await?;
+        Ok(post_response(post))
+    }
+
+    async fn list_posts(
+        &self,
+        request: Request<proto::ListPostsRequest>,
+    ) -> Result<Response<proto::ListPostsResponse>, Status> {
+        let viewer = self.viewer(&request).await?;
+        let req = request.into_inner();
+        let limit = (req.limit > 0).then_some(req.limit);
+        let status = post_status(req.status)?;
+
+        let response = match req.pagination {
+            Some(Pagination::Cursor(cursor)) => {
+                let page = self
+                    .blog
+                    .list_posts_by_cursor(viewer.as_ref(), status, Some(&cursor), limit)
+                    .await?;
+                proto::ListPostsResponse {
+                    posts: page.posts.into_iter().map(Into::into).collect(),
+                    limit: page.limit,
+                    total: 0,
+                    offset: 0,
+                    next_cursor: page.next_cursor,
+                    prev_cursor: page.prev_cursor,
+                }
+            }
+            Some(Pagination::Offset(offset)) => {
+                self.offset_page(viewer.as_ref(), status, limit, Some(offset)).await?
+            }
+            None => self.offset_page(viewer.as_ref(), status, limit, None).await?,
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_profile(
+        &self,
+        request: Request<proto::GetProfileRequest>,
+    ) -> Result<Response<proto::ProfileResponse>, Status> {
+        let profile = self.profiles.get_profile(request.into_inner().id).await?;
+        Ok(profile_response(profile))
+    }
+
+    async fn update_profile(
+        &self,
+        request: Request<proto::UpdateProfileRequest>,
+    ) -> Result<Response<proto::ProfileResponse>, Status> {
+        let (user, claims) = self.authenticate(&request).await?;
+        let req = request.into_inner();
+        let input = UpdateProfile {
+            display_name: req.display_name,
+            bio: req.bio,
+            avatar_url: req.avatar_url,
+        };
+        let profile = self
+            .profiles
+            .update_profile(&actor(&user, &claims), req.id, input)
+            .await?;
+        Ok(profile_response(profile))
+    }
+
+    async fn list_user_posts(
+        &self,
+        request: Request<proto::ListUserPostsRequest>,
+    ) -> Result<Response<proto::ListPostsResponse>, Status> {
+        let viewer = self.viewer(&request).await?;
+        let req = request.into_inner();
+        let page = self
+            .profiles
+            .list_author_posts(
+                viewer.as_ref(),
+                req.id,
+                post_status(req.status)?,
+                (req.limit > 0).then_some(req.limit),
+                Some(req.offset),
+            )
+            .await?;
+        Ok(Response::new(proto::ListPostsResponse {
+            posts: page.posts.into_iter().map(Into::into).collect(),
+            limit: page.limit,
+            total: page.total,
+            offset: page.offset,
+            next_cursor: None,
+            prev_cursor: None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::error::FieldError;
+
+    fn metadata(status: &Status, key: &str) -> Option<String> {
+        status.metadata().get(key).map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn errors_map_to_stable_codes_and_metadata() {
+        let cases = [
+            (BlogError::Validation("title".into()), Code::InvalidArgument, "validation_failed", None),
+            (BlogError::InvalidFields(vec![]), Code::InvalidArgument, "validation_failed", None),
+            (BlogError::InvalidCursor, Code::InvalidArgument, "invalid_cursor", None),
+            (BlogError::InvalidVerificationToken, Code::InvalidArgument, "invalid_verification_token", None),
+            (BlogError::InvalidResetToken, Code::InvalidArgument, "invalid_reset_token", None),
+            (BlogError::MissingToken, Code::Unauthenticated, "missing_token", None),
+            (BlogError::InvalidCredentials, Code::Unauthenticated, "invalid_credentials", None),
+            (BlogError::InvalidToken, Code::Unauthenticated, "invalid_token", None),
+            (BlogError::InvalidRefreshToken, Code::Unauthenticated, "invalid_refresh_token", None),
+            (BlogError::Forbidden, Code::PermissionDenied, "forbidden", None),
+            (BlogError::AccountSuspended, Code::PermissionDenied, "account_suspended", None),
+            (BlogError::EmailNotVerified, Code::FailedPrecondition, "email_not_verified", None),
+            (BlogError::EmailAlreadyVerified, Code::FailedPrecondition, "email_already_verified", None),
+            (BlogError::RateLimited { retry_after: 5 }, Code::ResourceExhausted, "rate_limited", Some("5")),
+            (BlogError::AccountLocked { retry_after: 30 }, Code::ResourceExhausted, "account_locked", Some("30")),
+            (BlogError::UserNotFound, Code::NotFound, "user_not_found", None),
+            (BlogError::PostNotFound, Code::NotFound, "post_not_found", None),
+            (BlogError::UserAlreadyExists, Code::AlreadyExists, "user_already_exists", None),
+            (BlogError::Database(sqlx::Error::PoolTimedOut), Code::Unavailable, "service_unavailable", None),
+            (BlogError::Database(sqlx::Error::RowNotFound), Code::Internal, "database_error", None),
+            (BlogError::Internal("boom".into()), Code::Internal, "internal_error", None),
+        ];
+        for (err, code, error_code, retry_after) in cases {
+            let name = format!("{err:?}");
+            let status = Status::from(err);
+            assert_eq!(status.code(), code, "{name}");
+            assert_eq!(metadata(&status, "x-error-code").as_deref(), Some(error_code), "{name}");
+            assert_eq!(metadata(&status, "retry-after").as_deref(), retry_after, "{name}");
+        }
+    }
+
+    #[test]
+    fn server_errors_hide_details() {
+        let status = Status::from(BlogError::Internal("secret stack".into()));
+        assert!(!status.message().contains("secret"), "{}", status.message());
+
+        let status = Status::from(BlogError::Database(sqlx::Error::Protocol("secret query".into())));
+        assert!(!status.message().contains("secret"), "{}", status.message());
+        assert!(status.details().is_empty());
+    }
+
+    #[test]
+    fn field_errors_are_sent_as_bad_request_details() {
+        let err = BlogError::InvalidFields(vec![FieldError::new("email", "invalid_format", "is not a valid address")]);
+        let status = Status::from(err);
+        let details = proto::BadRequest::decode(status.details()).unwrap();
+        assert_eq!(details.field_violations.len(), 1);
+        assert_eq!(details.field_violations[0].field, "email");
+        assert_eq!(details.field_violations[0].code, "invalid_format");
+
+        assert!(Status::from(BlogError::PostNotFound).details().is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+use std::task::{Context, Poll};
+
+use futures_util::future::BoxFuture;
+use tonic::codegen::http::{self, HeaderName, HeaderValue};
+use tower::{Layer, Service};
+use tracing::{field, info_span, Instrument, Span};
+
+use crate::infrastructure::telemetry;
+use crate::presentation::middleware::RequestId;
+
+// Tower-слой для tonic, симметричный RequestIdMiddleware: span на вызов с request_id
+// и маршрутом, продолжение трассы из metadata `traceparent` и её возврат в ответе.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct GrpcTraceLayer;
+
+impl<S> Layer<S> for GrpcTraceLayer {
+    type Service = GrpcTrace<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GrpcTrace { inner }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GrpcTrace<S> {
+    inner: S,
+}
+
+// Идентификатор берётся из metadata `x-request-id` по тем же правилам, что в HTTP.
+fn request_id<B>(request: &http::Request<B>) -> String {
+    RequestId::accept(request.headers().get("x-request-id").and_then(|v| v.to_str().ok()))
+}
+
+// user_id дописывает BlogGrpcService::authenticate.
+fn request_span<B>(request: &http::Request<B>, request_id: &str) -> Span {
+    info_span!(
+        "grpc_request",
+        request_id = %request_id,
+        route = %request.uri().path(),
+        user_id = field::Empty,
+        trace_id = field::Empty,
+    )
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for GrpcTrace<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<ReqBody>) -> Self::Future {
+        let request_id = request_id(&request);
+        let span = request_span(&request, &request_id);
+        request.extensions_mut().insert(RequestId(request_id));
+        telemetry::continue_trace(&span, |name| {
+            request.headers().get(name).and_then(|v| v.to_str().ok())
+        });
+        let trace_headers = telemetry::trace_headers(&span);
+        let fut = span.in_scope(|| self.inner.call(request));
+
+        Box::pin(
+            async move {
+                let mut response = fut.await?;
+                for (name, value) in trace_headers {
+                    if let (Ok(name), Ok(value)) =
+                        (HeaderName::try_from(name), HeaderValue::try_from(value))
+                    {
+                        response.headers_mut().insert(name, value);
+                    }
+                }
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
@@ -0,0 +1,150 @@
+use actix_web::http::{header, StatusCode};
+use actix_web::{web, HttpResponse, ResponseError};
+use serde::Serialize;
+
+use crate::domain::error::{BlogError, FieldError};
+use crate::infrastructure::metrics::metrics;
+
+const PROBLEM_JSON: &str = "application/problem+json";
+
+// Тело ответа об ошибке по RFC 7807. `code` и `request_id` — расширения:
+// стабильный код для клиентов и идентификатор запроса из RequestIdMiddleware.
+#[derive(Debug, Serialize)]
+struct Problem<'a> {
+    #[serde(rename = "type")]
+    kind: String,
+    title: &'a str,
+    status: u16,
+    detail: String,
+    code: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<&'a str>,
+    // Ошибки по отдельным полям запроса, если они известны.
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    errors: &'a [FieldError],
+}
+
+fn title(status: StatusCode) -> &'static str {
+    status.canonical_reason().unwrap_or("Error")
+}
+
+pub(crate) fn problem_response(err: &BlogError, request_id: Option<&str>) -> HttpResponse {
+    let status = err.status_code();
+    let problem = Problem {
+        kind: format!("/problems/{}", err.code().replace('_', "-")),
+        title: title(status),
+        status: status.as_u16(),
+        detail: err.public_message(),
+        code: err.code(),
+        request_id,
+        errors: err.field_errors(),
+    };
+    let mut res = HttpResponse::build(status);
+    res.insert_header((header::CONTENT_TYPE, PROBLEM_JSON));
+    if let Some(secs) = err.retry_after() {
+        res.insert_header((header::RETRY_AFTER, secs));
+    }
+    res.body(serde_json::to_string(&problem).unwrap_or_default())
+}
+
+impl ResponseError for BlogError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            BlogError::Validation(_)
+            | BlogError::InvalidFields(_)
+            | BlogError::InvalidCursor
+            | BlogError::InvalidVerificationToken
+            | BlogError::InvalidResetToken => StatusCode::BAD_REQUEST,
+            BlogError::MissingToken
+            | BlogError::InvalidCredentials
+            | BlogError::InvalidToken
+            | BlogError::InvalidRefreshToken => StatusCode::UNAUTHORIZED,
+            BlogError::Forbidden | BlogError::EmailNotVerified | BlogError::AccountSuspended => {
+                StatusCode::FORBIDDEN
+            }
+            BlogError::RateLimited { .. } | BlogError::AccountLocked { .. } => {
+                StatusCode::TOO_MANY_REQUESTS
+            }
+            BlogError::UserNotFound | BlogError::PostNotFound => StatusCode::NOT_FOUND,
+            BlogError::UserAlreadyExists | BlogError::EmailAlreadyVerified => StatusCode::CONFLICT,
+            BlogError::Database(sqlx::Error::PoolTimedOut) => StatusCode::SERVICE_UNAVAILABLE,
+            BlogError::Database(_) | BlogError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if self.is_server_error() {
+            tracing::error!(error = %self, code = self.code(), "request failed");
+        }
+        if matches!(self, BlogError::Database(sqlx::Error::PoolTimedOut)) {
+            metrics().db_pool_timeouts.inc();
+        }
+        problem_response(self, None)
+    }
+}
+
+// Ошибки разбора тела, query и path тоже отдаются как problem+json.
+pub(crate) fn configure_extractors(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(web::JsonConfig::default().error_handler(|err, _| {
+        BlogError::Validation(err.to_string()).into()
+    }))
+    .app_data(web::QueryConfig::default().error_handler(|err, _| {
+        BlogError::Validation(err.to_string()).into()
+    }))
+    .app_data(web::PathConfig::default().error_handler(|err, _| {
+        BlogError::Validation(err.to_string()).into()
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+
+    async fn body(err: BlogError) -> (StatusCode, serde_json::Value) {
+        let res = problem_response(&err, Some("req-1"));
+        let status = res.status();
+        let bytes = to_bytes(res.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[actix_web::test]
+    async fn problem_has_stable_code_and_request_id() {
+        let (status, json) = body(BlogError::PostNotFound).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(json["type"], "/problems/post-not-found");
+        assert_eq!(json["code"], "post_not_found");
+        assert_eq!(json["request_id"], "req-1");
+    }
+
+    #[actix_web::test]
+    async fn server_errors_hide_details() {
+        let (status, json) = body(BlogError::Internal("secret stack".into())).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(json["detail"], "Internal server error");
+
+        let (status, json) = body(BlogError::Database(sqlx::Error::PoolTimedOut)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(json["code"], "service_unavailable");
+    }
+
+    #[actix_web::test]
+    async fn field_errors_are_listed() {
+        let err = BlogError::InvalidFields(vec![FieldError::new("email", "invalid_format", "is not a valid address")]);
+        let (status, json) = body(err).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["code"], "validation_failed");
+        assert_eq!(json["errors"][0]["field"], "email");
+        assert_eq!(json["errors"][0]["code"], "invalid_format");
+
+        let (_, json) = body(BlogError::PostNotFound).await;
+        assert!(json.get("errors").is_none());
+    }
+
+    #[actix_web::test]
+    async fn rate_limit_sets_retry_after() {
+        let res = problem_response(&BlogError::AccountLocked { retry_after: 30 }, None);
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers().get(header::RETRY_AFTER).unwrap(), "30");
+    }
+}
@@ -1,74 +1,494 @@
-use actix_web::{get, post, web, HttpResponse, Responder};
+use actix_web::{delete, get, http::header, post, put, routes, web, HttpResponse, Responder};
+use serde::Deserialize;
 use sqlx::PgPool;
-use argon2::{
-    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
+
+use crate::application::admin_service::AdminService;
+use crate::application::auth_service::AuthService;
+use crate::application::blog_service::BlogService;
+use crate::application::profile_service::ProfileService;
+use crate::domain::audit::{AuditContext, AuditQuery};
+use crate::domain::error::BlogError;
+use crate::domain::post::{CreatePost, PostStatus, UpdatePost};
+use crate::domain::role::Permission;
+use crate::domain::user::{
+    ChangePasswordRequest, ForgotPasswordRequest, LoginUser, LogoutRequest, RefreshRequest,
+    RegisterUser, ResetPasswordRequest, SetRoleRequest, UpdateProfile, UserQuery,
+    VerifyEmailRequest,
+};
+use crate::infrastructure::health::HealthCheck;
+use crate::infrastructure::metrics::metrics;
+use crate::presentation::http_error::configure_extractors;
+use crate::presentation::middleware::{
+    AuthenticatedUser, JwtAuthMiddleware, OptionalJwtAuthMiddleware, RateLimitMiddleware,
+    RequirePermission,
 };
-use crate::infrastructure::{jwt, config::Config};
-use crate::domain::user::{RegisterUser, LoginUser};
-use crate::data::user_repository;
 
+// Liveness: процесс жив и обрабатывает запросы, зависимости не проверяются.
+// `/health` оставлен для старых проверок.
+#[routes]
 #[get("/health")]
-async fn health() -> impl Responder {
+#[get("/health/live")]
+async fn live() -> impl Responder {
     HttpResponse::Ok().json(serde_json::json!({"status":"ok"}))
 }
 
-#[post("/register")]
-async fn register(
-    pool: web::Data<PgPool>,
-    body: web::Json<RegisterUser>,
-) -> actix_web::Result<impl Responder> {
-    let email = body.email.trim().to_lowercase();
-    if email.is_empty() || body.password.len() < 6 {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "invalid input"
-        })));
+// Readiness: 503, пока недоступна БД, не применены миграции или gRPC не слушает.
+#[get("/health/ready")]
+async fn ready(health: web::Data<HealthCheck>) -> impl Responder {
+    let readiness = health.readiness().await;
+    if readiness.ready {
+        HttpResponse::Ok().json(readiness)
+    } else {
+        HttpResponse::ServiceUnavailable().json(readiness)
     }
+}
 
-    let pw_hash = security::hash_password(&body.password)
-        .map_err(|_| actix_web::error::ErrorInternalServerError("hash error"))?;
+// Формат Prometheus text exposition; эндпоинт вне /api и без аутентификации,
+// доступ к нему ограничивается на уровне сети.
+#[get("/metrics")]
+async fn metrics_endpoint(pool: web::Data<PgPool>) -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(metrics().render(&pool))
+}
 
-    let user_id = uuid::Uuid::new_v4();
+// Публичные ключи проверки подписи для других сервисов; при HS256 список пуст.
+#[get("/.well-known/jwks.json")]
+async fn jwks(auth: web::Data<AuthService>) -> impl Responder {
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "public, max-age=300"))
+        .json(auth.jwks())
+}
 
-    let res = user_repository::create_user(&pool, user_id, &email, &pw_hash).await;
-    match res {
-        Ok(_) => Ok(HttpResponse::Created().finish()),
-        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
-            Ok(HttpResponse::Conflict().json(serde_json::json!({"error":"email taken"})))
-        }
-        Err(_) => Err(actix_web::error::ErrorInternalServerError("db error")),
-    }
+#[post("/register", wrap = "RateLimitMiddleware")]
+async fn register(
+    auth: web::Data<AuthService>,
+    body: web::Json<RegisterUser>,
+    ctx: AuditContext,
+) -> Result<HttpResponse, BlogError> {
+    let res = auth.register(body.into_inner(), &ctx).await?;
+    Ok(HttpResponse::Created().json(res))
+}
+
+#[post("/refresh")]
+async fn refresh(
+    auth: web::Data<AuthService>,
+    body: web::Json<RefreshRequest>,
+) -> Result<HttpResponse, BlogError> {
+    let res = auth.refresh(&body.refresh_token).await?;
+    Ok(HttpResponse::Ok().json(res))
 }
 
-#[post("/login")]
+#[post("/login", wrap = "RateLimitMiddleware")]
 async fn login(
-    pool: web::Data<PgPool>,
-    cfg: web::Data<Config>,
+    auth: web::Data<AuthService>,
     body: web::Json<LoginUser>,
-) -> actix_web::Result<impl Responder> {
-    let username = body.username.trim().to_lowercase();
+    ctx: AuditContext,
+) -> Result<HttpResponse, BlogError> {
+    let res = auth.login(body.into_inner(), &ctx).await?;
+    Ok(HttpResponse::Ok().json(res))
+}
+
+// POST для фронтенда, GET — для перехода по ссылке из письма напрямую.
+#[post("/verify-email")]
+async fn verify_email(
+    auth: web::Data<AuthService>,
+    body: web::Json<VerifyEmailRequest>,
+) -> Result<HttpResponse, BlogError> {
+    let user = auth.verify_email(&body.token).await?;
+    Ok(HttpResponse::Ok().json(user))
+}
+
+#[get("/verify-email")]
+async fn verify_email_link(
+    auth: web::Data<AuthService>,
+    query: web::Query<VerifyEmailRequest>,
+) -> Result<HttpResponse, BlogError> {
+    let user = auth.verify_email(&query.token).await?;
+    Ok(HttpResponse::Ok().json(user))
+}
+
+#[post("/verify-email/resend", wrap = "RateLimitMiddleware", wrap = "JwtAuthMiddleware")]
+async fn resend_verification(
+    auth: web::Data<AuthService>,
+    user: web::ReqData<AuthenticatedUser>,
+) -> Result<HttpResponse, BlogError> {
+    auth.resend_verification(user.user_id).await?;
+    Ok(HttpResponse::Accepted().finish())
+}
+
+// Всегда 202: по ответу нельзя узнать, зарегистрирован ли адрес.
+#[post("/forgot-password", wrap = "RateLimitMiddleware")]
+async fn forgot_password(
+    auth: web::Data<AuthService>,
+    body: web::Json<ForgotPasswordRequest>,
+) -> Result<HttpResponse, BlogError> {
+    auth.forgot_password(&body.email).await?;
+    Ok(HttpResponse::Accepted().finish())
+}
+
+#[post("/reset-password", wrap = "RateLimitMiddleware")]
+async fn reset_password(
+    auth: web::Data<AuthService>,
+    body: web::Json<ResetPasswordRequest>,
+    ctx: AuditContext,
+) -> Result<HttpResponse, BlogError> {
+    auth.reset_password(body.into_inner(), &ctx).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
 
-    let user = match user_repository::find_user(&pool, &username).await {
-        Ok(Some(u)) => u,
-        Ok(None) => return Ok(HttpResponse::Unauthorized().finish()),
-        Err(_) => return Err(actix_web::error::ErrorInternalServerError("db error")),
-    };
+// Прежние токены отзываются, в ответе новая пара для текущего клиента.
+#[post("/change-password", wrap = "RateLimitMiddleware", wrap = "JwtAuthMiddleware")]
+async fn change_password(
+    auth: web::Data<AuthService>,
+    user: web::ReqData<AuthenticatedUser>,
+    body: web::Json<ChangePasswordRequest>,
+    ctx: AuditContext,
+) -> Result<HttpResponse, BlogError> {
+    let res = auth.change_password(user.user_id, body.into_inner(), &ctx).await?;
+    Ok(HttpResponse::Ok().json(res))
+}
 
-    let ok = security::verify_password(&body.password, &user.password_hash)
-        .map_err(|_| actix_web::error::ErrorInternalServerError("verify error"))?;
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Pagination {
+    Offset,
+    Cursor,
+}
 
-    if !ok {
-        return Ok(HttpResponse::Unauthorized().finish());
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    limit: Option<i64>,
+    offset: Option<i64>,
+    cursor: Option<String>,
+    pagination: Option<Pagination>,
+    // Без статуса — опубликованные посты; остальные статусы требуют токена.
+    status: Option<PostStatus>,
+}
+
+#[get("", wrap = "OptionalJwtAuthMiddleware")]
+async fn list_posts(
+    blog: web::Data<BlogService>,
+    user: Option<web::ReqData<AuthenticatedUser>>,
+    query: web::Query<ListQuery>,
+) -> Result<HttpResponse, BlogError> {
+    let query = query.into_inner();
+    let viewer = user.map(|user| user.actor());
+    if query.cursor.is_some() || query.pagination == Some(Pagination::Cursor) {
+        let page = blog
+            .list_posts_by_cursor(viewer.as_ref(), query.status, query.cursor.as_deref(), query.limit)
+            .await?;
+        return Ok(HttpResponse::Ok().json(page));
     }
 
-    let token = security::generate_jwt(&cfg.jwt_secret, user.id)
-        .map_err(|_| actix_web::error::ErrorInternalServerError("jwt error"))?;
+    let page = blog
+        .list_posts(viewer.as_ref(), query.status, query.limit, query.offset)
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[get("/{id}", wrap = "OptionalJwtAuthMiddleware")]
+async fn get_post(
+    blog: web::Data<BlogService>,
+    user: Option<web::ReqData<AuthenticatedUser>>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    let viewer = user.map(|user| user.actor());
+    let post = blog.get_post(viewer.as_ref(), path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+#[post("", wrap = "RequirePermission(Permission::CreatePost)", wrap = "JwtAuthMiddleware")]
+async fn create_post(
+    blog: web::Data<BlogService>,
+    user: web::ReqData<AuthenticatedUser>,
+    body: web::Json<CreatePost>,
+    ctx: AuditContext,
+) -> Result<HttpResponse, BlogError> {
+    let post = blog.create_post(&user.actor(), body.into_inner(), &ctx).await?;
+    tracing::info!(post_id = post.id, author = %user.username, "post created");
+    Ok(HttpResponse::Created().json(post))
+}
+
+#[put("/{id}", wrap = "JwtAuthMiddleware")]
+async fn update_post(
+    blog: web::Data<BlogService>,
+    user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<i64>,
+    body: web::Json<UpdatePost>,
+    ctx: AuditContext,
+) -> Result<HttpResponse, BlogError> {
+    let post = blog
+        .update_post(&user.actor(), path.into_inner(), body.into_inner(), &ctx)
+        .await?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+#[delete("/{id}", wrap = "JwtAuthMiddleware")]
+async fn delete_post(
+    blog: web::Data<BlogService>,
+    user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<i64>,
+    ctx: AuditContext,
+) -> Result<HttpResponse, BlogError> {
+    blog.delete_post(&user.actor(), path.into_inner(), &ctx).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/{id}/hide", wrap = "RequirePermission(Permission::HidePost)", wrap = "JwtAuthMiddleware")]
+async fn hide_post(
+    blog: web::Data<BlogService>,
+    user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<i64>,
+    ctx: AuditContext,
+) -> Result<HttpResponse, BlogError> {
+    let post = blog.set_post_hidden(&user.actor(), path.into_inner(), true, &ctx).await?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+#[post("/{id}/unhide", wrap = "RequirePermission(Permission::HidePost)", wrap = "JwtAuthMiddleware")]
+async fn unhide_post(
+    blog: web::Data<BlogService>,
+    user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<i64>,
+    ctx: AuditContext,
+) -> Result<HttpResponse, BlogError> {
+    let post = blog.set_post_hidden(&user.actor(), path.into_inner(), false, &ctx).await?;
+    Ok(HttpResponse::Ok().json(post))
+}
+
+// Публичные профили: читать можно без токена, менять — только свой профиль
+// (администратор — любой).
+#[get("/{id}")]
+async fn get_profile(
+    profiles: web::Data<ProfileService>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    let profile = profiles.get_profile(path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(profile))
+}
+
+#[get("/{id}/posts", wrap = "OptionalJwtAuthMiddleware")]
+async fn list_user_posts(
+    profiles: web::Data<ProfileService>,
+    user: Option<web::ReqData<AuthenticatedUser>>,
+    path: web::Path<i64>,
+    query: web::Query<ListQuery>,
+) -> Result<HttpResponse, BlogError> {
+    let viewer = user.map(|user| user.actor());
+    let page = profiles
+        .list_author_posts(viewer.as_ref(), path.into_inner(), query.status, query.limit, query.offset)
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[put("/{id}/profile", wrap = "JwtAuthMiddleware")]
+async fn update_profile(
+    profiles: web::Data<ProfileService>,
+    user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<i64>,
+    body: web::Json<UpdateProfile>,
+) -> Result<HttpResponse, BlogError> {
+    let profile = profiles
+        .update_profile(&user.actor(), path.into_inner(), body.into_inner())
+        .await?;
+    Ok(HttpResponse::Ok().json(profile))
+}
+
+// Тело необязательно: без refresh-токена отзывается только access-токен.
+#[post("/logout", wrap = "JwtAuthMiddleware")]
+async fn logout(
+    auth: web::Data<AuthService>,
+    user: web::ReqData<AuthenticatedUser>,
+    body: Option<web::Json<LogoutRequest>>,
+) -> Result<HttpResponse, BlogError> {
+    let refresh_token = body.as_ref().and_then(|b| b.refresh_token.as_deref());
+    auth.logout(&user.claims, refresh_token).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/logout-all", wrap = "JwtAuthMiddleware")]
+async fn logout_all(
+    auth: web::Data<AuthService>,
+    user: web::ReqData<AuthenticatedUser>,
+) -> Result<HttpResponse, BlogError> {
+    auth.logout_all(user.user_id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// Администрирование: вся область /api/admin требует Permission::ManageUsers.
+#[get("/users")]
+async fn admin_list_users(
+    admin: web::Data<AdminService>,
+    user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<UserQuery>,
+) -> Result<HttpResponse, BlogError> {
+    let page = admin.list_users(&user.actor(), query.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[get("/audit")]
+async fn admin_list_audit_events(
+    admin: web::Data<AdminService>,
+    user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<AuditQuery>,
+) -> Result<HttpResponse, BlogError> {
+    let page = admin.list_audit_events(&user.actor(), query.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[post("/users/{id}/suspend")]
+async fn admin_suspend_user(
+    admin: web::Data<AdminService>,
+    user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    let target = admin.set_suspended(&user.actor(), path.into_inner(), true).await?;
+    Ok(HttpResponse::Ok().json(target))
+}
 
-    Ok(HttpResponse::Ok().json(TokenResponse { access_token: token }))
+#[post("/users/{id}/unsuspend")]
+async fn admin_unsuspend_user(
+    admin: web::Data<AdminService>,
+    user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    let target = admin.set_suspended(&user.actor(), path.into_inner(), false).await?;
+    Ok(HttpResponse::Ok().json(target))
+}
+
+#[put("/users/{id}/role")]
+async fn admin_set_role(
+    admin: web::Data<AdminService>,
+    user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<i64>,
+    body: web::Json<SetRoleRequest>,
+) -> Result<HttpResponse, BlogError> {
+    let target = admin.set_role(&user.actor(), path.into_inner(), body.role).await?;
+    Ok(HttpResponse::Ok().json(target))
+}
+
+#[post("/users/{id}/force-password-reset")]
+async fn admin_force_password_reset(
+    admin: web::Data<AdminService>,
+    user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    admin.force_password_reset(&user.actor(), path.into_inner()).await?;
+    Ok(HttpResponse::Accepted().finish())
+}
+
+#[delete("/users/{id}")]
+async fn admin_delete_user(
+    admin: web::Data<AdminService>,
+    user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, BlogError> {
+    admin.delete_user(&user.actor(), path.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.service(health)
-        .service(register)
-        .service(login);
-}
\ No newline at end of file
+    configure_extractors(cfg);
+    cfg.service(live)
+        .service(ready)
+        .service(metrics_endpoint)
+        .service(jwks)
+        .service(
+            web::scope("/api/auth")
+                .service(register)
+                .service(login)
+                .service(refresh)
+                .service(verify_email)
+                .service(verify_email_link)
+                .service(forgot_password)
+                .service(reset_password)
+                .service(logout)
+                .service(logout_all)
+                .service(resend_verification)
+                .service(change_password),
+        )
+        .service(
+            web::scope("/api/posts")
+                .service(list_posts)
+                .service(get_post)
+                .service(create_post)
+                .service(update_post)
+                .service(delete_post)
+                .service(hide_post)
+                .service(unhide_post),
+        )
+        .service(
+            web::scope("/api/users")
+                .service(get_profile)
+                .service(list_user_posts)
+                .service(update_profile),
+        )
+        .service(
+            web::scope("/api/admin")
+                .wrap(RequirePermission(Permission::ManageUsers))
+                .wrap(JwtAuthMiddleware)
+                .service(admin_list_users)
+                .service(admin_list_audit_events)
+                .service(admin_suspend_user)
+                .service(admin_unsuspend_user)
+                .service(admin_set_role)
+                .service(admin_force_password_reset)
+                .service(admin_delete_user),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+    use sqlx::postgres::PgPoolOptions;
+    use std::sync::Arc;
+
+    use crate::application::auth_service::tests::{hasher, service_with};
+    use crate::data::user_repository::PostgresUserRepository;
+    use crate::data::audit_repository::InMemoryAuditRepository;
+
+    // Пул ленивый: без токена запрос до базы не доходит.
+    async fn status(req: test::TestRequest) -> StatusCode {
+        let pool = PgPoolOptions::new().connect_lazy("postgres://localhost/unused").unwrap();
+        let (auth, _) = service_with(
+            Arc::new(PostgresUserRepository::new(pool)),
+            hasher(1),
+            Arc::new(InMemoryAuditRepository::new()),
+        );
+        let app = test::init_service(App::new().app_data(web::Data::new(auth)).configure(configure)).await;
+        match test::try_call_service(&app, req.to_request()).await {
+            Ok(res) => res.status(),
+            Err(err) => err.as_response_error().status_code(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn unknown_paths_are_not_found_without_token() {
+        for uri in ["/api/auth/nope", "/api/posts/1/nope", "/api/users/1/nope"] {
+            assert_eq!(status(test::TestRequest::get().uri(uri)).await, StatusCode::NOT_FOUND, "{uri}");
+        }
+        assert_eq!(
+            status(test::TestRequest::post().uri("/api/users/1/profile")).await,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[actix_web::test]
+    async fn protected_resources_require_token() {
+        let requests = [
+            test::TestRequest::post().uri("/api/auth/logout"),
+            test::TestRequest::post().uri("/api/auth/change-password"),
+            test::TestRequest::post().uri("/api/posts"),
+            test::TestRequest::put().uri("/api/posts/1"),
+            test::TestRequest::delete().uri("/api/posts/1"),
+            test::TestRequest::post().uri("/api/posts/1/hide"),
+            test::TestRequest::put().uri("/api/users/1/profile"),
+        ];
+        for req in requests {
+            assert_eq!(status(req).await, StatusCode::UNAUTHORIZED);
+        }
+    }
+}
@@ -1,27 +1,109 @@
 use std::cell::RefCell;
 use std::future::{ready, Ready};
+use std::net::{IpAddr, SocketAddr};
 use std::rc::Rc;
 use std::task::{Context, Poll};
 use std::time::Instant;
 
 use actix_service::{Service, Transform};
-use actix_web::dev::{ServiceRequest, ServiceResponse};
-use actix_web::http::header::{HeaderName, HeaderValue};
-use actix_web::{web, Error, HttpMessage};
+use actix_web::body::EitherBody;
+use actix_web::error::InternalError;
+use actix_web::dev::{ConnectionInfo, Payload, ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+use actix_web::{web, Error, FromRequest, HttpMessage, HttpRequest};
 use futures_util::future::LocalBoxFuture;
-use tracing::info;
+use tracing::{field, info, info_span, Instrument, Span};
 use uuid::Uuid;
 
 use crate::application::auth_service::AuthService;
-use crate::data::user_repository::InMemoryUserRepository;
-use crate::infrastructure::security::JwtKeys;
-use crate::presentation::auth::extract_user_from_token;
+use crate::application::policy::Actor;
+use crate::application::rate_limiter::RateLimiter;
+use crate::domain::audit::AuditContext;
+use crate::domain::error::BlogError;
+use crate::domain::role::Permission;
+use crate::presentation::http_error::problem_response;
+use crate::infrastructure::jwt::Claims;
+use crate::infrastructure::metrics::metrics;
+use crate::infrastructure::telemetry;
 
 static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
 static TIMING_HEADER: HeaderName = HeaderName::from_static("server-timing");
 
-#[derive(Clone)]
-pub struct RequestId(pub String);
+#[derive(Clone, Debug)]
+pub struct AuthenticatedUser {
+    pub user_id: i64,
+    pub username: String,
+    pub email_verified: bool,
+    pub claims: Claims,
+}
+
+impl AuthenticatedUser {
+    pub(crate) fn actor(&self) -> Actor {
+        Actor {
+            user_id: self.user_id,
+            role: self.claims.role,
+            email_verified: self.email_verified,
+        }
+    }
+}
+
+// Идентификатор запроса из RequestIdMiddleware (HTTP) или GrpcTraceLayer (gRPC),
+// лежит в extensions запроса.
+#[derive(Clone, Debug)]
+pub(crate) struct RequestId(pub(crate) String);
+
+// Размер колонки audit_events.request_id.
+const REQUEST_ID_MAX_LEN: usize = 128;
+
+impl RequestId {
+    // Идентификатор клиента попадает в логи, заголовки ответа и журнал аудита,
+    // поэтому принимается только короткий и из безопасных символов; иначе
+    // генерируется новый.
+    pub(crate) fn accept(value: Option<&str>) -> String {
+        match value {
+            Some(id)
+                if !id.is_empty()
+                    && id.len() <= REQUEST_ID_MAX_LEN
+                    && id.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-')) =>
+            {
+                id.to_owned()
+            }
+            _ => Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+// Контекст для журнала аудита. Адрес клиента определяется так же, как для
+// RateLimitMiddleware.
+impl FromRequest for AuditContext {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let trust_proxy_headers = req
+            .app_data::<web::Data<RateLimiter>>()
+            .is_some_and(|limiter| limiter.trust_proxy_headers());
+        ready(Ok(AuditContext {
+            ip: client_ip(&req.connection_info(), trust_proxy_headers).map(|ip| ip.to_string()),
+            user_agent: req
+                .headers()
+                .get(header::USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_owned),
+            request_id: req.extensions().get::<RequestId>().map(|id| id.0.clone()),
+        }))
+    }
+}
+
+// traceparent/tracestate span-а запроса в виде заголовков ответа.
+fn trace_response_headers(span: &Span) -> Vec<(HeaderName, HeaderValue)> {
+    telemetry::trace_headers(span)
+        .into_iter()
+        .filter_map(|(name, value)| {
+            Some((HeaderName::try_from(name).ok()?, HeaderValue::try_from(value).ok()?))
+        })
+        .collect()
+}
 
 pub struct RequestIdMiddleware;
 
@@ -30,7 +112,7 @@ where
     S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type InitError = ();
     type Transform = RequestIdService<S>;
@@ -50,7 +132,7 @@ where
     S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
 
@@ -59,28 +141,60 @@ where
     }
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let request_id = req
-            .headers()
-            .get(&REQUEST_ID_HEADER)
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_owned())
-            .unwrap_or_else(|| Uuid::new_v4().to_string());
-
-        req.extensions_mut()
-            .insert(RequestId(request_id.clone()));
-
-        let fut = self.service.call(req);
+        let request_id =
+            RequestId::accept(req.headers().get(&REQUEST_ID_HEADER).and_then(|v| v.to_str().ok()));
+
+        // Span запроса: все записи ниже по стеку (middleware, обработчики, сервисы,
+        // репозитории) наследуют request_id и маршрут; user_id дописывает JwtAuthMiddleware.
+        let route = req.match_pattern().unwrap_or_else(|| req.path().to_owned());
+        let span = info_span!(
+            "http_request",
+            request_id = %request_id,
+            method = %req.method(),
+            route = %route,
+            user_id = field::Empty,
+            trace_id = field::Empty,
+        );
+        telemetry::continue_trace(&span, |name| {
+            req.headers().get(name).and_then(|v| v.to_str().ok())
+        });
+        let trace_headers = trace_response_headers(&span);
+        req.extensions_mut().insert(RequestId(request_id.clone()));
+        let fut = span.in_scope(|| self.service.call(req));
 
         Box::pin(async move {
-            let mut res = fut.await?;
-            res.response_mut()
-                .headers_mut()
-                .insert(
-                    REQUEST_ID_HEADER.clone(),
-                    HeaderValue::from_str(&request_id).unwrap(),
-                );
+            let header_value = HeaderValue::from_str(&request_id).unwrap();
+            // Ошибки BlogError (из обработчика или вложенного middleware) пересобираются
+            // в problem+json уже с идентификатором запроса.
+            let mut res = match fut.await {
+                Ok(res) => match res.response().error().and_then(|e| e.as_error::<BlogError>()) {
+                    Some(err) => {
+                        let problem = problem_response(err, Some(&request_id));
+                        res.into_response(problem).map_into_right_body()
+                    }
+                    None => res.map_into_left_body(),
+                },
+                Err(err) => {
+                    let Some(blog_err) = err.as_error::<BlogError>() else {
+                        return Err(err);
+                    };
+                    let mut problem = problem_response(blog_err, Some(&request_id));
+                    let headers = problem.headers_mut();
+                    headers.insert(REQUEST_ID_HEADER.clone(), header_value);
+                    for (name, value) in trace_headers {
+                        headers.insert(name, value);
+                    }
+                    return Err(InternalError::from_response(blog_err.to_string(), problem).into());
+                }
+            };
+            let headers = res.response_mut().headers_mut();
+            headers.insert(REQUEST_ID_HEADER.clone(), header_value);
+            for (name, value) in trace_headers {
+                headers.insert(name, value);
+            }
             Ok(res)
-        })
+        }
+        .instrument(span))
     }
 }
 
@@ -123,36 +237,37 @@ where
         let start = Instant::now();
         let method = req.method().clone();
         let path = req.path().to_owned();
-        let rid = req
-            .extensions()
-            .get::<RequestId>()
-            .map(|id| id.0.clone());
+        // Шаблон маршрута, а не путь: иначе у метрик неограниченная кардинальность.
+        let route = req
+            .match_pattern()
+            .unwrap_or_else(|| "unmatched".to_owned());
 
         let fut = self.service.call(req);
 
         Box::pin(async move {
-            let mut res = fut.await?;
+            // Ошибки вложенных middleware (например, 401 от JwtAuthMiddleware) тоже учитываются.
+            let res = fut.await;
             let duration = start.elapsed();
-            let status = res.status().as_u16();
-            if let Some(rid) = rid {
-                info!(
-                    request_id = %rid,
-                    method = %method,
-                    path = %path,
-                    status,
-                    duration_ms = duration.as_millis(),
-                    "request completed"
-                );
-            } else {
-                info!(
-                    method = %method,
-                    path = %path,
-                    status,
-                    duration_ms = duration.as_millis(),
-                    "request completed"
-                );
-            }
-
+            let status = match &res {
+                Ok(res) => res.status(),
+                Err(err) => err.as_response_error().status_code(),
+            };
+            // request_id и маршрут берутся из span RequestIdMiddleware.
+            info!(
+                method = %method,
+                path = %path,
+                status = status.as_u16(),
+                duration_ms = duration.as_millis(),
+                "request completed"
+            );
+            let labels = [method.as_str(), route.as_str(), status.as_str()];
+            metrics().http_requests.with_label_values(&labels).inc();
+            metrics()
+                .http_duration
+                .with_label_values(&labels)
+                .observe(duration.as_secs_f64());
+
+            let mut res = res?;
             if let Ok(value) = HeaderValue::from_str(&format!("app;dur={}", duration.as_millis())) {
                 res.response_mut()
                     .headers_mut()
@@ -164,17 +279,33 @@ where
     }
 }
 
-pub struct JwtAuthMiddleware {
-    keys: JwtKeys,
-}
+pub struct JwtAuthMiddleware;
 
-impl JwtAuthMiddleware {
-    pub fn new(keys: JwtKeys) -> Self {
-        Self { keys }
+impl<S, B> Transform<S, ServiceRequest> for JwtAuthMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = JwtAuthService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(JwtAuthService {
+            service: Rc::new(RefCell::new(service)),
+            required: true,
+        }))
     }
 }
 
-impl<S, B> Transform<S, ServiceRequest> for JwtAuthMiddleware
+// Для публичных маршрутов, ответ которых зависит от читателя (например, автор видит
+// свои черновики): без заголовка Authorization запрос проходит анонимно, но
+// переданный недействительный токен по-прежнему даёт 401.
+pub struct OptionalJwtAuthMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for OptionalJwtAuthMiddleware
 where
     S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
@@ -188,14 +319,14 @@ where
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(JwtAuthService {
             service: Rc::new(RefCell::new(service)),
-            keys: self.keys.clone(),
+            required: false,
         }))
     }
 }
 
 pub struct JwtAuthService<S> {
     service: Rc<RefCell<S>>,
-    keys: JwtKeys,
+    required: bool,
 }
 
 impl<S, B> Service<ServiceRequest> for JwtAuthService<S>
@@ -212,10 +343,10 @@ where
     }
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let keys = self.keys.clone();
         let service = Rc::clone(&self.service);
+        let required = self.required;
         let auth_service = req
-            .app_data::<web::Data<AuthService<InMemoryUserRepository>>>()
+            .app_data::<web::Data<AuthService>>()
             .cloned();
 
         let auth_header = req
@@ -225,17 +356,104 @@ where
             .map(|value| value.to_string());
 
         Box::pin(async move {
+            if auth_header.is_none() && !required {
+                let fut = {
+                    let svc = service.borrow_mut();
+                    svc.call(req)
+                };
+                return fut.await;
+            }
+
             let auth_service = auth_service
-                .ok_or_else(|| actix_web::error::ErrorInternalServerError("AuthService missing"))?;
+                .ok_or_else(|| BlogError::Internal("AuthService missing".into()))?;
+
+            let token = auth_header
+                .as_deref()
+                .and_then(|header| header.strip_prefix("Bearer "))
+                .ok_or(BlogError::MissingToken)?;
+
+            let (user, claims) = auth_service.authenticate(token).await?;
+            Span::current().record("user_id", user.id);
+            req.extensions_mut().insert(AuthenticatedUser {
+                user_id: user.id,
+                email_verified: user.is_email_verified(),
+                username: user.username,
+                claims,
+            });
+
+            let fut = {
+                let svc = service.borrow_mut();
+                svc.call(req)
+            };
+
+            let res = fut.await?;
+            Ok(res)
+        })
+    }
+}
+
+// Ограничение частоты запросов по IP клиента для входа и регистрации.
+// Лимит на аккаунт проверяет сам AuthService::login.
+pub struct RateLimitMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimitMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitService {
+            service: Rc::new(RefCell::new(service)),
+        }))
+    }
+}
+
+pub struct RateLimitService<S> {
+    service: Rc<RefCell<S>>,
+}
+
+// Адрес из X-Forwarded-For/Forwarded учитывается только при trust_proxy_headers,
+// иначе клиент мог бы подставлять новый IP в каждом запросе.
+fn client_ip(info: &ConnectionInfo, trust_proxy_headers: bool) -> Option<IpAddr> {
+    let addr = if trust_proxy_headers {
+        info.realip_remote_addr()?.to_owned()
+    } else {
+        info.peer_addr()?.to_owned()
+    };
+    addr.parse::<IpAddr>()
+        .or_else(|_| addr.parse::<SocketAddr>().map(|socket| socket.ip()))
+        .ok()
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
 
-            let header = auth_header
-                .ok_or_else(|| actix_web::error::ErrorUnauthorized("missing authorization header"))?;
-            let token = header
-                .strip_prefix("Bearer ")
-                .ok_or_else(|| actix_web::error::ErrorUnauthorized("invalid authorization header"))?;
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.borrow_mut().poll_ready(cx)
+    }
 
-            let user = extract_user_from_token(token, &keys, auth_service.get_ref()).await?;
-            req.extensions_mut().insert(user);
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let limiter = req.app_data::<web::Data<RateLimiter>>().cloned();
+
+        Box::pin(async move {
+            let limiter = limiter.ok_or_else(|| BlogError::Internal("RateLimiter missing".into()))?;
+            let ip = client_ip(&req.connection_info(), limiter.trust_proxy_headers());
+            if let Some(ip) = ip {
+                limiter.check_ip(ip).await?;
+            }
 
             let fut = {
                 let svc = service.borrow_mut();
@@ -248,3 +466,117 @@ where
     }
 }
 
+// Отклоняет запрос с 403, если у роли из токена нет `Permission`. Ставится на маршрут
+// внутри области с JwtAuthMiddleware, которая кладёт AuthenticatedUser в запрос.
+// Сервисы проверяют права сами; middleware отсекает заведомо запрещённые вызовы
+// до разбора тела и обращений к БД.
+pub struct RequirePermission(pub(crate) Permission);
+
+impl<S, B> Transform<S, ServiceRequest> for RequirePermission
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RequirePermissionService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequirePermissionService {
+            service,
+            permission: self.0,
+        }))
+    }
+}
+
+pub struct RequirePermissionService<S> {
+    service: S,
+    permission: Permission,
+}
+
+impl<S, B> Service<ServiceRequest> for RequirePermissionService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let allowed = req
+            .extensions()
+            .get::<AuthenticatedUser>()
+            .map(|user| user.actor().can(self.permission));
+        match allowed {
+            Some(true) => Box::pin(self.service.call(req)),
+            Some(false) => Box::pin(ready(Err(BlogError::Forbidden.into()))),
+            None => Box::pin(ready(Err(BlogError::MissingToken.into()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test as actix_test, App, HttpResponse};
+
+    use crate::application::audit;
+    use crate::data::audit_repository::InMemoryAuditRepository;
+    use crate::domain::audit::{AuditAction, AuditEvent};
+
+    async fn login_failed(ctx: AuditContext, sink: web::Data<InMemoryAuditRepository>) -> HttpResponse {
+        audit::record(sink.get_ref(), AuditEvent::new(AuditAction::LoginFailed, &ctx)).await;
+        HttpResponse::Unauthorized().finish()
+    }
+
+    async fn audited(request_id: &str) -> (String, Vec<AuditEvent>) {
+        let sink = web::Data::new(InMemoryAuditRepository::new());
+        let app = actix_test::init_service(
+            App::new()
+                .app_data(sink.clone())
+                .wrap(RequestIdMiddleware)
+                .route("/login", web::post().to(login_failed)),
+        )
+        .await;
+        let req = actix_test::TestRequest::post()
+            .uri("/login")
+            .insert_header((REQUEST_ID_HEADER.clone(), request_id))
+            .to_request();
+        let res = actix_test::call_service(&app, req).await;
+        let header = res.headers().get(&REQUEST_ID_HEADER).unwrap().to_str().unwrap().to_owned();
+        (header, sink.all())
+    }
+
+    #[test]
+    fn only_short_safe_request_ids_are_accepted() {
+        assert_eq!(RequestId::accept(Some("req-1.a_B")), "req-1.a_B");
+        let max = "a".repeat(REQUEST_ID_MAX_LEN);
+        assert_eq!(RequestId::accept(Some(&max)), max);
+        for bad in [None, Some(""), Some("a b"), Some("id\"x"), Some(&*"a".repeat(REQUEST_ID_MAX_LEN + 1))] {
+            let id = RequestId::accept(bad);
+            assert!(Uuid::parse_str(&id).is_ok(), "{bad:?} -> {id}");
+        }
+    }
+
+    #[actix_web::test]
+    async fn oversized_request_id_is_replaced_and_still_audited() {
+        let (id, events) = audited(&"a".repeat(1000)).await;
+        assert!(Uuid::parse_str(&id).is_ok(), "{id}");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, AuditAction::LoginFailed);
+        assert_eq!(events[0].request_id.as_deref(), Some(id.as_str()));
+
+        let (id, events) = audited("req-42").await;
+        assert_eq!(id, "req-42");
+        assert_eq!(events[0].request_id.as_deref(), Some("req-42"));
+    }
+}
@@ -1,3 +1,9 @@
-mod middleware;
-mod http_handlers;
-mod grpc_service;
\ No newline at end of file
+pub(crate) mod middleware;
+pub(crate) mod http_handlers;
+pub(crate) mod http_error;
+pub(crate) mod grpc_service;
+pub(crate) mod grpc_metrics;
+pub(crate) mod grpc_tracing;
+pub(crate) mod grpc_rate_limit;
+pub(crate) mod grpc_auth;
+pub(crate) mod grpc_admin;
@@ -0,0 +1,312 @@
+use std::net::SocketAddr;
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_cors::Cors;
+use actix_web::{http::header, web, App, HttpServer};
+use sqlx::PgPool;
+use tokio::sync::oneshot;
+use tonic::server::NamedService;
+use tonic::transport::server::TcpIncoming;
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+use tracing::{error, info, warn};
+
+use crate::application::admin_service::AdminService;
+use crate::application::auth_service::AuthService;
+use crate::application::blog_service::BlogService;
+use crate::application::profile_service::ProfileService;
+use crate::application::rate_limiter::RateLimiter;
+use crate::infrastructure::config::{CorsConfig, SchedulerConfig};
+use crate::infrastructure::health::{HealthCheck, ListenerState};
+use crate::presentation::grpc_admin::AdminGrpcService;
+use crate::presentation::grpc_auth::GrpcAuthLayer;
+use crate::presentation::grpc_metrics::GrpcMetricsLayer;
+use crate::presentation::grpc_rate_limit::GrpcRateLimitLayer;
+use crate::presentation::grpc_service::proto::admin_service_server::AdminServiceServer;
+use crate::presentation::grpc_service::proto::blog_service_server::BlogServiceServer;
+use crate::presentation::grpc_service::BlogGrpcService;
+use crate::presentation::grpc_tracing::GrpcTraceLayer;
+use crate::presentation::http_handlers;
+use crate::presentation::middleware::{RequestIdMiddleware, TimingMiddleware};
+
+// Сколько секунд actix ждёт завершения запросов в обработке при остановке.
+const HTTP_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+// Как часто состояние зависимостей переносится в grpc.health.v1.Health.
+const GRPC_HEALTH_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct ServerSettings {
+    pub http_addr: SocketAddr,
+    pub grpc_addr: SocketAddr,
+    pub cors: CorsConfig,
+    pub scheduler: SchedulerConfig,
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    pub auth: Arc<AuthService>,
+    pub blog: Arc<BlogService>,
+    pub admin: Arc<AdminService>,
+    pub profiles: Arc<ProfileService>,
+    pub limiter: Arc<RateLimiter>,
+}
+
+fn cors(cfg: &CorsConfig) -> Cors {
+    let cors = if cfg.allows_any() {
+        Cors::default().allow_any_origin()
+    } else {
+        cfg.allowed_origins
+            .iter()
+            .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
+    };
+    cors.allowed_methods(["GET", "POST", "PUT", "DELETE", "OPTIONS"])
+        .allow_any_header()
+        .expose_headers([
+            header::HeaderName::from_static("x-request-id"),
+            header::HeaderName::from_static("traceparent"),
+            header::HeaderName::from_static("tracestate"),
+        ])
+        .max_age(3600)
+}
+
+// Запускает HTTP и gRPC серверы и ждёт SIGINT/SIGTERM. После сигнала оба сервера
+// перестают принимать соединения и дорабатывают начатые запросы, и только затем
+// закрывается пул соединений с БД.
+pub async fn run(settings: ServerSettings, state: AppState, pool: PgPool) -> anyhow::Result<()> {
+    let http_state = state.clone();
+    let scheduled_blog = Arc::clone(&state.blog);
+    let cors_cfg = settings.cors.clone();
+    if cors_cfg.allows_any() {
+        warn!("CORS allows any origin (`*`); use it for local development only");
+    }
+    let http_pool = pool.clone();
+    let health = Arc::new(HealthCheck::new(pool.clone()));
+    let http_health = Arc::clone(&health);
+    let http_server = HttpServer::new(move || {
+        App::new()
+            .wrap(TimingMiddleware)
+            .wrap(RequestIdMiddleware)
+            .wrap(cors(&cors_cfg))
+            .app_data(web::Data::from(Arc::clone(&http_state.auth)))
+            .app_data(web::Data::from(Arc::clone(&http_state.blog)))
+            .app_data(web::Data::from(Arc::clone(&http_state.admin)))
+            .app_data(web::Data::from(Arc::clone(&http_state.profiles)))
+            .app_data(web::Data::from(Arc::clone(&http_state.limiter)))
+            .app_data(web::Data::new(http_pool.clone()))
+            .app_data(web::Data::from(Arc::clone(&http_health)))
+            .configure(http_handlers::configure)
+    })
+    .disable_signals()
+    .shutdown_timeout(HTTP_SHUTDOWN_TIMEOUT_SECS)
+    .bind(settings.http_addr)?
+    .run();
+    let http_handle = http_server.handle();
+    info!(addr = %settings.http_addr, "HTTP server listening");
+
+    // Сокет занимается заранее: ошибка bind всплывает сразу, а readiness отражает
+    // реальное состояние листенера.
+    let grpc_incoming = TcpIncoming::bind(settings.grpc_addr)?;
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    let (grpc_stop_tx, grpc_stop_rx) = oneshot::channel::<()>();
+    let grpc_server = tonic::transport::Server::builder()
+        .layer(GrpcTraceLayer)
+        .layer(GrpcMetricsLayer)
+        .layer(GrpcRateLimitLayer::new(state.limiter))
+        .layer(GrpcAuthLayer::new(Arc::clone(&state.auth)))
+        .add_service(health_service)
+        .add_service(BlogGrpcService::new(state.auth, state.blog, state.profiles).into_server())
+        .add_service(AdminGrpcService::new(state.admin).into_server())
+        .serve_with_incoming_shutdown(grpc_incoming, async {
+            let _ = grpc_stop_rx.await;
+        });
+    health.set_grpc(ListenerState::Serving);
+    info!(addr = %settings.grpc_addr, "gRPC server listening");
+    let health_task = tokio::spawn(report_grpc_health(health_reporter.clone(), Arc::clone(&health)));
+    let publish_task = tokio::spawn(publish_scheduled_posts(
+        scheduled_blog,
+        settings.scheduler.publish_interval,
+    ));
+
+    let mut http = pin!(http_server);
+    let mut grpc = pin!(grpc_server);
+    let mut http_result = None;
+    let mut grpc_result = None;
+
+    tokio::select! {
+        _ = shutdown_signal() => info!("shutdown signal received, draining in-flight requests"),
+        res = &mut http => {
+            error!("HTTP server stopped unexpectedly");
+            http_result = Some(res);
+        }
+        res = &mut grpc => {
+            error!("gRPC server stopped unexpectedly");
+            grpc_result = Some(res);
+        }
+    }
+
+    health_task.abort();
+    publish_task.abort();
+    let http_drain = async {
+        match http_result {
+            Some(res) => res,
+            None => tokio::join!(&mut http, http_handle.stop(true)).0,
+        }
+    };
+    let grpc_drain = async {
+        match grpc_result {
+            Some(res) => res,
+            None => grpc.await,
+        }
+    };
+    let (http_result, grpc_result) = drain(
+        &health,
+        &health_reporter,
+        grpc_stop_tx,
+        http_drain,
+        grpc_drain,
+        pool.close(),
+    )
+    .await;
+    info!("servers stopped, database pool closed");
+
+    http_result?;
+    grpc_result?;
+    Ok(())
+}
+
+// Сначала readiness и grpc.health.v1 переходят в NOT_SERVING, чтобы балансировщик
+// перестал слать новые запросы, затем серверы дорабатывают начатые, и только
+// после этого закрывается пул соединений с БД.
+async fn drain<H, G>(
+    health: &HealthCheck,
+    reporter: &HealthReporter,
+    grpc_stop: oneshot::Sender<()>,
+    http: impl Future<Output = H>,
+    grpc: impl Future<Output = G>,
+    close_pool: impl Future<Output = ()>,
+) -> (H, G) {
+    health.set_grpc(ListenerState::Stopping);
+    set_grpc_health(reporter, ServingStatus::NotServing).await;
+    let _ = grpc_stop.send(());
+    let http = http.await;
+    let grpc = grpc.await;
+    close_pool.await;
+    (http, grpc)
+}
+
+async fn set_grpc_health(reporter: &HealthReporter, status: ServingStatus) {
+    reporter.set_service_status("", status).await;
+    reporter
+        .set_service_status(<BlogServiceServer<BlogGrpcService> as NamedService>::NAME, status)
+        .await;
+    reporter
+        .set_service_status(<AdminServiceServer<AdminGrpcService> as NamedService>::NAME, status)
+        .await;
+}
+
+// Периодически переносит результат readiness в grpc.health.v1.Health: пустое имя
+// сервиса означает состояние сервера целиком.
+async fn report_grpc_health(reporter: HealthReporter, health: Arc<HealthCheck>) {
+    let mut interval = tokio::time::interval(GRPC_HEALTH_INTERVAL);
+    loop {
+        interval.tick().await;
+        let status = if health.readiness().await.ready {
+            ServingStatus::Serving
+        } else {
+            ServingStatus::NotServing
+        };
+        set_grpc_health(&reporter, status).await;
+    }
+}
+
+// Фоновая публикация запланированных постов. Ошибка одного прохода (например,
+// недоступна БД) не останавливает задачу — пост выйдет на следующем тике.
+async fn publish_scheduled_posts(blog: Arc<BlogService>, period: Duration) {
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+        if let Err(err) = blog.publish_due_posts().await {
+            warn!(error = %err, "failed to publish scheduled posts");
+        }
+    }
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            error!(error = %err, "failed to listen for SIGINT");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(err) => {
+                error!(error = %err, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use tonic::Request;
+    use tonic_health::pb::health_check_response::ServingStatus as PbServingStatus;
+    use tonic_health::pb::health_server::Health;
+    use tonic_health::pb::HealthCheckRequest;
+    use tonic_health::server::HealthService;
+
+    use super::*;
+    use crate::infrastructure::health::tests::health;
+
+    async fn grpc_health(service: &HealthService) -> PbServingStatus {
+        let request = Request::new(HealthCheckRequest { service: String::new() });
+        service.check(request).await.unwrap().into_inner().status()
+    }
+
+    #[tokio::test]
+    async fn drain_stops_traffic_before_servers_and_closes_pool_last() {
+        let (health, _) = health();
+        health.set_grpc(ListenerState::Serving);
+        let (reporter, _) = tonic_health::server::health_reporter();
+        set_grpc_health(&reporter, ServingStatus::Serving).await;
+        let service = HealthService::from_health_reporter(reporter.clone());
+        let (grpc_stop_tx, mut grpc_stop_rx) = oneshot::channel();
+        let events = Mutex::new(Vec::new());
+
+        let http = async {
+            // К остановке HTTP балансировщик уже видит NOT_SERVING, а gRPC получил сигнал.
+            assert!(!health.readiness().await.ready);
+            assert_eq!(grpc_health(&service).await, PbServingStatus::NotServing);
+            assert!(grpc_stop_rx.try_recv().is_ok());
+            events.lock().unwrap().push("http");
+            "http done"
+        };
+        let grpc = async {
+            events.lock().unwrap().push("grpc");
+            "grpc done"
+        };
+        let close_pool = async {
+            events.lock().unwrap().push("pool");
+        };
+
+        let results = drain(&health, &reporter, grpc_stop_tx, http, grpc, close_pool).await;
+        assert_eq!(results, ("http done", "grpc done"));
+        assert_eq!(*events.lock().unwrap(), ["http", "grpc", "pool"]);
+    }
+}
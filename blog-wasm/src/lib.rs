@@ -1,3 +1,128 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo_net::http::Request;
+use js_sys::Promise;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+
+const PAGE_SIZE: u32 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Post {
+    pub id: i64,
+    pub title: String,
+    pub content: String,
+    pub author_id: i64,
+    pub created_at: String,
+    pub updated_at: String,
+    // Подпись автора приходит вместе с постом, отдельный запрос профиля не нужен.
+    #[serde(default)]
+    pub author: Option<Author>,
+    // Лента показывает только опубликованные посты; статус нужен для отображения.
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub published_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Author {
+    pub id: i64,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CursorPage {
+    posts: Vec<Post>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Default)]
+struct FeedState {
+    next_cursor: Option<String>,
+    exhausted: bool,
+}
+
+#[wasm_bindgen]
+pub struct BlogApp {
+    server_url: String,
+    feed: Rc<RefCell<FeedState>>,
+}
+
+#[wasm_bindgen]
+impl BlogApp {
+    #[wasm_bindgen(constructor)]
+    pub fn new(server_url: String) -> BlogApp {
+        BlogApp {
+            server_url: server_url.trim_end_matches('/').to_string(),
+            feed: Rc::new(RefCell::new(FeedState::default())),
+        }
+    }
+
+    // Первая страница ленты; сбрасывает состояние бесконечной прокрутки.
+    pub fn load_posts(&self) -> Promise {
+        *self.feed.borrow_mut() = FeedState::default();
+        self.load_more_posts()
+    }
+
+    // Следующая страница ленты по курсору; пустой массив, когда посты закончились.
+    pub fn load_more_posts(&self) -> Promise {
+        let feed = Rc::clone(&self.feed);
+        let server_url = self.server_url.clone();
+
+        future_to_promise(async move {
+            let cursor = {
+                let state = feed.borrow();
+                if state.exhausted {
+                    return to_js(&Vec::<Post>::new());
+                }
+                state.next_cursor.clone()
+            };
+
+            let limit = PAGE_SIZE.to_string();
+            let mut query = vec![("pagination", "cursor"), ("limit", limit.as_str())];
+            if let Some(cursor) = cursor.as_deref() {
+                query.push(("cursor", cursor));
+            }
+
+            let response = Request::get(&format!("{server_url}/api/posts"))
+                .query(query)
+                .send()
+                .await
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+            if !response.ok() {
+                return Err(JsValue::from_str(&format!(
+                    "failed to load posts: {}",
+                    response.status()
+                )));
+            }
+            let page: CursorPage = response
+                .json()
+                .await
+                .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+            {
+                let mut state = feed.borrow_mut();
+                state.exhausted = page.next_cursor.is_none();
+                state.next_cursor = page.next_cursor;
+            }
+            to_js(&page.posts)
+        })
+    }
+
+    pub fn has_more_posts(&self) -> bool {
+        !self.feed.borrow().exhausted
+    }
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }